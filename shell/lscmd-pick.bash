@@ -0,0 +1,22 @@
+# lscmd picker for bash: press Ctrl-G to insert a command name at the cursor.
+#
+# Source this file from ~/.bashrc:
+#
+#   source /path/to/lscmd/shell/lscmd-pick.bash
+#
+# Set LSCMD_PICK_OPTS before sourcing to pass options to `lscmd pick`, e.g.
+# `--body` to insert the command's body instead of its name, and bind the
+# function to another key with `bind -x '"<key>": __lscmd_pick'`.
+
+__lscmd_pick() {
+  local picked
+  # The picker draws on /dev/tty and prints only the pick; it exits with
+  # 130 when cancelled, leaving the command line untouched.
+  # shellcheck disable=SC2086
+  picked="$(lscmd pick $LSCMD_PICK_OPTS < /dev/tty)" || return
+  [[ -n $picked ]] || return
+  READLINE_LINE="${READLINE_LINE:0:READLINE_POINT}${picked}${READLINE_LINE:READLINE_POINT}"
+  READLINE_POINT=$((READLINE_POINT + ${#picked}))
+}
+
+bind -x '"\C-g": __lscmd_pick'
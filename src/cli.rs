@@ -1,3 +1,6 @@
+use crate::config::Discovery;
+use crate::database::CommandType;
+use crate::output::OutputFormat;
 use clap::{Parser, Subcommand};
 use std::path::PathBuf;
 
@@ -5,6 +8,7 @@
 #[command(name = "lscmd")]
 #[command(about = "A high-performance CLI tool to manage shell aliases and functions")]
 #[command(version)]
+#[command(disable_help_subcommand = true)]
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
@@ -21,43 +25,142 @@ pub enum Commands {
     /// Incremental update - only reparse modified files
     Update,
     
-    /// Change alias directory path (WARNING: triggers full rescan)
-    Path { 
-        /// New alias directory path
-        new_path: PathBuf 
+    /// Watch the alias directory and reindex changed files as they are saved (until Ctrl-C)
+    Watch,
+    
+    /// Manage the alias roots to index; lists them without a subcommand
+    Path {
+        #[command(subcommand)]
+        action: Option<PathAction>,
+    },
+    
+    /// Follow `source`/`.` statements from the shell rc files; prints the load order and the alias files nothing sources
+    Sources {
+        /// Start from this rc file instead of the configured ones (repeatable)
+        #[arg(long = "rc", value_name = "FILE")]
+        rc_files: Vec<PathBuf>,
+        
+        /// Save how scan, update and watch find files (together with any --rc files)
+        #[arg(long, value_enum)]
+        discovery: Option<Discovery>,
     },
     
     /// List all commands (opens TUI interface with case-insensitive filtering)
     List {
-        /// Filter by command type ('alias' or 'function')
-        #[arg(long)]
-        type_filter: Option<String>,
+        /// Only include commands of this type
+        #[arg(long, value_enum)]
+        type_filter: Option<CommandType>,
+        
+        /// Print in this format instead of opening the TUI
+        #[arg(long, value_enum)]
+        format: Option<OutputFormat>,
+        
+        /// Print names in N columns per file instead of opening the TUI [default: 5]
+        #[arg(short, long, value_name = "N", num_args = 0..=1, default_missing_value = "5", conflicts_with = "format")]
+        columns: Option<usize>,
     },
     
     /// Search commands by name/pattern (opens TUI interface with fixed case-insensitive matching)
     Search {
-        /// Search query string
+        /// Search query, e.g. `name:git* type:function code:"kubectl get" -deprecated`
         query: String,
         
         /// Enable regex mode
         #[arg(long)]
         regex: bool,
         
-        /// Filter by command type ('alias' or 'function')
+        /// Enable fzf-style fuzzy matching, e.g. `gco` for `git_checkout`
+        #[arg(long, conflicts_with = "regex")]
+        fuzzy: bool,
+        
+        /// Only include commands of this type
+        #[arg(long, value_enum)]
+        type_filter: Option<CommandType>,
+        
+        /// Print in this format instead of opening the TUI
+        #[arg(long, value_enum)]
+        format: Option<OutputFormat>,
+    },
+    
+    /// Pick a command in the TUI and print only its name, for shell key bindings (exits with 130 if cancelled)
+    Pick {
+        /// Start out searching for this query
+        query: Option<String>,
+        
+        /// Print the command's body instead of its name
+        #[arg(long)]
+        body: bool,
+        
+        /// Enable regex mode
         #[arg(long)]
-        type_filter: Option<String>,
+        regex: bool,
+        
+        /// Enable fzf-style fuzzy matching, e.g. `gco` for `git_checkout`
+        #[arg(long, conflicts_with = "regex")]
+        fuzzy: bool,
+        
+        /// Only include commands of this type
+        #[arg(long, value_enum)]
+        type_filter: Option<CommandType>,
     },
     
-    /// Show specific command details
+    /// Show specific command details, including every shadowed definition
     Show { 
         /// Command name to display
-        name: String 
+        name: String,
+        
+        /// Print in this format
+        #[arg(long, value_enum, default_value_t)]
+        format: OutputFormat,
     },
     
+    /// Open the active definition of a command in $VISUAL/$EDITOR, then reindex its file
+    Edit {
+        /// Command name to edit
+        name: String
+    },
+    
+    /// List names defined more than once and which definition wins
+    Conflicts,
+    
     /// Initialize lscmd with interactive setup
     Init {
         /// Optional alias directory path
         #[arg(long)]
         path: Option<PathBuf>,
     },
+}
+
+#[derive(Subcommand)]
+pub enum PathAction {
+    /// List the configured alias roots
+    List,
+    
+    /// Add an alias root and index only its files
+    Add {
+        /// Directory to index
+        path: PathBuf,
+        
+        /// Short name to show instead of the path
+        #[arg(long)]
+        label: Option<String>,
+        
+        /// Only index files matching this glob, relative to the root (repeatable) [default: *.sh]
+        #[arg(long, value_name = "GLOB")]
+        include: Vec<String>,
+        
+        /// Skip files and directories matching this glob, relative to the root (repeatable)
+        #[arg(long, value_name = "GLOB")]
+        exclude: Vec<String>,
+        
+        /// Descend at most N levels of subdirectories (0: the root only)
+        #[arg(long, value_name = "N")]
+        max_depth: Option<usize>,
+    },
+    
+    /// Remove an alias root, by label or path, and drop only its commands
+    Remove {
+        /// Label or path of the root
+        root: String,
+    },
 }
\ No newline at end of file
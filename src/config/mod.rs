@@ -1,8 +1,9 @@
 use crate::error::{LscmdError, Result};
+use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
 use std::env;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Represents the XDG Base Directory paths.
 /// This struct provides a cross-platform way to locate user-specific
@@ -84,10 +85,84 @@ pub fn config_file_path(&self) -> Result<PathBuf> {
     }
 }
 
+/// A directory lscmd indexes shell files from.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AliasRoot {
+    /// The directory to scan.
+    pub path: PathBuf,
+    /// A short name to show instead of the path, e.g. `team`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Globs, relative to `path`, that a file must match to be indexed.
+    #[serde(default = "default_include")]
+    pub include: Vec<String>,
+    /// Globs, relative to `path`, of files and directories to skip.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+    /// How many levels of subdirectories to descend into; `0` only scans
+    /// `path` itself. Unlimited if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_depth: Option<usize>,
+}
+
+fn default_include() -> Vec<String> {
+    vec!["*.sh".to_string()]
+}
+
+impl AliasRoot {
+    /// Creates a root that indexes every `*.sh` file below `path`.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            label: None,
+            include: default_include(),
+            exclude: Vec::new(),
+            max_depth: None,
+        }
+    }
+
+    /// The label, or the path if the root has none.
+    pub fn name(&self) -> String {
+        self.label.clone().unwrap_or_else(|| self.path.display().to_string())
+    }
+
+    /// Whether `key` refers to this root, by label or by path. A relative
+    /// path is taken from the current directory, as `lscmd path add` does.
+    pub fn is_named(&self, key: &str) -> bool {
+        self.label.as_deref() == Some(key)
+            || self.path == Path::new(key)
+            || std::path::absolute(key).is_ok_and(|path| self.path == path)
+    }
+}
+
+/// How `scan`, `update` and `watch` find the files to index.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Discovery {
+    /// Every matching file below the alias roots.
+    #[default]
+    Roots,
+    /// The rc files and every file their `source` statements load.
+    Sources,
+}
+
 /// Represents the application's configuration.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Config {
-    pub alias_path: PathBuf,
+    /// The directories to index, in sourcing order: where a file lies below
+    /// several roots, the first one claims it.
+    #[serde(default)]
+    pub roots: Vec<AliasRoot>,
+    /// The single alias directory of configs written before `roots`
+    /// existed; `load` turns it into the first root.
+    #[serde(default, rename = "alias_path", skip_serializing)]
+    legacy_alias_path: Option<PathBuf>,
+    /// The shell startup files to follow `source` statements from; see
+    /// `sources::default_rc_files` for the defaults when empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rc_files: Vec<PathBuf>,
+    #[serde(default)]
+    pub discovery: Discovery,
     pub version: String,
 }
 
@@ -101,14 +176,19 @@ pub fn load() -> Result<Self> {
 
         if config_path.exists() {
             let content = fs::read_to_string(&config_path)?;
-            let config: Config = serde_json::from_str(&content)?;
-            // TODO: Implement version migration logic here
+            let mut config: Config = serde_json::from_str(&content)?;
+            if let Some(alias_path) = config.legacy_alias_path.take() {
+                config.roots.insert(0, AliasRoot::new(alias_path));
+            }
             Ok(config)
         } else {
             // Return a default configuration in memory
             let home_dir = dirs::home_dir().ok_or_else(|| LscmdError::XdgError("Home directory not found".to_string()))?;
             Ok(Config {
-                alias_path: home_dir.join(".aliases"), // Default path
+                roots: vec![AliasRoot::new(home_dir.join(".aliases"))], // Default path
+                legacy_alias_path: None,
+                rc_files: Vec::new(),
+                discovery: Discovery::default(),
                 version: env!("CARGO_PKG_VERSION").to_string(),
             })
         }
@@ -122,4 +202,40 @@ pub fn save(&self) -> Result<()> {
         fs::write(config_path, content)?;
         Ok(())
     }
+
+    /// Finds the root called `key`, by label or by path.
+    pub fn find_root(&self, key: &str) -> Option<&AliasRoot> {
+        self.roots.iter().find(|root| root.is_named(key))
+    }
+
+    /// Appends `root` after the existing roots.
+    ///
+    /// # Errors
+    ///
+    /// Returns `LscmdError::Validation` if a root with the same path or
+    /// label is already configured.
+    pub fn add_root(&mut self, root: AliasRoot) -> Result<()> {
+        let taken = self.roots.iter().any(|r| {
+            r.path == root.path || (root.label.is_some() && r.label == root.label)
+        });
+        if taken {
+            return Err(LscmdError::Validation(format!("Alias root '{}' is already configured", root.name())));
+        }
+        self.roots.push(root);
+        Ok(())
+    }
+
+    /// Removes and returns the root called `key`, by label or by path.
+    ///
+    /// # Errors
+    ///
+    /// Returns `LscmdError::Validation` if no such root is configured.
+    pub fn remove_root(&mut self, key: &str) -> Result<AliasRoot> {
+        let index = self
+            .roots
+            .iter()
+            .position(|root| root.is_named(key))
+            .ok_or_else(|| LscmdError::Validation(format!("No alias root named '{}'", key)))?;
+        Ok(self.roots.remove(index))
+    }
 }
@@ -2,15 +2,23 @@
 pub mod schema;
 
 use crate::error::{LscmdError, Result};
+use clap::ValueEnum;
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
 use rusqlite::Connection;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::path::Path;
+use std::str::FromStr;
 
 /// Initializes the database connection and ensures the schema is up to date.
 ///
 /// This function connects to the SQLite database at the given path, creating the
-/// file if it doesn't exist. It then executes the schema script to create
-/// tables and indexes if they are not already present.
+/// file if it doesn't exist. It then creates the schema for a new database, or
+/// migrates an existing one to `schema::SCHEMA_VERSION` (see `schema::migrate`).
+///
+/// The connection uses WAL journaling so that readers keep seeing the last
+/// committed state while a scan rewrites the tables, and enforces foreign
+/// keys so that commands cannot outlive their file.
 ///
 /// # Arguments
 ///
@@ -18,13 +26,19 @@
 ///
 /// # Errors
 ///
-/// Returns `LscmdError::Database` if the connection fails or the schema
-/// cannot be executed.
+/// Returns `LscmdError::Database` if the connection fails, the database was
+/// written by a newer lscmd, or the schema cannot be created or migrated.
 pub fn init_db(db_path: &Path) -> Result<Connection> {
-    let conn = Connection::open(db_path)
+    let mut conn = Connection::open(db_path)
+        .map_err(|e| LscmdError::Database(e.to_string()))?;
+
+    conn.execute_batch("PRAGMA journal_mode = WAL; PRAGMA busy_timeout = 5000;")
         .map_err(|e| LscmdError::Database(e.to_string()))?;
 
-    conn.execute_batch(schema::get_schema())
+    // Migrations rebuild tables, so foreign keys are only enforced afterwards.
+    schema::migrate(&mut conn, Some(db_path))?;
+
+    conn.execute_batch("PRAGMA foreign_keys = ON;")
         .map_err(|e| LscmdError::Database(e.to_string()))?;
 
     Ok(conn)
@@ -34,19 +48,127 @@ pub fn init_db(db_path: &Path) -> Result<Connection> {
 /// This struct is mapped directly to the `commands` table in the database.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Command {
-    /// The name of the alias or function (PRIMARY KEY).
+    /// The name of the alias or function.
+    /// Together with `path` and `start_line` it forms the PRIMARY KEY, so the
+    /// same name may be defined in several places.
     pub name: String,
-    /// The type of the command, either 'alias' or 'function'.
-    pub cmd_type: String,
+    /// The kind of definition, stored as its lowercase name.
+    pub cmd_type: CommandType,
     /// The absolute path to the file where the command is defined.
     pub path: String,
+    /// The 1-based line on which the definition starts.
+    #[serde(default)]
+    pub start_line: usize,
+    /// The 1-based line on which the definition ends (inclusive).
+    #[serde(default)]
+    pub end_line: usize,
+    /// The byte offset of the start of `start_line` within the file.
+    #[serde(default)]
+    pub byte_offset: u64,
     /// The full code/body of the command.
     pub code: String,
+    /// The comment block above the definition and any inline comment after it.
+    #[serde(default)]
+    pub description: Option<String>,
     /// The last modification time of the source file (Unix timestamp).
+    /// Read from the `files` table; on insert it seeds a new file row.
     pub file_mtime: i64,
     /// The timestamp when the command was first added to the database.
     /// This is handled by the database DEFAULT trigger.
     pub created_at: Option<i64>,
+    /// The alias root the source file was found under, as its absolute path.
+    /// Read from the `files` table.
+    #[serde(default)]
+    pub root: Option<String>,
+}
+
+/// The kinds of definitions lscmd indexes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum CommandType {
+    /// `alias name=value`
+    Alias,
+    /// `name() { ... }` or `function name { ... }`
+    Function,
+    /// An exported environment variable, `export NAME=value`
+    Variable,
+    /// A readline or zle key binding
+    Keybinding,
+}
+
+impl CommandType {
+    /// Every type, in the order they are listed to users.
+    pub const ALL: [CommandType; 4] = [
+        CommandType::Alias,
+        CommandType::Function,
+        CommandType::Variable,
+        CommandType::Keybinding,
+    ];
+
+    /// The lowercase name used in the database, in queries and on the command line.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CommandType::Alias => "alias",
+            CommandType::Function => "function",
+            CommandType::Variable => "variable",
+            CommandType::Keybinding => "keybinding",
+        }
+    }
+
+    /// Lists every type name for error messages, as `alias, function, ...`.
+    pub fn names() -> String {
+        Self::ALL.map(Self::as_str).join(", ")
+    }
+}
+
+impl fmt::Display for CommandType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.as_str())
+    }
+}
+
+impl FromStr for CommandType {
+    type Err = LscmdError;
+
+    /// Parses a type name, ignoring case.
+    fn from_str(s: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|t| t.as_str().eq_ignore_ascii_case(s))
+            .ok_or_else(|| {
+                LscmdError::Validation(format!("Invalid command type: '{}', expected one of: {}", s, Self::names()))
+            })
+    }
+}
+
+impl ToSql for CommandType {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.as_str()))
+    }
+}
+
+impl FromSql for CommandType {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        value
+            .as_str()?
+            .parse()
+            .map_err(|e: LscmdError| FromSqlError::Other(e.to_string().into()))
+    }
+}
+
+/// Returns the index of the definition the shell actually uses.
+///
+/// `definitions` must all share one name and be in sourcing order, as
+/// returned by `CommandRepository::get_definitions`. Aliases are expanded
+/// before function lookup, so the last alias wins over any function;
+/// otherwise the last function sourced wins.
+///
+/// Returns `None` if `definitions` is empty.
+pub fn effective_definition(definitions: &[Command]) -> Option<usize> {
+    definitions
+        .iter()
+        .rposition(|c| c.cmd_type == CommandType::Alias)
+        .or_else(|| definitions.len().checked_sub(1))
 }
 
 impl Command {
@@ -62,12 +184,6 @@ pub fn validate(&self) -> Result<()> {
                 self.name
             )));
         }
-        if self.cmd_type != "alias" && self.cmd_type != "function" {
-            return Err(LscmdError::Validation(format!(
-                "Invalid command type: {}",
-                self.cmd_type
-            )));
-        }
         if self.path.is_empty() {
             return Err(LscmdError::Validation(
                 "Command path cannot be empty".to_string(),
@@ -80,4 +196,90 @@ pub fn validate(&self) -> Result<()> {
         }
         Ok(())
     }
+
+    /// The code as it would be typed at the prompt: alias values as they
+    /// are, function bodies without their common indentation.
+    pub fn body(&self) -> String {
+        let indent = self
+            .code
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.len() - line.trim_start().len())
+            .min()
+            .unwrap_or(0);
+        let lines: Vec<_> = self
+            .code
+            .lines()
+            .map(|line| line.get(indent..).unwrap_or_else(|| line.trim_start()))
+            .collect();
+        lines.join("\n").trim_matches('\n').to_string()
+    }
+}
+
+/// A shell file tracked in the `files` table.
+///
+/// Every scanned file gets a row, including files that define no commands
+/// and files that failed to parse, so that broken files stay visible.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileRecord {
+    /// The absolute path to the file (PRIMARY KEY).
+    pub path: String,
+    /// The file's modification time when it was last parsed (Unix timestamp).
+    pub mtime: i64,
+    /// The file's size in bytes when it was last parsed.
+    pub size: u64,
+    /// The content hash recorded when the file was last parsed.
+    pub hash: String,
+    /// The outcome of the last parse: 'ok', 'partial' or 'failed'.
+    pub parse_status: String,
+    /// The errors from the last parse, one per line.
+    pub error_message: Option<String>,
+    /// The number of commands currently stored for this file.
+    pub command_count: usize,
+    /// When the file was last written to the database (Unix timestamp).
+    /// This is handled by the database DEFAULT trigger.
+    pub indexed_at: Option<i64>,
+    /// The alias root the file was found under, as its absolute path.
+    #[serde(default)]
+    pub root: Option<String>,
+    /// The file's position in the shell's startup sequence, when it was
+    /// found by following `source` statements from the rc files.
+    #[serde(default)]
+    pub load_order: Option<usize>,
+}
+
+impl FileRecord {
+    /// Builds the record for a parsed file, deriving its status from the outcome.
+    ///
+    /// A file with errors is 'partial' if some commands were still extracted
+    /// and 'failed' otherwise.
+    pub fn from_parse(
+        path: &str,
+        mtime: i64,
+        size: u64,
+        hash: String,
+        command_count: usize,
+        errors: &[LscmdError],
+    ) -> Self {
+        let (parse_status, error_message) = match (errors.is_empty(), command_count) {
+            (true, _) => ("ok", None),
+            (false, 0) => ("failed", Some(errors)),
+            (false, _) => ("partial", Some(errors)),
+        };
+
+        Self {
+            path: path.to_string(),
+            mtime,
+            size,
+            hash,
+            parse_status: parse_status.to_string(),
+            error_message: error_message.map(|errors| {
+                errors.iter().map(|e| e.to_string()).collect::<Vec<_>>().join("\n")
+            }),
+            command_count,
+            indexed_at: None,
+            root: None,
+            load_order: None,
+        }
+    }
 }
@@ -1,7 +1,8 @@
-use super::{Command, Result};
+use super::{effective_definition, Command, CommandType, FileRecord, Result};
 use crate::error::LscmdError;
-use crate::search::SearchEngine;
-use rusqlite::{params, Connection};
+use crate::search::{SearchEngine, SearchResult};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::BTreeSet;
 use std::sync::{Arc, Mutex};
 
 /// Trait defining the interface for command persistence.
@@ -10,14 +11,39 @@
 pub trait CommandRepository: Send + Sync {
     fn insert_command(&self, command: &Command) -> Result<()>;
     fn batch_insert(&self, commands: &[Command]) -> Result<()>;
-    fn search_commands(&self, engine: &SearchEngine, query: &str) -> Result<Vec<Command>>;
+    fn replace_all(&self, files: &[FileRecord], commands: &[Command]) -> Result<()>;
+    fn search_commands(&self, engine: &SearchEngine, query: &str, cmd_type: Option<CommandType>) -> Result<Vec<SearchResult>>;
     fn get_command_by_name(&self, name: &str) -> Result<Option<Command>>;
+    fn get_definitions(&self, name: &str) -> Result<Vec<Command>>;
+    fn list_conflicts(&self) -> Result<Vec<Vec<Command>>>;
+    fn list_names(&self) -> Result<Vec<String>>;
+    fn list_commands(&self, path: Option<&str>, cmd_type: Option<CommandType>) -> Result<Vec<Command>>;
     fn delete_by_path(&self, path: &str) -> Result<u64>;
     fn clear_all(&self) -> Result<u64>;
     fn get_file_mtime(&self, path: &str) -> Result<Option<i64>>;
+    fn get_file(&self, path: &str) -> Result<Option<FileRecord>>;
+    fn list_files(&self) -> Result<Vec<FileRecord>>;
+    fn replace_file(&self, file: &FileRecord, commands: &[Command]) -> Result<()>;
+    fn replace_files(&self, files: &[FileRecord], commands: &[Command], deleted: &[String]) -> Result<()>;
+    fn replace_root(&self, root: &str, files: &[FileRecord], commands: &[Command]) -> Result<()>;
+    fn delete_root(&self, root: &str) -> Result<u64>;
+    fn touch_file(&self, path: &str, mtime: i64, root: &str, load_order: Option<usize>) -> Result<u64>;
     fn health_check(&self) -> Result<()>;
 }
 
+/// Selects commands together with the mtime and root of their source file, in the
+/// column order expected by `row_to_command`.
+const COMMAND_SELECT: &str = "SELECT c.name, c.cmd_type, c.path, c.start_line, c.end_line, c.byte_offset, c.code, c.description, COALESCE(f.mtime, 0), c.created_at, f.root \
+     FROM commands c LEFT JOIN files f ON f.path = c.path";
+
+/// Orders definitions the way the shell sources them: files in their
+/// discovered load order if known, otherwise in glob order, then top to
+/// bottom within a file.
+const SOURCING_ORDER: &str = "ORDER BY f.load_order IS NULL, f.load_order, c.path, c.start_line";
+
+/// Selects files in the column order expected by `row_to_file`.
+const FILE_SELECT: &str = "SELECT path, mtime, size, hash, parse_status, error_message, command_count, indexed_at, root, load_order FROM files";
+
 /// An implementation of `CommandRepository` using `rusqlite` with a SQLite backend.
 ///
 /// Uses Arc<Mutex<Connection>> to allow safe concurrent access while maintaining
@@ -39,11 +65,9 @@ impl CommandRepository for SqliteCommandRepository {
     /// Inserts a single command, replacing it if it already exists.
     fn insert_command(&self, command: &Command) -> Result<()> {
         let conn = self.conn.lock().unwrap();
-        conn.execute(
-            "INSERT OR REPLACE INTO commands (name, cmd_type, path, code, file_mtime) VALUES (?1, ?2, ?3, ?4, ?5)",
-            params![&command.name, &command.cmd_type, &command.path, &command.code, command.file_mtime],
-        )
-        .map_err(|e| LscmdError::Database(e.to_string()))?;
+        let tx = conn.unchecked_transaction().map_err(|e| LscmdError::Database(e.to_string()))?;
+        insert_all(&tx, std::slice::from_ref(command))?;
+        tx.commit().map_err(|e| LscmdError::Database(e.to_string()))?;
         Ok(())
     }
 
@@ -55,99 +79,273 @@ fn batch_insert(&self, commands: &[Command]) -> Result<()> {
 
         let conn = self.conn.lock().unwrap();
         let tx = conn.unchecked_transaction().map_err(|e| LscmdError::Database(e.to_string()))?;
+        insert_all(&tx, commands)?;
+        tx.commit().map_err(|e| LscmdError::Database(e.to_string()))?;
+        Ok(())
+    }
 
-        for command in commands {
-            tx.execute(
-                "INSERT OR REPLACE INTO commands (name, cmd_type, path, code, file_mtime) VALUES (?1, ?2, ?3, ?4, ?5)",
-                params![&command.name, &command.cmd_type, &command.path, &command.code, command.file_mtime],
-            )
-            .map_err(|e| LscmdError::Database(e.to_string()))?;
+    /// Replaces the entire database contents with `files` and `commands`.
+    ///
+    /// The clear and the inserts share a single transaction, so concurrent
+    /// readers see either the old rows or the new ones, never an empty table.
+    fn replace_all(&self, files: &[FileRecord], commands: &[Command]) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction().map_err(|e| LscmdError::Database(e.to_string()))?;
+        delete_all(&tx)?;
+        for file in files {
+            upsert_file(&tx, file)?;
         }
-
+        insert_all(&tx, commands)?;
         tx.commit().map_err(|e| LscmdError::Database(e.to_string()))?;
         Ok(())
     }
 
-    /// Searches commands by first fetching all and then using the search engine.
-    fn search_commands(&self, engine: &SearchEngine, query: &str) -> Result<Vec<Command>> {
+    /// Searches commands of type `cmd_type`, or of any type, with the search engine.
+    ///
+    /// Results are ordered by the engine's score. Word-mode queries are
+    /// answered by the `commands_fts` index, whose bm25 rank breaks ties
+    /// between equal scores. Regex queries, and words too short for the trigram index,
+    /// fall back to fetching every command and filtering in Rust. The type
+    /// filter is applied by the database either way.
+    fn search_commands(&self, engine: &SearchEngine, query: &str, cmd_type: Option<CommandType>) -> Result<Vec<SearchResult>> {
         let conn = self.conn.lock().unwrap();
-        let mut stmt = conn.prepare("SELECT name, cmd_type, path, code, file_mtime, created_at FROM commands")
+        let candidates: Vec<Command> = match engine.fts_query(query) {
+            // Names weigh most, then descriptions, then code.
+            Some(fts_query) => conn
+                .prepare(&format!(
+                    "{} JOIN commands_fts ON commands_fts.rowid = c.id \
+                     WHERE commands_fts MATCH ?1 AND (?2 IS NULL OR c.cmd_type = ?2) \
+                     ORDER BY bm25(commands_fts, 10.0, 1.0, 5.0)",
+                    COMMAND_SELECT
+                ))
+                .and_then(|mut stmt| {
+                    stmt.query_map(params![fts_query, cmd_type], row_to_command)?
+                        .collect::<std::result::Result<Vec<_>, _>>()
+                }),
+            None => conn
+                .prepare(&format!("{} WHERE ?1 IS NULL OR c.cmd_type = ?1", COMMAND_SELECT))
+                .and_then(|mut stmt| {
+                    stmt.query_map([cmd_type], row_to_command)?
+                        .collect::<std::result::Result<Vec<_>, _>>()
+                }),
+        }
+        .map_err(|e| LscmdError::Database(e.to_string()))?;
+
+        // The index only narrows the candidates down; the engine has the
+        // final say so both paths match and rank exactly the same commands.
+        // It returns references, so we clone them to return owned results.
+        let results = engine.search(query, &candidates)?.iter().map(SearchResult::to_owned).collect();
+        Ok(results)
+    }
+
+    /// Retrieves the effective definition of a name (case-insensitive).
+    ///
+    /// When a name is defined more than once, the definition the shell would
+    /// use wins; definitions matching `name` exactly are preferred over ones
+    /// that only match ignoring case.
+    fn get_command_by_name(&self, name: &str) -> Result<Option<Command>> {
+        let definitions = self.get_definitions(name)?;
+        let exact: Vec<Command> = definitions.iter().filter(|c| c.name == name).cloned().collect();
+        let mut candidates = if exact.is_empty() { definitions } else { exact };
+
+        // Names differing only in case are separate shell commands; fall back
+        // to the first one in sourcing order.
+        if let Some(first) = candidates.first().map(|c| c.name.clone()) {
+            candidates.retain(|c| c.name == first);
+        }
+        Ok(effective_definition(&candidates).map(|index| candidates.swap_remove(index)))
+    }
+
+    /// Retrieves every definition of a name (case-insensitive) in sourcing order.
+    fn get_definitions(&self, name: &str) -> Result<Vec<Command>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!("{} WHERE LOWER(c.name) = LOWER(?1) {}", COMMAND_SELECT, SOURCING_ORDER))
             .map_err(|e| LscmdError::Database(e.to_string()))?;
 
-        let command_iter = stmt.query_map([], |row| {
-            Ok(Command {
-                name: row.get(0)?,
-                cmd_type: row.get(1)?,
-                path: row.get(2)?,
-                code: row.get(3)?,
-                file_mtime: row.get(4)?,
-                created_at: row.get(5)?,
-            })
-        })
+        let definitions = stmt.query_map([name], row_to_command)
+            .map_err(|e| LscmdError::Database(e.to_string()))?
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|e| LscmdError::Database(e.to_string()))?;
+        Ok(definitions)
+    }
+
+    /// Lists every name defined more than once, grouped by name.
+    ///
+    /// Groups are sorted by name and each group is in sourcing order.
+    fn list_conflicts(&self) -> Result<Vec<Vec<Command>>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!(
+            "{} WHERE c.name IN (SELECT name FROM commands GROUP BY name HAVING COUNT(*) > 1) \
+             ORDER BY c.name, f.load_order IS NULL, f.load_order, c.path, c.start_line",
+            COMMAND_SELECT
+        ))
         .map_err(|e| LscmdError::Database(e.to_string()))?;
 
-        let all_commands: Vec<Command> = command_iter
+        let commands = stmt.query_map([], row_to_command)
+            .map_err(|e| LscmdError::Database(e.to_string()))?
             .collect::<std::result::Result<Vec<_>, _>>()
             .map_err(|e| LscmdError::Database(e.to_string()))?;
 
-        // The search engine returns references, so we clone them to return owned Commands.
-        let results = engine.search(query, &all_commands)?.into_iter().cloned().collect();
-        Ok(results)
+        let mut groups: Vec<Vec<Command>> = Vec::new();
+        for command in commands {
+            match groups.last_mut() {
+                Some(group) if group[0].name == command.name => group.push(command),
+                _ => groups.push(vec![command]),
+            }
+        }
+        Ok(groups)
     }
 
-    /// Retrieves a command by its exact name (case-insensitive).
-    fn get_command_by_name(&self, name: &str) -> Result<Option<Command>> {
+    /// Lists every distinct command name, sorted.
+    fn list_names(&self) -> Result<Vec<String>> {
         let conn = self.conn.lock().unwrap();
-        let mut stmt = conn.prepare("SELECT name, cmd_type, path, code, file_mtime, created_at FROM commands WHERE LOWER(name) = LOWER(?1)")
+        let mut stmt = conn.prepare("SELECT DISTINCT name FROM commands ORDER BY name")
+            .map_err(|e| LscmdError::Database(e.to_string()))?;
+
+        let names = stmt.query_map([], |row| row.get(0))
+            .map_err(|e| LscmdError::Database(e.to_string()))?
+            .collect::<std::result::Result<Vec<_>, _>>()
             .map_err(|e| LscmdError::Database(e.to_string()))?;
+        Ok(names)
+    }
 
-        let mut rows = stmt.query_map([name], |row| {
-            Ok(Command {
-                name: row.get(0)?,
-                cmd_type: row.get(1)?,
-                path: row.get(2)?,
-                code: row.get(3)?,
-                file_mtime: row.get(4)?,
-                created_at: row.get(5)?,
-            })
-        })
+    /// Lists the commands defined in `path`, or in every file, in sourcing
+    /// order, optionally only those of type `cmd_type`.
+    fn list_commands(&self, path: Option<&str>, cmd_type: Option<CommandType>) -> Result<Vec<Command>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!(
+            "{} WHERE (?1 IS NULL OR c.path = ?1) AND (?2 IS NULL OR c.cmd_type = ?2) {}",
+            COMMAND_SELECT, SOURCING_ORDER
+        ))
         .map_err(|e| LscmdError::Database(e.to_string()))?;
 
-        match rows.next() {
-            Some(Ok(command)) => Ok(Some(command)),
-            Some(Err(e)) => Err(LscmdError::Database(e.to_string())),
-            None => Ok(None),
-        }
+        let commands = stmt.query_map(params![path, cmd_type], row_to_command)
+            .map_err(|e| LscmdError::Database(e.to_string()))?
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|e| LscmdError::Database(e.to_string()))?;
+        Ok(commands)
     }
 
-    /// Deletes all commands from a given file path.
+    /// Deletes a file and all commands from it, returning the number of commands removed.
     fn delete_by_path(&self, path: &str) -> Result<u64> {
         let conn = self.conn.lock().unwrap();
-        let affected = conn.execute("DELETE FROM commands WHERE path = ?1", [path])
+        let tx = conn.unchecked_transaction().map_err(|e| LscmdError::Database(e.to_string()))?;
+        let affected = tx.execute("DELETE FROM commands WHERE path = ?1", [path])
             .map_err(|e| LscmdError::Database(e.to_string()))?;
+        tx.execute("DELETE FROM files WHERE path = ?1", [path])
+            .map_err(|e| LscmdError::Database(e.to_string()))?;
+        tx.commit().map_err(|e| LscmdError::Database(e.to_string()))?;
         Ok(affected as u64)
     }
 
-    /// Clears the entire commands table.
+    /// Clears all files and commands, returning the number of commands removed.
     fn clear_all(&self) -> Result<u64> {
         let conn = self.conn.lock().unwrap();
-        let affected = conn.execute("DELETE FROM commands", [])
-            .map_err(|e| LscmdError::Database(e.to_string()))?;
-        Ok(affected as u64)
+        let tx = conn.unchecked_transaction().map_err(|e| LscmdError::Database(e.to_string()))?;
+        let affected = delete_all(&tx)?;
+        tx.commit().map_err(|e| LscmdError::Database(e.to_string()))?;
+        Ok(affected)
     }
 
-    /// Gets the most recent modification time for a given file path.
+    /// Gets the modification time recorded for a given file path.
     fn get_file_mtime(&self, path: &str) -> Result<Option<i64>> {
         let conn = self.conn.lock().unwrap();
-        let mut stmt = conn.prepare("SELECT MAX(file_mtime) as max_mtime FROM commands WHERE path = ?1")
+        conn.query_row("SELECT mtime FROM files WHERE path = ?1", [path], |row| row.get(0))
+            .optional()
+            .map_err(|e| LscmdError::Database(e.to_string()))
+    }
+
+    /// Retrieves the tracked state of a single file.
+    fn get_file(&self, path: &str) -> Result<Option<FileRecord>> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(&format!("{} WHERE path = ?1", FILE_SELECT), [path], row_to_file)
+            .optional()
+            .map_err(|e| LscmdError::Database(e.to_string()))
+    }
+
+    /// Lists every tracked file, including ones without commands, ordered by path.
+    fn list_files(&self) -> Result<Vec<FileRecord>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!("{} ORDER BY path", FILE_SELECT))
             .map_err(|e| LscmdError::Database(e.to_string()))?;
 
-        let result: Option<i64> = stmt.query_row([path], |row| {
-            Ok(row.get::<_, Option<i64>>(0)?)
-        })
-        .map_err(|e| LscmdError::Database(e.to_string()))?;
+        let files = stmt.query_map([], row_to_file)
+            .map_err(|e| LscmdError::Database(e.to_string()))?
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|e| LscmdError::Database(e.to_string()))?;
 
-        Ok(result)
+        Ok(files)
+    }
+
+    /// Records `file` and replaces all of its commands in a single transaction.
+    fn replace_file(&self, file: &FileRecord, commands: &[Command]) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction().map_err(|e| LscmdError::Database(e.to_string()))?;
+        upsert_file(&tx, file)?;
+        tx.execute("DELETE FROM commands WHERE path = ?1", [&file.path])
+            .map_err(|e| LscmdError::Database(e.to_string()))?;
+        insert_all(&tx, commands)?;
+        tx.commit().map_err(|e| LscmdError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Records `files`, replaces all of their commands with `commands` and
+    /// deletes the `deleted` paths, all in a single transaction.
+    fn replace_files(&self, files: &[FileRecord], commands: &[Command], deleted: &[String]) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction().map_err(|e| LscmdError::Database(e.to_string()))?;
+        for file in files {
+            upsert_file(&tx, file)?;
+            tx.execute("DELETE FROM commands WHERE path = ?1", [&file.path])
+                .map_err(|e| LscmdError::Database(e.to_string()))?;
+        }
+        insert_all(&tx, commands)?;
+        for path in deleted {
+            tx.execute("DELETE FROM commands WHERE path = ?1", [path])
+                .map_err(|e| LscmdError::Database(e.to_string()))?;
+            tx.execute("DELETE FROM files WHERE path = ?1", [path])
+                .map_err(|e| LscmdError::Database(e.to_string()))?;
+        }
+        tx.commit().map_err(|e| LscmdError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Replaces the files found under `root`, and their commands, with `files`
+    /// and `commands`, leaving every other root alone.
+    ///
+    /// Like `replace_all`, this happens in a single transaction.
+    fn replace_root(&self, root: &str, files: &[FileRecord], commands: &[Command]) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction().map_err(|e| LscmdError::Database(e.to_string()))?;
+        delete_root(&tx, root)?;
+        for file in files {
+            upsert_file(&tx, file)?;
+        }
+        insert_all(&tx, commands)?;
+        tx.commit().map_err(|e| LscmdError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Deletes every file found under `root` and their commands, returning
+    /// the number of commands removed.
+    fn delete_root(&self, root: &str) -> Result<u64> {
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction().map_err(|e| LscmdError::Database(e.to_string()))?;
+        let affected = delete_root(&tx, root)?;
+        tx.commit().map_err(|e| LscmdError::Database(e.to_string()))?;
+        Ok(affected)
+    }
+
+    /// Records a new mtime, root and load order for a file whose content did
+    /// not change.
+    fn touch_file(&self, path: &str, mtime: i64, root: &str, load_order: Option<usize>) -> Result<u64> {
+        let conn = self.conn.lock().unwrap();
+        let affected = conn.execute(
+            "UPDATE files SET mtime = ?1, root = ?2, load_order = ?3, indexed_at = strftime('%s', 'now') WHERE path = ?4",
+            params![mtime, root, load_order, path],
+        )
+        .map_err(|e| LscmdError::Database(e.to_string()))?;
+        Ok(affected as u64)
     }
 
     /// Pings the database to check for a live connection.
@@ -157,4 +355,139 @@ fn health_check(&self) -> Result<()> {
             .map_err(|e| LscmdError::Database(e.to_string()))?;
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+/// Maps a row selected with `COMMAND_SELECT` to a `Command`.
+fn row_to_command(row: &rusqlite::Row<'_>) -> rusqlite::Result<Command> {
+    Ok(Command {
+        name: row.get(0)?,
+        cmd_type: row.get(1)?,
+        path: row.get(2)?,
+        start_line: row.get(3)?,
+        end_line: row.get(4)?,
+        byte_offset: row.get(5)?,
+        code: row.get(6)?,
+        description: row.get(7)?,
+        file_mtime: row.get(8)?,
+        created_at: row.get(9)?,
+        root: row.get(10)?,
+    })
+}
+
+/// Maps a row selected with `FILE_SELECT` to a `FileRecord`.
+fn row_to_file(row: &rusqlite::Row<'_>) -> rusqlite::Result<FileRecord> {
+    Ok(FileRecord {
+        path: row.get(0)?,
+        mtime: row.get(1)?,
+        size: row.get(2)?,
+        hash: row.get(3)?,
+        parse_status: row.get(4)?,
+        error_message: row.get(5)?,
+        command_count: row.get(6)?,
+        indexed_at: row.get(7)?,
+        root: row.get(8)?,
+        load_order: row.get(9)?,
+    })
+}
+
+/// Inserts or updates a file row without touching its commands.
+///
+/// A record without a root, such as one reindexed by `lscmd edit`, keeps
+/// the root and load order the file already had.
+fn upsert_file(conn: &Connection, file: &FileRecord) -> Result<()> {
+    conn.prepare_cached(
+        "INSERT INTO files (path, mtime, size, hash, parse_status, error_message, command_count, root, load_order) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9) \
+         ON CONFLICT(path) DO UPDATE SET mtime = excluded.mtime, size = excluded.size, hash = excluded.hash, \
+         parse_status = excluded.parse_status, error_message = excluded.error_message, \
+         command_count = excluded.command_count, indexed_at = strftime('%s', 'now'), \
+         root = COALESCE(excluded.root, files.root), \
+         load_order = CASE WHEN excluded.root IS NULL THEN files.load_order ELSE excluded.load_order END",
+    )
+    .and_then(|mut stmt| {
+        stmt.execute(params![
+            &file.path,
+            file.mtime,
+            file.size,
+            &file.hash,
+            &file.parse_status,
+            &file.error_message,
+            file.command_count,
+            &file.root,
+            file.load_order,
+        ])
+    })
+    .map_err(|e| LscmdError::Database(e.to_string()))?;
+    Ok(())
+}
+
+/// Inserts `commands` on `conn` without opening a transaction of its own.
+///
+/// A file row is created for any path not tracked yet, and the command count
+/// of every affected file is refreshed afterwards. A definition already stored
+/// at the same place is updated in place: `INSERT OR REPLACE` would delete it
+/// without firing the triggers that keep `commands_fts` in sync.
+fn insert_all(conn: &Connection, commands: &[Command]) -> Result<()> {
+    let mut file_stmt = conn
+        .prepare_cached("INSERT OR IGNORE INTO files (path, mtime, size, hash, parse_status) VALUES (?1, ?2, 0, '', 'ok')")
+        .map_err(|e| LscmdError::Database(e.to_string()))?;
+    let mut stmt = conn
+        .prepare_cached(
+            "INSERT INTO commands (name, cmd_type, path, start_line, end_line, byte_offset, code, description) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8) \
+             ON CONFLICT(name, path, start_line) DO UPDATE SET cmd_type = excluded.cmd_type, \
+             end_line = excluded.end_line, byte_offset = excluded.byte_offset, code = excluded.code, \
+             description = excluded.description",
+        )
+        .map_err(|e| LscmdError::Database(e.to_string()))?;
+
+    let mut paths = BTreeSet::new();
+    for command in commands {
+        if paths.insert(command.path.as_str()) {
+            file_stmt.execute(params![&command.path, command.file_mtime])
+                .map_err(|e| LscmdError::Database(e.to_string()))?;
+        }
+        stmt.execute(params![
+            &command.name,
+            command.cmd_type,
+            &command.path,
+            command.start_line,
+            command.end_line,
+            command.byte_offset,
+            &command.code,
+            &command.description,
+        ])
+            .map_err(|e| LscmdError::Database(e.to_string()))?;
+    }
+
+    // Definitions are keyed by their location, so only the files inserted
+    // into can have changed.
+    let mut count_stmt = conn
+        .prepare_cached("UPDATE files SET command_count = (SELECT COUNT(*) FROM commands WHERE commands.path = files.path) WHERE path = ?1")
+        .map_err(|e| LscmdError::Database(e.to_string()))?;
+    for path in paths {
+        count_stmt.execute([path])
+            .map_err(|e| LscmdError::Database(e.to_string()))?;
+    }
+    Ok(())
+}
+
+/// Deletes every command and file on `conn`, returning the number of commands removed.
+fn delete_all(conn: &Connection) -> Result<u64> {
+    let affected = conn.execute("DELETE FROM commands", [])
+        .map_err(|e| LscmdError::Database(e.to_string()))?;
+    conn.execute("DELETE FROM files", [])
+        .map_err(|e| LscmdError::Database(e.to_string()))?;
+    Ok(affected as u64)
+}
+
+/// Deletes the files under `root` and their commands on `conn`, returning
+/// the number of commands removed.
+fn delete_root(conn: &Connection, root: &str) -> Result<u64> {
+    let affected = conn
+        .execute("DELETE FROM commands WHERE path IN (SELECT path FROM files WHERE root = ?1)", [root])
+        .map_err(|e| LscmdError::Database(e.to_string()))?;
+    conn.execute("DELETE FROM files WHERE root = ?1", [root])
+        .map_err(|e| LscmdError::Database(e.to_string()))?;
+    Ok(affected as u64)
+}
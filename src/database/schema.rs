@@ -1,3 +1,7 @@
+use crate::error::{LscmdError, Result};
+use rusqlite::Connection;
+use std::path::Path;
+
 /// Defines the SQL schema for the commands database.
 ///
 /// This function returns the SQL statements required to create the necessary
@@ -5,20 +9,51 @@
 /// executed during the database initialization process.
 ///
 /// The schema includes:
-/// - A `commands` table to store aliases and functions.
+/// - A `files` table tracking every scanned file, the alias root it was
+///   found under, where it is sourced during shell startup and its last
+///   parse outcome.
+/// - A `commands` table to store aliases, functions and the other
+///   `CommandType`s.
 /// - Indexes to optimize search performance, including a case-insensitive
 ///   index on the command name.
+/// - A `commands_fts` full-text index over name, code and description, kept
+///   in sync with `commands` by triggers.
 pub fn get_schema() -> &'static str {
     r#"
+-- Track every scanned file, including ones that define no commands or failed to parse.
+CREATE TABLE IF NOT EXISTS files (
+    path TEXT PRIMARY KEY NOT NULL,    -- Absolute path to the source file
+    mtime INTEGER NOT NULL,            -- Last modification time when parsed
+    size INTEGER NOT NULL,             -- File size in bytes when parsed
+    hash TEXT NOT NULL,                -- Content hash when parsed
+    parse_status TEXT NOT NULL,        -- 'ok', 'partial' or 'failed'
+    error_message TEXT,                -- Parse errors, one per line
+    command_count INTEGER NOT NULL DEFAULT 0,
+    indexed_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+    root TEXT,                         -- Absolute path of the alias root it was found under
+    load_order INTEGER                 -- Position in the shell's startup sequence, if discovered from rc files
+);
+
+-- Find a root's files when it is rescanned or removed.
+CREATE INDEX IF NOT EXISTS idx_files_root ON files(root);
+
 -- Create the main table for storing shell commands.
--- The `name` is the PRIMARY KEY to ensure uniqueness.
+-- A name may be defined in several files (or several times in one file), so
+-- each definition is keyed by where it lives. `id` gives every row a stable
+-- rowid for the full-text index.
 CREATE TABLE IF NOT EXISTS commands (
-    name TEXT PRIMARY KEY NOT NULL,
-    cmd_type TEXT NOT NULL,      -- 'alias' or 'function'
-    path TEXT NOT NULL,          -- Absolute path to the source file
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL,
+    cmd_type TEXT NOT NULL       -- One of the `CommandType` names
+        CHECK (cmd_type IN ('alias', 'function', 'variable', 'keybinding')),
+    path TEXT NOT NULL REFERENCES files(path) ON DELETE CASCADE, -- Source file
+    start_line INTEGER NOT NULL, -- 1-based line where the definition starts
+    end_line INTEGER NOT NULL,   -- 1-based line where the definition ends
+    byte_offset INTEGER NOT NULL, -- Byte offset of `start_line` in the file
     code TEXT NOT NULL,          -- The actual command or function body
-    file_mtime INTEGER NOT NULL, -- Last modification time of the source file
-    created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+    description TEXT,            -- Leading and inline comments, if any
+    created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+    UNIQUE (name, path, start_line)
 );
 
 -- Create an index on the command type for faster filtering.
@@ -30,5 +65,336 @@ pub fn get_schema() -> &'static str {
 -- Create an index on the lowercased command name to ensure fast, case-insensitive searches.
 -- This is a key performance optimization for the search functionality.
 CREATE INDEX IF NOT EXISTS idx_name_lower ON commands(LOWER(name));
+
+-- Full-text index over the searchable columns. The trigram tokenizer matches
+-- case-insensitive substrings, like word-mode search does.
+CREATE VIRTUAL TABLE IF NOT EXISTS commands_fts USING fts5(
+    name, code, description,
+    content = 'commands', content_rowid = 'id', tokenize = 'trigram'
+);
+
+-- Keep the full-text index in sync with `commands`.
+CREATE TRIGGER IF NOT EXISTS commands_fts_insert AFTER INSERT ON commands BEGIN
+    INSERT INTO commands_fts (rowid, name, code, description)
+        VALUES (new.id, new.name, new.code, new.description);
+END;
+CREATE TRIGGER IF NOT EXISTS commands_fts_delete AFTER DELETE ON commands BEGIN
+    INSERT INTO commands_fts (commands_fts, rowid, name, code, description)
+        VALUES ('delete', old.id, old.name, old.code, old.description);
+END;
+CREATE TRIGGER IF NOT EXISTS commands_fts_update AFTER UPDATE ON commands BEGIN
+    INSERT INTO commands_fts (commands_fts, rowid, name, code, description)
+        VALUES ('delete', old.id, old.name, old.code, old.description);
+    INSERT INTO commands_fts (rowid, name, code, description)
+        VALUES (new.id, new.name, new.code, new.description);
+END;
     "#
 }
+
+/// The schema version written by this build, stored in `PRAGMA user_version`.
+///
+/// Version 1 is the original, unversioned layout. Bump this together with a
+/// new entry in `MIGRATIONS` and the matching change to `get_schema`.
+pub const SCHEMA_VERSION: u32 = 9;
+
+/// A single, ordered schema change.
+struct Migration {
+    /// The version the database is at after this migration has run.
+    version: u32,
+    sql: &'static str,
+}
+
+/// Migrations from version 1 onwards, in ascending order.
+const MIGRATIONS: &[Migration] = &[
+    // v2: track files in their own table instead of per-command mtimes.
+    Migration {
+        version: 2,
+        sql: r#"
+CREATE TABLE files (
+    path TEXT PRIMARY KEY NOT NULL,
+    mtime INTEGER NOT NULL,
+    size INTEGER NOT NULL,
+    hash TEXT NOT NULL,
+    parse_status TEXT NOT NULL,
+    error_message TEXT,
+    command_count INTEGER NOT NULL DEFAULT 0,
+    indexed_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+);
+INSERT INTO files (path, mtime, size, hash, parse_status, command_count)
+    SELECT path, MAX(file_mtime), 0, '', 'ok', COUNT(*) FROM commands GROUP BY path;
+
+CREATE TABLE commands_v2 (
+    name TEXT PRIMARY KEY NOT NULL,
+    cmd_type TEXT NOT NULL,
+    path TEXT NOT NULL REFERENCES files(path) ON DELETE CASCADE,
+    code TEXT NOT NULL,
+    created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+);
+INSERT INTO commands_v2 (name, cmd_type, path, code, created_at)
+    SELECT name, cmd_type, path, code, created_at FROM commands;
+DROP TABLE commands;
+ALTER TABLE commands_v2 RENAME TO commands;
+
+CREATE INDEX idx_type ON commands(cmd_type);
+CREATE INDEX idx_path ON commands(path);
+CREATE INDEX idx_name_lower ON commands(LOWER(name));
+"#,
+    },
+    // v3: key commands by (name, path, start_line) so duplicate names are kept.
+    // Existing rows have no line numbers, so their files are marked stale: with
+    // `indexed_at` no later than `mtime` the change detector falls back to the
+    // (now empty) hash, and the next `lscmd update` reparses them.
+    Migration {
+        version: 3,
+        sql: r#"
+CREATE TABLE commands_v3 (
+    name TEXT NOT NULL,
+    cmd_type TEXT NOT NULL,
+    path TEXT NOT NULL REFERENCES files(path) ON DELETE CASCADE,
+    start_line INTEGER NOT NULL,
+    code TEXT NOT NULL,
+    created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+    PRIMARY KEY (name, path, start_line)
+);
+INSERT INTO commands_v3 (name, cmd_type, path, start_line, code, created_at)
+    SELECT name, cmd_type, path, 0, code, created_at FROM commands;
+DROP TABLE commands;
+ALTER TABLE commands_v3 RENAME TO commands;
+UPDATE files SET hash = '', indexed_at = mtime;
+
+CREATE INDEX idx_type ON commands(cmd_type);
+CREATE INDEX idx_path ON commands(path);
+CREATE INDEX idx_name_lower ON commands(LOWER(name));
+"#,
+    },
+    // v4: record where each definition ends and its byte offset. Like v3, the
+    // old rows lack the new data, so their files are marked stale.
+    Migration {
+        version: 4,
+        sql: r#"
+CREATE TABLE commands_v4 (
+    name TEXT NOT NULL,
+    cmd_type TEXT NOT NULL,
+    path TEXT NOT NULL REFERENCES files(path) ON DELETE CASCADE,
+    start_line INTEGER NOT NULL,
+    end_line INTEGER NOT NULL,
+    byte_offset INTEGER NOT NULL,
+    code TEXT NOT NULL,
+    created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+    PRIMARY KEY (name, path, start_line)
+);
+INSERT INTO commands_v4 (name, cmd_type, path, start_line, end_line, byte_offset, code, created_at)
+    SELECT name, cmd_type, path, start_line, start_line, 0, code, created_at FROM commands;
+DROP TABLE commands;
+ALTER TABLE commands_v4 RENAME TO commands;
+UPDATE files SET hash = '', indexed_at = mtime;
+
+CREATE INDEX idx_type ON commands(cmd_type);
+CREATE INDEX idx_path ON commands(path);
+CREATE INDEX idx_name_lower ON commands(LOWER(name));
+"#,
+    },
+    // v5: store the comments documenting each definition. Files are marked
+    // stale so the next update fills the column in.
+    Migration {
+        version: 5,
+        sql: r#"
+ALTER TABLE commands ADD COLUMN description TEXT;
+UPDATE files SET hash = '', indexed_at = mtime;
+"#,
+    },
+    // v6: give commands a stable integer id and index them for full-text
+    // search. Tables rebuilt by later migrations must recreate the triggers.
+    Migration {
+        version: 6,
+        sql: r#"
+CREATE TABLE commands_v6 (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL,
+    cmd_type TEXT NOT NULL,
+    path TEXT NOT NULL REFERENCES files(path) ON DELETE CASCADE,
+    start_line INTEGER NOT NULL,
+    end_line INTEGER NOT NULL,
+    byte_offset INTEGER NOT NULL,
+    code TEXT NOT NULL,
+    description TEXT,
+    created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+    UNIQUE (name, path, start_line)
+);
+INSERT INTO commands_v6 (name, cmd_type, path, start_line, end_line, byte_offset, code, description, created_at)
+    SELECT name, cmd_type, path, start_line, end_line, byte_offset, code, description, created_at FROM commands;
+DROP TABLE commands;
+ALTER TABLE commands_v6 RENAME TO commands;
+
+CREATE INDEX idx_type ON commands(cmd_type);
+CREATE INDEX idx_path ON commands(path);
+CREATE INDEX idx_name_lower ON commands(LOWER(name));
+
+CREATE VIRTUAL TABLE commands_fts USING fts5(
+    name, code, description,
+    content = 'commands', content_rowid = 'id', tokenize = 'trigram'
+);
+CREATE TRIGGER commands_fts_insert AFTER INSERT ON commands BEGIN
+    INSERT INTO commands_fts (rowid, name, code, description)
+        VALUES (new.id, new.name, new.code, new.description);
+END;
+CREATE TRIGGER commands_fts_delete AFTER DELETE ON commands BEGIN
+    INSERT INTO commands_fts (commands_fts, rowid, name, code, description)
+        VALUES ('delete', old.id, old.name, old.code, old.description);
+END;
+CREATE TRIGGER commands_fts_update AFTER UPDATE ON commands BEGIN
+    INSERT INTO commands_fts (commands_fts, rowid, name, code, description)
+        VALUES ('delete', old.id, old.name, old.code, old.description);
+    INSERT INTO commands_fts (rowid, name, code, description)
+        VALUES (new.id, new.name, new.code, new.description);
+END;
+INSERT INTO commands_fts (commands_fts) VALUES ('rebuild');
+"#,
+    },
+    // v7: restrict cmd_type to the known `CommandType` names. Rows of any
+    // other type could never be read back, so they are dropped.
+    Migration {
+        version: 7,
+        sql: r#"
+CREATE TABLE commands_v7 (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL,
+    cmd_type TEXT NOT NULL
+        CHECK (cmd_type IN ('alias', 'function', 'variable', 'keybinding')),
+    path TEXT NOT NULL REFERENCES files(path) ON DELETE CASCADE,
+    start_line INTEGER NOT NULL,
+    end_line INTEGER NOT NULL,
+    byte_offset INTEGER NOT NULL,
+    code TEXT NOT NULL,
+    description TEXT,
+    created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+    UNIQUE (name, path, start_line)
+);
+INSERT INTO commands_v7 (id, name, cmd_type, path, start_line, end_line, byte_offset, code, description, created_at)
+    SELECT id, name, LOWER(cmd_type), path, start_line, end_line, byte_offset, code, description, created_at
+    FROM commands WHERE LOWER(cmd_type) IN ('alias', 'function', 'variable', 'keybinding');
+DROP TABLE commands;
+ALTER TABLE commands_v7 RENAME TO commands;
+UPDATE files SET command_count = (SELECT COUNT(*) FROM commands WHERE commands.path = files.path);
+
+CREATE INDEX idx_type ON commands(cmd_type);
+CREATE INDEX idx_path ON commands(path);
+CREATE INDEX idx_name_lower ON commands(LOWER(name));
+
+CREATE TRIGGER commands_fts_insert AFTER INSERT ON commands BEGIN
+    INSERT INTO commands_fts (rowid, name, code, description)
+        VALUES (new.id, new.name, new.code, new.description);
+END;
+CREATE TRIGGER commands_fts_delete AFTER DELETE ON commands BEGIN
+    INSERT INTO commands_fts (commands_fts, rowid, name, code, description)
+        VALUES ('delete', old.id, old.name, old.code, old.description);
+END;
+CREATE TRIGGER commands_fts_update AFTER UPDATE ON commands BEGIN
+    INSERT INTO commands_fts (commands_fts, rowid, name, code, description)
+        VALUES ('delete', old.id, old.name, old.code, old.description);
+    INSERT INTO commands_fts (rowid, name, code, description)
+        VALUES (new.id, new.name, new.code, new.description);
+END;
+INSERT INTO commands_fts (commands_fts) VALUES ('rebuild');
+"#,
+    },
+    // v8: remember which alias root each file came from. Existing files
+    // have none; the next update records it without reparsing them.
+    Migration {
+        version: 8,
+        sql: r#"
+ALTER TABLE files ADD COLUMN root TEXT;
+CREATE INDEX idx_files_root ON files(root);
+"#,
+    },
+    // v9: remember the order in which the shell sources each file, when files
+    // are discovered from rc files. Existing files are not sourced that way.
+    Migration {
+        version: 9,
+        sql: r#"
+ALTER TABLE files ADD COLUMN load_order INTEGER;
+"#,
+    },
+];
+
+/// Brings the database schema up to `SCHEMA_VERSION`.
+///
+/// A new database gets the current schema from `get_schema` directly. An
+/// existing one is first backed up next to `db_path` (as
+/// `commands.db.v<N>.bak`) and then upgraded one migration at a time, each in
+/// its own transaction together with its `user_version` bump, so an
+/// interrupted upgrade leaves the database at the last completed version.
+///
+/// # Arguments
+///
+/// * `conn` - The connection to migrate. Foreign keys should not be enabled
+///   yet, since migrations may rebuild tables.
+/// * `db_path` - The database file, used for the backup. `None` skips it.
+///
+/// # Errors
+///
+/// Returns `LscmdError::Database` if the database was written by a newer
+/// lscmd, or if the backup or any migration step fails.
+pub fn migrate(conn: &mut Connection, db_path: Option<&Path>) -> Result<()> {
+    let mut version: u32 = conn
+        .pragma_query_value(None, "user_version", |row| row.get(0))
+        .map_err(|e| LscmdError::Database(e.to_string()))?;
+
+    if version > SCHEMA_VERSION {
+        return Err(LscmdError::Database(format!(
+            "database schema version {} is newer than the newest version this lscmd supports ({}); \
+             upgrade lscmd, or delete {} and run `lscmd scan`",
+            version,
+            SCHEMA_VERSION,
+            db_path.map_or_else(|| "the database".to_string(), |p| p.display().to_string())
+        )));
+    }
+
+    if version == 0 {
+        if !has_table(conn, "commands")? {
+            let tx = conn.transaction().map_err(|e| LscmdError::Database(e.to_string()))?;
+            tx.execute_batch(get_schema())
+                .map_err(|e| LscmdError::Database(e.to_string()))?;
+            tx.pragma_update(None, "user_version", SCHEMA_VERSION)
+                .map_err(|e| LscmdError::Database(e.to_string()))?;
+            tx.commit().map_err(|e| LscmdError::Database(e.to_string()))?;
+            return Ok(());
+        }
+        // Databases created before versioning are the original layout.
+        version = 1;
+    }
+
+    if version == SCHEMA_VERSION {
+        return Ok(());
+    }
+
+    if let Some(db_path) = db_path {
+        let backup = db_path.with_extension(format!("db.v{}.bak", version));
+        if backup.exists() {
+            std::fs::remove_file(&backup)?;
+        }
+        conn.execute("VACUUM INTO ?1", [backup.to_string_lossy()])
+            .map_err(|e| LscmdError::Database(format!("failed to back up database before migrating: {}", e)))?;
+    }
+
+    for migration in MIGRATIONS.iter().filter(|m| m.version > version) {
+        let tx = conn.transaction().map_err(|e| LscmdError::Database(e.to_string()))?;
+        tx.execute_batch(migration.sql)
+            .and_then(|_| tx.pragma_update(None, "user_version", migration.version))
+            .map_err(|e| {
+                LscmdError::Database(format!("migration to schema version {} failed: {}", migration.version, e))
+            })?;
+        tx.commit().map_err(|e| LscmdError::Database(e.to_string()))?;
+    }
+
+    Ok(())
+}
+
+fn has_table(conn: &Connection, name: &str) -> Result<bool> {
+    conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1)",
+        [name],
+        |row| row.get(0),
+    )
+    .map_err(|e| LscmdError::Database(e.to_string()))
+}
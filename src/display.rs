@@ -0,0 +1,386 @@
+use crate::database::{Command, CommandType};
+use crate::search::{Field, SearchResult};
+use colored::Colorize;
+use std::env;
+use std::io::IsTerminal;
+use std::ops::Range;
+use std::path::Path;
+
+/// Shell words rendered as keywords by the highlighter.
+const SHELL_KEYWORDS: &[&str] = &[
+    "case", "declare", "do", "done", "elif", "else", "esac", "export", "fi", "for", "function", "if", "in",
+    "local", "readonly", "return", "select", "then", "time", "until", "while",
+];
+
+/// How many columns `lscmd list --columns` uses by default, as lscmd.sh did.
+pub const DEFAULT_COLUMNS: usize = 5;
+
+/// The width assumed when the terminal's size cannot be read.
+const DEFAULT_TERMINAL_WIDTH: usize = 90;
+
+/// The kinds of shell tokens the highlighter distinguishes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Plain,
+    Keyword,
+    String,
+    Variable,
+    Comment,
+    Operator,
+}
+
+/// Formats commands for the terminal.
+///
+/// Colors are only emitted when `color_enabled` is set; otherwise the output
+/// is plain text suitable for pipes and files.
+#[derive(Debug, Clone)]
+pub struct DisplayFormatter {
+    color_enabled: bool,
+}
+
+impl DisplayFormatter {
+    pub fn new(color_enabled: bool) -> Self {
+        Self { color_enabled }
+    }
+
+    /// Enables colors only when stdout is a terminal and `NO_COLOR` is unset
+    /// or empty, following <https://no-color.org>.
+    pub fn from_env() -> Self {
+        let no_color = env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+        Self::new(std::io::stdout().is_terminal() && !no_color)
+    }
+
+    /// Formats every definition of a name, the one the shell uses (at index
+    /// `active`) first, followed by the definitions it shadows.
+    pub fn format_definitions(&self, definitions: &[Command], active: usize) -> String {
+        let mut blocks = Vec::with_capacity(definitions.len());
+        if let Some(command) = definitions.get(active) {
+            let status = (definitions.len() > 1).then_some("active");
+            blocks.push(self.format_command(command, status));
+        }
+        for (index, command) in definitions.iter().enumerate() {
+            if index != active {
+                blocks.push(self.format_command(command, Some("shadowed")));
+            }
+        }
+        blocks.join("\n")
+    }
+
+    /// Formats a single command with its location, description and body.
+    pub fn format_command(&self, command: &Command, status: Option<&str>) -> String {
+        let mut out = String::new();
+
+        let mut header = format!("{} ({})", self.paint_name(&command.name), command.cmd_type);
+        if let Some(status) = status {
+            header.push_str(&format!(" [{}]", self.paint_status(status)));
+        }
+        out.push_str(&header);
+        out.push('\n');
+
+        let location = match (command.start_line, command.end_line) {
+            (0, _) => command.path.clone(),
+            (start, end) if end > start => format!("{}:{}-{}", command.path, start, end),
+            (start, _) => format!("{}:{}", command.path, start),
+        };
+        out.push_str(&format!("  {} {}\n", self.paint_label("file:"), location));
+
+        if let Some(description) = &command.description {
+            for (index, line) in description.lines().enumerate() {
+                let label = if index == 0 { "about:" } else { "" };
+                out.push_str(&format!("  {} {}\n", self.paint_label(label), line));
+            }
+        }
+
+        out.push('\n');
+        for line in self.highlight(&command.code).lines() {
+            out.push_str("    ");
+            out.push_str(line);
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Formats search results as aligned `name  type  file:line` rows, in
+    /// the order given, highlighting where the query matched the name.
+    pub fn format_search_results(&self, results: &[SearchResult]) -> String {
+        let width = results.iter().map(|r| r.name.chars().count()).max().unwrap_or(0);
+        let mut out = String::new();
+        for result in results {
+            let padding = " ".repeat(width - result.name.chars().count());
+            out.push_str(&format!(
+                "{}{}  {:<8}  {}:{}\n",
+                self.paint_matched_name(result),
+                padding,
+                result.cmd_type,
+                result.path,
+                result.start_line
+            ));
+        }
+        out
+    }
+
+    /// Formats commands the way lscmd.sh listed them: an uppercase `## FILE`
+    /// header per file, in path order, over the file's names sorted
+    /// alphabetically and laid out top to bottom in `columns` columns that
+    /// share `width` characters. Aliases are green and functions blue.
+    pub fn format_columns(&self, commands: &[Command], columns: usize, width: usize) -> String {
+        let columns = columns.max(1);
+        let column_width = width.saturating_sub(columns * 2) / columns;
+
+        let mut files: Vec<(&str, Vec<&Command>)> = Vec::new();
+        for command in commands {
+            match files.iter_mut().find(|(path, _)| *path == command.path) {
+                Some((_, file_commands)) => file_commands.push(command),
+                None => files.push((&command.path, vec![command])),
+            }
+        }
+        files.sort_by_key(|(path, _)| *path);
+
+        let mut blocks = Vec::with_capacity(files.len());
+        for (path, mut file_commands) in files {
+            file_commands.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()).then(a.name.cmp(&b.name)));
+            let mut block = self.paint_file_header(path);
+            block.push('\n');
+
+            let rows = file_commands.len().div_ceil(columns);
+            for row in 0..rows {
+                let mut line = String::new();
+                let mut cells = (0..columns).filter_map(|column| file_commands.get(row + column * rows)).peekable();
+                while let Some(command) = cells.next() {
+                    line.push_str(&self.paint_typed_name(command));
+                    if cells.peek().is_some() {
+                        // Names too long for their column still get a gap.
+                        let padding = column_width.saturating_sub(command.name.chars().count());
+                        line.push_str(&" ".repeat(if padding == 0 { 2 } else { padding }));
+                    }
+                }
+                block.push_str(&line);
+                block.push('\n');
+            }
+            blocks.push(block);
+        }
+        blocks.join("\n")
+    }
+
+    /// Highlights shell `code`, or returns it unchanged when colors are off.
+    pub fn highlight(&self, code: &str) -> String {
+        if !self.color_enabled {
+            return code.to_string();
+        }
+
+        let mut out = String::with_capacity(code.len() * 2);
+        for (kind, range) in shell_tokens(code) {
+            // Paint line by line so escape codes never span a newline.
+            let text = &code[range];
+            for (index, part) in text.split('\n').enumerate() {
+                if index > 0 {
+                    out.push('\n');
+                }
+                if !part.is_empty() {
+                    out.push_str(&paint_token(kind, part));
+                }
+            }
+        }
+        out
+    }
+
+    fn paint_name(&self, name: &str) -> String {
+        match self.color_enabled {
+            true => name.bold().to_string(),
+            false => name.to_string(),
+        }
+    }
+
+    /// `## GIT` for `/aliases/git.sh`, in bold yellow.
+    fn paint_file_header(&self, path: &str) -> String {
+        let stem = Path::new(path).file_stem().map_or_else(|| path.into(), |stem| stem.to_string_lossy());
+        let header = format!("## {}", stem.to_uppercase());
+        match self.color_enabled {
+            true => header.yellow().bold().to_string(),
+            false => header,
+        }
+    }
+
+    fn paint_typed_name(&self, command: &Command) -> String {
+        match (self.color_enabled, command.cmd_type) {
+            (true, CommandType::Alias) => command.name.bright_green().to_string(),
+            (true, CommandType::Function) => command.name.bright_blue().to_string(),
+            _ => command.name.clone(),
+        }
+    }
+
+    fn paint_matched_name(&self, result: &SearchResult) -> String {
+        let ranges = result.matches.iter().find(|m| m.field == Field::Name).map(|m| m.ranges.as_slice());
+        let (true, Some(ranges)) = (self.color_enabled, ranges) else {
+            return self.paint_name(&result.name);
+        };
+
+        let name = &result.name;
+        let mut out = String::new();
+        let mut end = 0;
+        for range in ranges.iter().filter(|r| r.end <= name.len()) {
+            out.push_str(&name[end..range.start].bold().to_string());
+            out.push_str(&name[range.clone()].bold().yellow().underline().to_string());
+            end = range.end;
+        }
+        out.push_str(&name[end..].bold().to_string());
+        out
+    }
+
+    fn paint_status(&self, status: &str) -> String {
+        match (self.color_enabled, status) {
+            (false, _) => status.to_string(),
+            (true, "active") => status.green().to_string(),
+            (true, _) => status.yellow().to_string(),
+        }
+    }
+
+    /// Pads `label` so the values after it line up, then paints it.
+    fn paint_label(&self, label: &str) -> String {
+        let label = format!("{:<6}", label);
+        match self.color_enabled {
+            true => label.dimmed().to_string(),
+            false => label.to_string(),
+        }
+    }
+}
+
+/// The width of the terminal, read from the terminal itself so it is right
+/// even when stdout is piped, or a default if there is none.
+pub fn terminal_width() -> usize {
+    crossterm::terminal::size().map_or(DEFAULT_TERMINAL_WIDTH, |(columns, _)| columns as usize)
+}
+
+fn paint_token(kind: TokenKind, text: &str) -> String {
+    match kind {
+        TokenKind::Plain => text.to_string(),
+        TokenKind::Keyword => text.magenta().bold().to_string(),
+        TokenKind::String => text.green().to_string(),
+        TokenKind::Variable => text.cyan().to_string(),
+        TokenKind::Comment => text.bright_black().to_string(),
+        TokenKind::Operator => text.yellow().to_string(),
+    }
+}
+
+/// Splits shell `code` into highlightable tokens covering every byte.
+///
+/// This is a display aid, not a parser: it recognises quotes (which may span
+/// lines), `#` comments, `$` expansions, operators and keywords, and treats
+/// everything else as plain text.
+pub fn shell_tokens(code: &str) -> Vec<(TokenKind, Range<usize>)> {
+    let bytes = code.as_bytes();
+    let mut tokens = Vec::new();
+    let mut quote: Option<u8> = None;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let start = i;
+        if let Some(q) = quote {
+            let (end, closed) = quoted_end(code, i, q);
+            i = end;
+            if closed {
+                quote = None;
+            }
+            if i > start {
+                tokens.push((TokenKind::String, start..i));
+            }
+            if i < bytes.len() && bytes[i] == b'\n' {
+                tokens.push((TokenKind::Plain, i..i + 1));
+                i += 1;
+            }
+            continue;
+        }
+
+        let kind = match bytes[i] {
+            b'#' if i == 0 || is_word_break(bytes[i - 1]) => {
+                i = bytes[i..].iter().position(|&b| b == b'\n').map_or(bytes.len(), |n| i + n);
+                TokenKind::Comment
+            }
+            q @ (b'\'' | b'"') => {
+                let (end, closed) = quoted_end(code, i + 1, q);
+                i = end;
+                if !closed {
+                    quote = Some(q);
+                }
+                TokenKind::String
+            }
+            b'$' => {
+                i += 1;
+                match bytes.get(i) {
+                    Some(b'{') => {
+                        i = bytes[i..].iter().position(|&b| b == b'}').map_or(bytes.len(), |n| i + n + 1);
+                        TokenKind::Variable
+                    }
+                    Some(b'(') => {
+                        i += 1;
+                        TokenKind::Variable
+                    }
+                    Some(&b) if b.is_ascii_alphabetic() || b == b'_' => {
+                        while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                            i += 1;
+                        }
+                        TokenKind::Variable
+                    }
+                    Some(&b) if b.is_ascii_digit() || b"@*#?$!-".contains(&b) => {
+                        i += 1;
+                        TokenKind::Variable
+                    }
+                    _ => TokenKind::Plain,
+                }
+            }
+            b'\\' => {
+                i = (i + 2).min(bytes.len());
+                TokenKind::Plain
+            }
+            b if is_operator(b) => {
+                i += 1;
+                TokenKind::Operator
+            }
+            b if b.is_ascii_whitespace() => {
+                while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+                    i += 1;
+                }
+                TokenKind::Plain
+            }
+            _ => {
+                while i < bytes.len() && !is_word_break(bytes[i]) && !matches!(bytes[i], b'\'' | b'"' | b'$' | b'\\') {
+                    i += 1;
+                }
+                match SHELL_KEYWORDS.contains(&&code[start..i]) {
+                    true => TokenKind::Keyword,
+                    false => TokenKind::Plain,
+                }
+            }
+        };
+        // Multi-byte characters are only ever consumed whole, as part of a word.
+        while !code.is_char_boundary(i) {
+            i += 1;
+        }
+        tokens.push((kind, start..i));
+    }
+    tokens
+}
+
+/// Scans a quoted string from `i` up to and including its closing quote `q`,
+/// stopping at the end of the line if it continues on the next one.
+///
+/// Returns the end offset and whether the closing quote was found.
+fn quoted_end(code: &str, mut i: usize, q: u8) -> (usize, bool) {
+    let bytes = code.as_bytes();
+    while i < bytes.len() && bytes[i] != q && bytes[i] != b'\n' {
+        i += if q == b'"' && bytes[i] == b'\\' { 2 } else { 1 };
+    }
+    let i = i.min(bytes.len());
+    match bytes.get(i) {
+        Some(&b) if b == q => (i + 1, true),
+        _ => (i, false),
+    }
+}
+
+fn is_operator(b: u8) -> bool {
+    matches!(b, b'|' | b'&' | b';' | b'<' | b'>' | b'(' | b')' | b'{' | b'}')
+}
+
+fn is_word_break(b: u8) -> bool {
+    b.is_ascii_whitespace() || is_operator(b)
+}
@@ -29,6 +29,21 @@ pub enum LscmdError {
     
     #[error("Validation error: {0}")]
     Validation(String),
+    
+    #[error("Command not found: '{name}'{}", did_you_mean(.suggestions))]
+    CommandNotFound {
+        name: String,
+        suggestions: Vec<String>,
+    },
+}
+
+/// Renders the suggestion tail of `LscmdError::CommandNotFound`.
+fn did_you_mean(suggestions: &[String]) -> String {
+    match suggestions {
+        [] => String::new(),
+        [only] => format!("; did you mean '{}'?", only),
+        _ => format!("; did you mean one of: {}?", suggestions.join(", ")),
+    }
 }
 
 impl From<rusqlite::Error> for LscmdError {
@@ -1,9 +1,12 @@
 pub mod cli;
 pub mod config;
 pub mod database;
+pub mod display;
 pub mod error;
+pub mod output;
 pub mod parser;
 pub mod search;
 pub mod security;
+pub mod suggest;
 pub mod tui;
 pub mod utils;
@@ -1,12 +1,34 @@
-use lscmd::cli::{Cli, Commands};
-use lscmd::error::Result;
+use lscmd::cli::{Cli, Commands, PathAction};
+use lscmd::config::{AliasRoot, Config, Discovery, XdgPaths};
+use lscmd::database::{effective_definition, init_db, Command, CommandType};
+use lscmd::database::operations::{CommandRepository, SqliteCommandRepository};
+use lscmd::display::{terminal_width, DisplayFormatter};
+use lscmd::error::{LscmdError, Result};
+use lscmd::output::{OutputFormat, Records};
+use lscmd::search::{SearchEngine, SearchMode, SearchResult};
+use lscmd::suggest::NameSuggester;
+use lscmd::tui::{self, App};
+use lscmd::utils::editor::Editor;
+use lscmd::utils::file_scanner::{scan_roots, ChangeDetector, FileScanner, ScanResult};
+use lscmd::utils::parallel::{FileOutcome, ParallelProcessor};
+use lscmd::utils::sources::{LoadOrder, SkipReason, SourceDiscovery};
+use lscmd::utils::watcher::{apply_changes, index_root, unindex_root, FileWatcher, DEFAULT_DEBOUNCE};
 use clap::Parser;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
-mod tui; // TUI is not part of the library yet
+/// The exit status of `lscmd pick` when nothing was picked, as with fzf.
+const EXIT_CANCELLED: i32 = 130;
 
-fn main() -> Result<()> {
-    let cli = Cli::parse();
+fn main() {
+    if let Err(e) = run(Cli::parse()) {
+        eprintln!("lscmd: {}", e);
+        std::process::exit(1);
+    }
+}
 
+fn run(cli: Cli) -> Result<()> {
     match cli.command {
         Commands::Help => {
             // Clap handles help, but we might want a custom one.
@@ -14,23 +36,70 @@ fn main() -> Result<()> {
             println!("Use 'lscmd --help' for more information.");
         },
         Commands::Scan => {
-            println!("Full scan - TODO: implement in Phase 4");
+            let config = Config::load()?;
+            let repo = open_repository()?;
+            handle_scan(&config, &repo)?;
         },
         Commands::Update => {
-            println!("Incremental update - TODO: implement in Phase 4");
+            let config = Config::load()?;
+            let repo = open_repository()?;
+            handle_update(&config, &repo)?;
+        },
+        Commands::Watch => {
+            let config = Config::load()?;
+            let repo = open_repository()?;
+            handle_watch(&config, &repo)?;
+        },
+        Commands::Path { action } => {
+            let mut config = Config::load()?;
+            let repo = open_repository()?;
+            match action.unwrap_or(PathAction::List) {
+                PathAction::List => handle_path_list(&config, &repo)?,
+                PathAction::Add { path, label, include, exclude, max_depth } => {
+                    let mut root = AliasRoot::new(std::path::absolute(path)?);
+                    root.label = label;
+                    if !include.is_empty() {
+                        root.include = include;
+                    }
+                    root.exclude = exclude;
+                    root.max_depth = max_depth;
+                    handle_path_add(&mut config, &repo, root)?;
+                }
+                PathAction::Remove { root } => handle_path_remove(&mut config, &repo, &root)?,
+            }
         },
-        Commands::Path { new_path } => {
-            println!("Change path to: {:?} - TODO: implement in Phase 4", new_path);
+        Commands::Sources { rc_files, discovery } => {
+            let mut config = Config::load()?;
+            handle_sources(&mut config, rc_files, discovery)?;
         },
-        Commands::List { type_filter } => {
-            println!("List commands (filter: {:?}) - TODO: implement TUI in Phase 6", type_filter);
+        Commands::List { type_filter, format, columns } => {
+            let repo = open_repository()?;
+            match columns {
+                Some(columns) => handle_list_columns(&repo, type_filter, columns)?,
+                None => handle_list(&repo, type_filter, format)?,
+            }
         },
-        Commands::Search { query, regex, type_filter } => {
-            println!("Search: '{}' (regex: {}, filter: {:?}) - TODO: implement in Phase 4", 
-                    query, regex, type_filter);
+        Commands::Search { query, regex, fuzzy, type_filter, format } => {
+            let repo = open_repository()?;
+            handle_search(&repo, &query, search_mode(regex, fuzzy), type_filter, format)?;
         },
-        Commands::Show { name } => {
-            println!("Show command: '{}' - TODO: implement in Phase 4", name);
+        Commands::Pick { query, body, regex, fuzzy, type_filter } => {
+            let repo = open_repository()?;
+            if !handle_pick(&repo, query.as_deref(), body, search_mode(regex, fuzzy), type_filter)? {
+                std::process::exit(EXIT_CANCELLED);
+            }
+        },
+        Commands::Show { name, format } => {
+            let repo = open_repository()?;
+            handle_show(&repo, &name, format)?;
+        },
+        Commands::Edit { name } => {
+            let repo = open_repository()?;
+            handle_edit(&repo, &name)?;
+        },
+        Commands::Conflicts => {
+            let repo = open_repository()?;
+            handle_conflicts(&repo)?;
         },
         Commands::Init { path } => {
             println!("Initialize with path: {:?} - TODO: implement in Phase 7", path);
@@ -39,3 +108,514 @@ fn main() -> Result<()> {
 
     Ok(())
 }
+
+/// Opens the command database at its XDG location.
+fn open_repository() -> Result<SqliteCommandRepository> {
+    let db_path = XdgPaths::new()?.database_path()?;
+    Ok(SqliteCommandRepository::new(init_db(&db_path)?))
+}
+
+/// Rebuilds the database from every file in the configured alias roots, or
+/// that the rc files source.
+///
+/// Files are parsed in parallel and the results replace the table contents in
+/// a single transaction. Per-file errors are reported but do not abort the scan.
+fn handle_scan(config: &Config, repo: &dyn CommandRepository) -> Result<()> {
+    let Found { scan, unsourced } = find_files(config)?;
+    let outcomes = ParallelProcessor::new().process_scanned(&scan.files.iter().collect::<Vec<_>>());
+
+    let files: Vec<_> = outcomes.iter().map(|o| o.file.clone()).collect();
+    let commands: Vec<_> = outcomes.iter().flat_map(|o| o.commands.iter().cloned()).collect();
+    repo.replace_all(&files, &commands)?;
+
+    let aliases = commands.iter().filter(|c| c.cmd_type == CommandType::Alias).count();
+    let functions = commands.len() - aliases;
+    let errors: usize = outcomes.iter().map(|o| o.errors.len()).sum();
+    println!(
+        "Scanned {} files in {}: {} aliases, {} functions, {} errors",
+        scan.files.len(),
+        index_names(config)?,
+        aliases,
+        functions,
+        errors
+    );
+    report_errors(&scan.errors, &outcomes);
+    report_unsourced(config, &unsourced);
+
+    Ok(())
+}
+
+/// Brings the database in sync with the alias roots, or the files the rc
+/// files source, reparsing only the files that were added or whose content
+/// changed.
+///
+/// All changes are written in a single transaction. A file that cannot be
+/// read keeps its previously indexed commands rather than losing them.
+fn handle_update(config: &Config, repo: &dyn CommandRepository) -> Result<()> {
+    let Found { scan, unsourced } = find_files(config)?;
+    let changes = ChangeDetector::new(repo).detect(&scan)?;
+    let outcomes = apply_changes(repo, &changes)?;
+
+    println!(
+        "Updated {}: {} added, {} modified, {} deleted, {} unchanged",
+        index_names(config)?,
+        changes.added.len(),
+        changes.modified.len(),
+        changes.deleted.len(),
+        changes.unchanged + changes.touched.len()
+    );
+    report_errors(&scan.errors, &outcomes);
+    report_unsourced(config, &unsourced);
+
+    Ok(())
+}
+
+/// Prints every configured root with its settings and how many files are
+/// indexed from it.
+fn handle_path_list(config: &Config, repo: &dyn CommandRepository) -> Result<()> {
+    if config.roots.is_empty() {
+        println!("No alias roots configured; add one with `lscmd path add <dir>`");
+        return Ok(());
+    }
+
+    let files = repo.list_files()?;
+    for root in &config.roots {
+        let key = FileScanner::for_root(root)?.root_key();
+        let indexed = files.iter().filter(|f| f.root.as_deref() == Some(key.as_str())).count();
+        let mut line = match &root.label {
+            Some(label) => format!("{}: {}", label, root.path.display()),
+            None => root.path.display().to_string(),
+        };
+        line.push_str(&format!("  include={}", root.include.join(",")));
+        if !root.exclude.is_empty() {
+            line.push_str(&format!(" exclude={}", root.exclude.join(",")));
+        }
+        if let Some(depth) = root.max_depth {
+            line.push_str(&format!(" max-depth={}", depth));
+        }
+        println!("{}  ({} files)", line, indexed);
+    }
+    Ok(())
+}
+
+/// Adds `root` to the configuration and indexes only the files it claims,
+/// leaving the other roots' commands as they are.
+///
+/// When files are discovered from the rc files, the root is only saved:
+/// its files are indexed if something sources them.
+fn handle_path_add(config: &mut Config, repo: &dyn CommandRepository, root: AliasRoot) -> Result<()> {
+    let scanner = FileScanner::for_root(&root)?;
+    config.add_root(root)?;
+    if config.discovery == Discovery::Sources {
+        config.save()?;
+        println!(
+            "Added {}; its files are indexed if the rc files source them (see `lscmd sources`)",
+            scanner.alias_dir().display()
+        );
+        return Ok(());
+    }
+
+    // Files an earlier root already covers stay with it.
+    let (scan, outcomes) = index_root(repo, &scanners(config)?, &scanner.root_key())?;
+    config.save()?;
+
+    let commands: usize = outcomes.iter().map(|o| o.commands.len()).sum();
+    println!("Added {}: {} files, {} commands", scanner.alias_dir().display(), outcomes.len(), commands);
+    report_errors(&scan.errors, &outcomes);
+    Ok(())
+}
+
+/// Removes the root called `key` from the configuration and drops only the
+/// commands indexed from it. Its files that another root also covers are
+/// reindexed under that root.
+fn handle_path_remove(config: &mut Config, repo: &dyn CommandRepository, key: &str) -> Result<()> {
+    let root = config.remove_root(key)?;
+    let remaining = config.roots.iter().map(FileScanner::for_root).collect::<Result<Vec<_>>>()?;
+    let report = unindex_root(repo, &FileScanner::for_root(&root)?.root_key(), &remaining)?;
+    config.save()?;
+    println!(
+        "Removed {}: {} files dropped, {} now indexed from other roots",
+        root.name(),
+        report.changes.deleted.len(),
+        report.changes.modified.len()
+    );
+    report_errors(&[], &report.outcomes);
+    Ok(())
+}
+
+/// Builds a scanner for every configured root, in order.
+fn scanners(config: &Config) -> Result<Vec<FileScanner>> {
+    if config.roots.is_empty() {
+        return Err(LscmdError::Config("No alias roots configured; add one with `lscmd path add <dir>`".to_string()));
+    }
+    config.roots.iter().map(FileScanner::for_root).collect()
+}
+
+/// Names the roots for status lines, e.g. `team, ~/.alias`.
+fn root_names(roots: &[AliasRoot]) -> String {
+    roots.iter().map(AliasRoot::name).collect::<Vec<_>>().join(", ")
+}
+
+/// Names where files are indexed from for status lines: the roots, or the
+/// rc files whose sources are followed.
+fn index_names(config: &Config) -> Result<String> {
+    Ok(match config.discovery {
+        Discovery::Roots => root_names(&config.roots),
+        Discovery::Sources => path_names(SourceDiscovery::from_config(config)?.rc_files()),
+    })
+}
+
+fn path_names(paths: &[PathBuf]) -> String {
+    paths.iter().map(|p| p.display().to_string()).collect::<Vec<_>>().join(", ")
+}
+
+/// The files to index, and the files in the alias roots that were left out
+/// because nothing sources them.
+struct Found {
+    scan: ScanResult,
+    unsourced: Vec<PathBuf>,
+}
+
+/// Finds the files to index as `config.discovery` says: every matching file
+/// in the alias roots, or the rc files and whatever they source.
+fn find_files(config: &Config) -> Result<Found> {
+    match config.discovery {
+        Discovery::Roots => Ok(Found {
+            scan: scan_roots(&scanners(config)?)?,
+            unsourced: Vec::new(),
+        }),
+        Discovery::Sources => {
+            let order = SourceDiscovery::from_config(config)?.discover();
+            Ok(Found {
+                scan: order.scan(),
+                unsourced: unsourced_files(config, &order),
+            })
+        }
+    }
+}
+
+/// The files in the alias roots that nothing in `order` sources. Roots that
+/// do not exist are skipped, since the rc files decide what is indexed.
+fn unsourced_files(config: &Config, order: &LoadOrder) -> Vec<PathBuf> {
+    let scanners: Vec<_> = config
+        .roots
+        .iter()
+        .filter_map(|root| FileScanner::for_root(root).ok())
+        .filter(|scanner| scanner.alias_dir().is_dir())
+        .collect();
+    let scan = scan_roots(&scanners).unwrap_or_default();
+    order.unsourced(&scan).into_iter().map(|file| file.path.clone()).collect()
+}
+
+fn report_unsourced(config: &Config, unsourced: &[PathBuf]) {
+    if !unsourced.is_empty() {
+        eprintln!(
+            "  warning: {} files in {} are not sourced by anything; see `lscmd sources`",
+            unsourced.len(),
+            root_names(&config.roots)
+        );
+    }
+}
+
+/// Follows the rc files' `source` statements and prints the load order, the
+/// statements that were not followed and the alias root files nothing
+/// sources.
+///
+/// `rc_files`, if any, replace the configured ones. With `discovery`, both
+/// are saved to the configuration first.
+fn handle_sources(config: &mut Config, rc_files: Vec<PathBuf>, discovery: Option<Discovery>) -> Result<()> {
+    if !rc_files.is_empty() {
+        config.rc_files = rc_files.into_iter().map(std::path::absolute).collect::<std::io::Result<_>>()?;
+    }
+    if let Some(discovery) = discovery {
+        config.discovery = discovery;
+        config.save()?;
+        let from = match discovery {
+            Discovery::Roots => "the alias roots",
+            Discovery::Sources => "what the rc files source",
+        };
+        println!("Indexing {}; run `lscmd scan` to reindex", from);
+    }
+
+    let discovery = SourceDiscovery::from_config(config)?;
+    let order = discovery.discover();
+    if order.files.is_empty() {
+        println!("None of the rc files exist: {}", path_names(discovery.rc_files()));
+        return Ok(());
+    }
+
+    println!("Load order:");
+    for (index, file) in order.files.iter().enumerate() {
+        let site = file
+            .sourced_from
+            .as_ref()
+            .map(|site| format!("  ({}:{})", site.file.display(), site.line))
+            .unwrap_or_default();
+        println!("{:>4}  {}{}{}", index + 1, "  ".repeat(file.depth), file.path.display(), site);
+    }
+
+    if !order.skipped.is_empty() {
+        println!("Not followed:");
+        for skipped in &order.skipped {
+            let reason = match skipped.reason {
+                SkipReason::Missing => "not found",
+                SkipReason::Unexpanded => "cannot be expanded without running the shell",
+            };
+            println!("  {}: {}  ({}:{})", skipped.argument, reason, skipped.site.file.display(), skipped.site.line);
+        }
+    }
+
+    let unsourced = unsourced_files(config, &order);
+    if !unsourced.is_empty() {
+        println!("Not sourced by anything in {}:", root_names(&config.roots));
+        for path in unsourced {
+            println!("  {}", path.display());
+        }
+    }
+    Ok(())
+}
+
+/// Brings the database in sync, then keeps reindexing files as they change
+/// until interrupted. Each debounced batch of changes is written in a single
+/// transaction.
+fn handle_watch(config: &Config, repo: &dyn CommandRepository) -> Result<()> {
+    // Watch before updating, so nothing saved in between is missed.
+    let mut watcher = file_watcher(config)?;
+    handle_update(config, repo)?;
+    println!("Watching {} for changes (Ctrl-C to stop)", index_names(config)?);
+
+    loop {
+        let Some(report) = watcher.sync(repo, Duration::from_secs(60))? else {
+            continue;
+        };
+        let changes = &report.changes;
+        println!(
+            "Updated {}: {} added, {} modified, {} deleted",
+            index_names(config)?,
+            changes.added.len(),
+            changes.modified.len(),
+            changes.deleted.len()
+        );
+        report_errors(&[], &report.outcomes);
+    }
+}
+
+/// Starts watching the alias roots, or the files the rc files source.
+fn file_watcher(config: &Config) -> Result<FileWatcher> {
+    match config.discovery {
+        Discovery::Roots => FileWatcher::new(&config.roots, DEFAULT_DEBOUNCE),
+        Discovery::Sources => FileWatcher::for_sources(SourceDiscovery::from_config(config)?, DEFAULT_DEBOUNCE),
+    }
+}
+
+/// Starts watching the indexed files for the TUI, so its lists refresh as
+/// files are saved. If a root cannot be watched the TUI still runs, just
+/// without live updates.
+fn tui_watcher() -> Option<FileWatcher> {
+    let config = Config::load().ok()?;
+    file_watcher(&config).ok()
+}
+
+/// Prints every definition of `name`, the one the shell uses first.
+fn handle_show(repo: &dyn CommandRepository, name: &str, format: OutputFormat) -> Result<()> {
+    let winner = find_command(repo, name)?;
+    let definitions: Vec<_> = repo
+        .get_definitions(name)?
+        .into_iter()
+        .filter(|c| c.name == winner.name)
+        .collect();
+    let active = definitions
+        .iter()
+        .position(|c| is_same_definition(c, &winner))
+        .unwrap_or_default();
+
+    let out = match format {
+        OutputFormat::Table => DisplayFormatter::from_env().format_definitions(&definitions, active),
+        OutputFormat::Plain => format!("{}\n", winner.body()),
+        format => Records::definitions(&definitions, active).format(format)?,
+    };
+    print!("{}", out);
+    Ok(())
+}
+
+/// Browses every command in the TUI, or prints them in sourcing order when
+/// a format is given or not attached to a terminal.
+fn handle_list(repo: &dyn CommandRepository, type_filter: Option<CommandType>, format: Option<OutputFormat>) -> Result<()> {
+    if format.is_none() && is_interactive() {
+        let mut app = App::new(repo, SearchEngine::new(false).lenient(true), type_filter)?;
+        return tui::run(&mut app, tui_watcher());
+    }
+
+    let commands = repo.list_commands(None, type_filter)?;
+    let out = match format.unwrap_or_default() {
+        OutputFormat::Table => {
+            let results: Vec<_> = commands
+                .into_iter()
+                .map(|command| SearchResult { command, score: 0, matches: Vec::new() })
+                .collect();
+            DisplayFormatter::from_env().format_search_results(&results)
+        }
+        format => Records::listed(&commands).format(format)?,
+    };
+    print!("{}", out);
+    Ok(())
+}
+
+/// Prints every command's name in `columns` columns, grouped by file.
+fn handle_list_columns(repo: &dyn CommandRepository, type_filter: Option<CommandType>, columns: usize) -> Result<()> {
+    let commands = repo.list_commands(None, type_filter)?;
+    print!("{}", DisplayFormatter::from_env().format_columns(&commands, columns, terminal_width()));
+    Ok(())
+}
+
+/// Opens the TUI on the results for `query`, or prints them, most relevant
+/// first, when a format is given or not attached to a terminal.
+fn handle_search(
+    repo: &dyn CommandRepository,
+    query: &str,
+    mode: SearchMode,
+    type_filter: Option<CommandType>,
+    format: Option<OutputFormat>,
+) -> Result<()> {
+    if format.is_none() && is_interactive() {
+        let mut app = App::new(repo, SearchEngine::with_mode(mode).lenient(true), type_filter)?;
+        app.start_search(query)?;
+        return tui::run(&mut app, tui_watcher());
+    }
+
+    let results = repo.search_commands(&SearchEngine::with_mode(mode), query, type_filter)?;
+    let out = match format.unwrap_or_default() {
+        OutputFormat::Table if results.is_empty() => {
+            eprintln!("No commands match '{}'", query);
+            return Ok(());
+        }
+        OutputFormat::Table => DisplayFormatter::from_env().format_search_results(&results),
+        // Machine formats stay parseable with no results: an empty list, or just a header.
+        format => Records::scored(&results).format(format)?,
+    };
+    print!("{}", out);
+    Ok(())
+}
+
+/// Lets the user pick a command in the TUI, drawn on the terminal itself,
+/// and prints its name, or its body, as the only output. Returns whether a
+/// command was picked.
+fn handle_pick(
+    repo: &dyn CommandRepository,
+    query: Option<&str>,
+    body: bool,
+    mode: SearchMode,
+    type_filter: Option<CommandType>,
+) -> Result<bool> {
+    let mut app = App::new(repo, SearchEngine::with_mode(mode).lenient(true), type_filter)?.picking(true);
+    if let Some(query) = query {
+        app.start_search(query)?;
+    }
+    let Some(picked) = tui::pick(&mut app)? else {
+        return Ok(false);
+    };
+
+    match body {
+        true => println!("{}", picked.body()),
+        false => println!("{}", picked.name),
+    }
+    Ok(true)
+}
+
+fn search_mode(regex: bool, fuzzy: bool) -> SearchMode {
+    match (regex, fuzzy) {
+        (true, _) => SearchMode::Regex,
+        (_, true) => SearchMode::Fuzzy,
+        _ => SearchMode::Query,
+    }
+}
+
+/// Whether both stdin and stdout are terminals, so the TUI can run.
+fn is_interactive() -> bool {
+    std::io::stdin().is_terminal() && std::io::stdout().is_terminal()
+}
+
+/// Looks up the effective definition of `name`, suggesting similar names
+/// when there is none.
+fn find_command(repo: &dyn CommandRepository, name: &str) -> Result<Command> {
+    if let Some(command) = repo.get_command_by_name(name)? {
+        return Ok(command);
+    }
+
+    let names = repo.list_names()?;
+    let suggestions = NameSuggester::default().suggest_names(name, names.iter().map(String::as_str));
+    Err(LscmdError::CommandNotFound {
+        name: name.to_string(),
+        suggestions,
+    })
+}
+
+/// Opens the active definition of `name` in the user's editor, then reindexes
+/// its file so the database reflects the edit straight away.
+fn handle_edit(repo: &dyn CommandRepository, name: &str) -> Result<()> {
+    let command = find_command(repo, name)?;
+    let path = Path::new(&command.path);
+    Editor::from_env().open(path, command.start_line)?;
+    reindex_file(repo, path)
+}
+
+/// Reparses a single file the same way `lscmd update` would, removing it
+/// from the database if it no longer exists.
+fn reindex_file(repo: &dyn CommandRepository, path: &Path) -> Result<()> {
+    if !path.exists() {
+        let removed = repo.delete_by_path(&path.to_string_lossy())?;
+        println!("Removed {}: {} commands", path.display(), removed);
+        return Ok(());
+    }
+
+    let outcomes = ParallelProcessor::new().process_files(&[path]);
+    for outcome in &outcomes {
+        if outcome.errors.iter().any(|e| matches!(e, LscmdError::Io(_))) {
+            continue;
+        }
+        repo.replace_file(&outcome.file, &outcome.commands)?;
+        println!("Updated {}: {} commands", path.display(), outcome.commands.len());
+    }
+    report_errors(&[], &outcomes);
+    Ok(())
+}
+
+/// Prints every name defined more than once, marking which definition wins.
+fn handle_conflicts(repo: &dyn CommandRepository) -> Result<()> {
+    let conflicts = repo.list_conflicts()?;
+    if conflicts.is_empty() {
+        println!("No conflicting definitions");
+        return Ok(());
+    }
+
+    for definitions in &conflicts {
+        let Some(winner) = effective_definition(definitions) else {
+            continue;
+        };
+        println!("{}: {} definitions", definitions[winner].name, definitions.len());
+        for (index, command) in definitions.iter().enumerate() {
+            let status = if index == winner { "active" } else { "shadowed" };
+            println!("  [{}] {} {}:{}", status, command.cmd_type, command.path, command.start_line);
+        }
+    }
+    println!("{} shadowed names", conflicts.len());
+    Ok(())
+}
+
+/// Returns whether two commands are the same definition in the same place.
+fn is_same_definition(a: &Command, b: &Command) -> bool {
+    a.name == b.name && a.path == b.path && a.start_line == b.start_line
+}
+
+/// Prints scan warnings and per-file parse errors to stderr.
+fn report_errors(scan_errors: &[LscmdError], outcomes: &[FileOutcome]) {
+    for error in scan_errors {
+        eprintln!("  warning: {}", error);
+    }
+    for outcome in outcomes.iter().filter(|o| !o.errors.is_empty()) {
+        eprintln!("  {}:", outcome.path.display());
+        for error in &outcome.errors {
+            eprintln!("    {}", error);
+        }
+    }
+}
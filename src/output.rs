@@ -0,0 +1,195 @@
+use crate::database::Command;
+use crate::error::Result;
+use crate::search::SearchResult;
+use clap::ValueEnum;
+use serde::Serialize;
+
+/// The version of the JSON layout written by `--format json` and `jsonl`.
+///
+/// Each command is an object with the fields of `Command`: `name`,
+/// `cmd_type`, `path`, `start_line`, `end_line`, `byte_offset`, `code`,
+/// `description`, `file_mtime`, `created_at` and `root`. `search` adds the `score`
+/// of each result and `show` adds whether each definition is `active`.
+///
+/// The version is bumped whenever a field is renamed, removed or changes
+/// meaning; new fields may be added without bumping it.
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
+/// How `list`, `search` and `show` print commands when not in the TUI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum OutputFormat {
+    /// Aligned columns for people, colored on a terminal
+    #[default]
+    Table,
+    /// Names only, one per line; for `show`, the active definition's body
+    Plain,
+    /// One JSON document, `{"schema_version": 1, "commands": [...]}`
+    Json,
+    /// One JSON object per command and line, each with its `schema_version`
+    Jsonl,
+    /// Tab-separated values with a header row; tabs, newlines and backslashes are escaped
+    Tsv,
+    /// Comma-separated values with a header row, quoted as in RFC 4180
+    Csv,
+}
+
+/// A command as written to machine-readable output, with what the
+/// subcommand knows about it besides the definition itself.
+#[derive(Debug, Clone, Serialize)]
+struct Record<'a> {
+    #[serde(flatten)]
+    command: &'a Command,
+    /// The relevance score, for search results.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    score: Option<u32>,
+    /// Whether the shell uses this definition, for `show`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    active: Option<bool>,
+}
+
+/// The column a subcommand adds to the command fields.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Extra {
+    None,
+    Score,
+    Active,
+}
+
+/// The commands printed by one subcommand, ready to format.
+#[derive(Debug, Clone)]
+pub struct Records<'a> {
+    records: Vec<Record<'a>>,
+    extra: Extra,
+}
+
+#[derive(Serialize)]
+struct Document<'a> {
+    schema_version: u32,
+    commands: &'a [Record<'a>],
+}
+
+#[derive(Serialize)]
+struct Line<'a> {
+    schema_version: u32,
+    #[serde(flatten)]
+    record: &'a Record<'a>,
+}
+
+impl<'a> Records<'a> {
+    /// Listed commands, as they are.
+    pub fn listed(commands: &'a [Command]) -> Self {
+        let records = commands.iter().map(|command| Record { command, score: None, active: None }).collect();
+        Self { records, extra: Extra::None }
+    }
+
+    /// Search results, with their scores.
+    pub fn scored(results: &'a [SearchResult]) -> Self {
+        let records = results
+            .iter()
+            .map(|result| Record { command: &result.command, score: Some(result.score), active: None })
+            .collect();
+        Self { records, extra: Extra::Score }
+    }
+
+    /// Every definition of a name, the active one first, as `lscmd show`
+    /// lists them.
+    pub fn definitions(definitions: &'a [Command], active: usize) -> Self {
+        let order = (active < definitions.len())
+            .then_some(active)
+            .into_iter()
+            .chain((0..definitions.len()).filter(|&index| index != active));
+        let records = order
+            .map(|index| Record { command: &definitions[index], score: None, active: Some(index == active) })
+            .collect();
+        Self { records, extra: Extra::Active }
+    }
+
+    /// Formats the commands in any format but `Table`, which is left to
+    /// `DisplayFormatter`; asked for it, this writes `Plain` instead.
+    pub fn format(&self, format: OutputFormat) -> Result<String> {
+        let mut out = String::new();
+        match format {
+            OutputFormat::Table | OutputFormat::Plain => {
+                for record in &self.records {
+                    out.push_str(&record.command.name);
+                    out.push('\n');
+                }
+            }
+            OutputFormat::Json => {
+                let document = Document { schema_version: JSON_SCHEMA_VERSION, commands: &self.records };
+                out = serde_json::to_string_pretty(&document)?;
+                out.push('\n');
+            }
+            OutputFormat::Jsonl => {
+                for record in &self.records {
+                    out.push_str(&serde_json::to_string(&Line { schema_version: JSON_SCHEMA_VERSION, record })?);
+                    out.push('\n');
+                }
+            }
+            OutputFormat::Tsv => self.write_rows(&mut out, '\t', escape_tsv),
+            OutputFormat::Csv => self.write_rows(&mut out, ',', quote_csv),
+        }
+        Ok(out)
+    }
+
+    /// Writes a header and one row per command.
+    fn write_rows(&self, out: &mut String, separator: char, escape: fn(&str) -> String) {
+        let mut header = vec!["name", "type", "path", "start_line", "end_line", "description", "code"];
+        match self.extra {
+            Extra::None => {}
+            Extra::Score => header.push("score"),
+            Extra::Active => header.push("active"),
+        }
+        push_row(out, header.into_iter().map(String::from), separator);
+
+        for record in &self.records {
+            let command = record.command;
+            let mut row = vec![
+                escape(&command.name),
+                command.cmd_type.to_string(),
+                escape(&command.path),
+                command.start_line.to_string(),
+                command.end_line.to_string(),
+                escape(command.description.as_deref().unwrap_or_default()),
+                escape(&command.code),
+            ];
+            row.extend(record.score.map(|score| score.to_string()));
+            row.extend(record.active.map(|active| active.to_string()));
+            push_row(out, row.into_iter(), separator);
+        }
+    }
+}
+
+fn push_row(out: &mut String, fields: impl Iterator<Item = String>, separator: char) {
+    for (index, field) in fields.enumerate() {
+        if index > 0 {
+            out.push(separator);
+        }
+        out.push_str(&field);
+    }
+    out.push('\n');
+}
+
+/// Escapes the characters TSV cannot hold as `\t`, `\n`, `\r` and `\\`.
+fn escape_tsv(field: &str) -> String {
+    let mut escaped = String::with_capacity(field.len());
+    for c in field.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Quotes a CSV field if it contains a separator, quote or line break.
+fn quote_csv(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
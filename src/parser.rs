@@ -1,2 +1,529 @@
-// Shell script parsing logic placeholder
-// Will be implemented in Phase 3
\ No newline at end of file
+use crate::database::{Command, CommandType, FileRecord};
+use crate::error::{LscmdError, Result};
+use crate::security::InputValidator;
+use crate::utils::file_scanner::{content_hash, mtime_secs};
+use aho_corasick::AhoCorasick;
+use regex::Regex;
+use std::fs;
+use std::path::Path;
+
+/// The outcome of parsing a single shell file.
+///
+/// A malformed definition never aborts the whole file: it is recorded in
+/// `errors` as an `LscmdError::Parse` and parsing resumes on the next line.
+#[derive(Debug)]
+pub struct ParseResult {
+    /// The aliases and functions that were extracted successfully.
+    pub commands: Vec<Command>,
+    /// One `LscmdError::Parse` per definition that could not be extracted.
+    pub errors: Vec<LscmdError>,
+    /// The `files` table entry describing the source and this outcome.
+    pub file: FileRecord,
+}
+
+/// Extracts alias and function definitions from shell scripts.
+///
+/// Candidate lines are found with a single Aho-Corasick pass over the
+/// `alias`, `function` and `(` markers; only those lines are then matched
+/// against the detailed regexes. Function bodies are delimited with a small
+/// shell-aware lexer so that braces inside quotes, comments and heredocs do
+/// not end the body early.
+#[derive(Debug)]
+pub struct ShellParser {
+    prefilter: AhoCorasick,
+    alias_re: Regex,
+    function_keyword_re: Regex,
+    function_posix_re: Regex,
+}
+
+impl Default for ShellParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ShellParser {
+    /// Creates a new `ShellParser` with its patterns compiled.
+    pub fn new() -> Self {
+        Self {
+            prefilter: AhoCorasick::new(["alias", "function", "("])
+                .expect("static prefilter patterns are valid"),
+            // `alias [-flags] name=value`
+            alias_re: Regex::new(r"^\s*alias\s+(?:-[A-Za-z]+\s+)*([^\s=]+)=")
+                .expect("static alias regex is valid"),
+            // `function name`, `function name()`
+            function_keyword_re: Regex::new(
+                r"^\s*function\s+([A-Za-z0-9_][A-Za-z0-9_.:+@-]*)\s*(?:\(\s*\))?",
+            )
+            .expect("static function regex is valid"),
+            // `name()`
+            function_posix_re: Regex::new(r"^\s*([A-Za-z0-9_][A-Za-z0-9_.:+@-]*)\s*\(\s*\)")
+                .expect("static function regex is valid"),
+        }
+    }
+
+    /// Parses the shell file at `path`.
+    ///
+    /// The file's modification time is stored on every extracted command.
+    /// Invalid UTF-8 sequences are replaced rather than rejected.
+    ///
+    /// # Errors
+    ///
+    /// Returns `LscmdError::Io` if the file or its metadata cannot be read.
+    /// Problems with individual definitions are reported in
+    /// `ParseResult::errors` instead.
+    pub fn parse_file(&self, path: &Path) -> Result<ParseResult> {
+        let file_mtime = mtime_secs(&fs::metadata(path)?);
+        let bytes = fs::read(path)?;
+        let content = String::from_utf8_lossy(&bytes);
+
+        Ok(self.parse_source(&content, &path.to_string_lossy(), file_mtime, &bytes))
+    }
+
+    /// Parses shell source that has already been read into memory.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The shell source.
+    /// * `path` - The path recorded on each command and in parse errors.
+    /// * `file_mtime` - The modification time recorded on each command.
+    pub fn parse_str(&self, content: &str, path: &str, file_mtime: i64) -> ParseResult {
+        self.parse_source(content, path, file_mtime, content.as_bytes())
+    }
+
+    /// Parses `content`, describing the file by its raw `bytes`.
+    fn parse_source(&self, content: &str, path: &str, file_mtime: i64, bytes: &[u8]) -> ParseResult {
+        let lines: Vec<&str> = content.lines().collect();
+        // Taken from the raw bytes: each invalid sequence in `content` has
+        // been replaced by a U+FFFD of a different length.
+        let offsets = line_offsets(bytes);
+        let mut commands = Vec::new();
+        let mut errors = Vec::new();
+        // Tracks heredocs and multi-line strings between definitions so that
+        // their contents are never mistaken for definitions.
+        let mut lexer = ShellLexer::default();
+        let mut idx = 0;
+
+        while idx < lines.len() {
+            let line = lines[idx];
+
+            if lexer.is_pending() || !self.prefilter.is_match(line) {
+                lexer.feed(line, 0);
+                idx += 1;
+                continue;
+            }
+
+            match self.parse_definition(&lines, idx) {
+                Some(Ok(def)) => {
+                    let description = describe(&lines, idx, def.comment);
+                    let command = Command {
+                        name: def.name,
+                        cmd_type: def.cmd_type,
+                        path: path.to_string(),
+                        start_line: idx + 1,
+                        end_line: def.end_line + 1,
+                        byte_offset: offsets[idx] as u64,
+                        code: def.code,
+                        description,
+                        file_mtime,
+                        created_at: None,
+                        root: None,
+                    };
+                    match InputValidator::validate_command_name(&command.name)
+                        .and_then(|_| command.validate())
+                    {
+                        Ok(()) => commands.push(command),
+                        Err(e) => errors.push(parse_error(path, idx + 1, e.to_string())),
+                    }
+                    idx = def.end_line + 1;
+                }
+                Some(Err(message)) => {
+                    // Resume right after the offending line with a fresh lexer
+                    // so one broken quote cannot swallow the rest of the file.
+                    errors.push(parse_error(path, idx + 1, message));
+                    lexer = ShellLexer::default();
+                    idx += 1;
+                }
+                None => {
+                    lexer.feed(line, 0);
+                    idx += 1;
+                }
+            }
+        }
+
+        let file = FileRecord::from_parse(
+            path,
+            file_mtime,
+            bytes.len() as u64,
+            content_hash(bytes),
+            commands.len(),
+            &errors,
+        );
+        ParseResult { commands, errors, file }
+    }
+
+    /// Attempts to parse a definition starting at `lines[idx]`.
+    ///
+    /// Returns `None` if the line does not start a definition.
+    fn parse_definition(
+        &self,
+        lines: &[&str],
+        idx: usize,
+    ) -> Option<std::result::Result<Definition, String>> {
+        let line = lines[idx];
+
+        if let Some(caps) = self.alias_re.captures(line) {
+            let name = caps[1].to_string();
+            let value_start = caps.get(0).map_or(0, |m| m.end());
+            return Some(parse_alias(lines, idx, value_start, name));
+        }
+
+        let caps = self
+            .function_keyword_re
+            .captures(line)
+            .or_else(|| self.function_posix_re.captures(line))?;
+        let name = caps[1].to_string();
+        let header_end = caps.get(0).map_or(0, |m| m.end());
+        Some(parse_function(lines, idx, header_end, name))
+    }
+}
+
+/// A definition extracted from the source, before it becomes a `Command`.
+struct Definition {
+    name: String,
+    cmd_type: CommandType,
+    code: String,
+    end_line: usize,
+    /// A trailing inline comment on the definition line, if any.
+    comment: Option<String>,
+}
+
+fn parse_error(file: &str, line: usize, message: String) -> LscmdError {
+    LscmdError::Parse {
+        file: file.to_string(),
+        line,
+        message,
+    }
+}
+
+/// Builds a definition's description from the contiguous block of `#`
+/// comments directly above `lines[idx]`, followed by its inline `comment`.
+///
+/// Shebangs and decoration-only lines such as `#####` are left out.
+fn describe(lines: &[&str], idx: usize, comment: Option<String>) -> Option<String> {
+    let block_start = lines[..idx]
+        .iter()
+        .rposition(|line| {
+            let line = line.trim_start();
+            !line.starts_with('#') || line.starts_with("#!")
+        })
+        .map_or(0, |i| i + 1);
+
+    let description: Vec<String> = lines[block_start..idx]
+        .iter()
+        .map(|line| line.trim().trim_start_matches('#').trim().to_string())
+        .filter(|line| !line.is_empty())
+        .chain(comment)
+        .collect();
+    (!description.is_empty()).then(|| description.join("\n"))
+}
+
+/// Returns the byte offset at which each line of `bytes` starts, matching
+/// the lines yielded by `str::lines` on its (lossily) decoded text, since
+/// decoding never touches a `\n`.
+fn line_offsets(bytes: &[u8]) -> Vec<usize> {
+    std::iter::once(0)
+        .chain(bytes.iter().enumerate().filter(|(_, &b)| b == b'\n').map(|(i, _)| i + 1))
+        .collect()
+}
+
+/// Reads the (possibly quoted, possibly multi-line) value of an alias.
+fn parse_alias(
+    lines: &[&str],
+    idx: usize,
+    value_start: usize,
+    name: String,
+) -> std::result::Result<Definition, String> {
+    let mut value = String::new();
+    let mut quote: Option<char> = None;
+    let mut line_idx = idx;
+    let mut chars = lines[idx][value_start..].chars().peekable();
+
+    loop {
+        let Some(c) = chars.next() else {
+            if quote.is_none() {
+                break;
+            }
+            // The quoted value continues on the next line.
+            line_idx += 1;
+            if line_idx >= lines.len() {
+                return Err(format!("unterminated quote in alias '{}'", name));
+            }
+            value.push('\n');
+            chars = lines[line_idx].chars().peekable();
+            continue;
+        };
+
+        match (quote, c) {
+            (None, c) if c.is_whitespace() || matches!(c, ';' | '&' | '|') => break,
+            (None, '\'') | (None, '"') => quote = Some(c),
+            (None, '\\') => {
+                if let Some(next) = chars.next() {
+                    value.push(next);
+                }
+            }
+            (Some('\''), '\'') => quote = None,
+            (Some('"'), '"') => quote = None,
+            (Some('"'), '\\') => match chars.peek() {
+                Some(&next) if matches!(next, '"' | '\\' | '$' | '`') => {
+                    value.push(next);
+                    chars.next();
+                }
+                _ => value.push('\\'),
+            },
+            (_, c) => value.push(c),
+        }
+    }
+
+    if value.is_empty() {
+        return Err(format!("alias '{}' has an empty value", name));
+    }
+
+    // A trailing `# ...` right after the value documents the alias.
+    let rest: String = chars.collect();
+    let comment = rest
+        .trim_start_matches(|c: char| c.is_whitespace() || c == ';')
+        .strip_prefix('#')
+        .map(|comment| comment.trim().to_string())
+        .filter(|comment| !comment.is_empty());
+
+    Ok(Definition {
+        name,
+        cmd_type: CommandType::Alias,
+        code: value,
+        end_line: line_idx,
+        comment,
+    })
+}
+
+/// Locates the brace-delimited body of a function and extracts it.
+fn parse_function(
+    lines: &[&str],
+    idx: usize,
+    header_end: usize,
+    name: String,
+) -> std::result::Result<Definition, String> {
+    let rest = &lines[idx][header_end..];
+    let trimmed = rest.trim_start();
+
+    let (open_line, open_col) = if trimmed.starts_with('{') {
+        (idx, header_end + (rest.len() - trimmed.len()))
+    } else if trimmed.is_empty() || trimmed.starts_with('#') {
+        // The opening brace may be on a following line.
+        let next = (idx + 1..lines.len())
+            .find(|&i| !lines[i].trim().is_empty())
+            .filter(|&i| lines[i].trim_start().starts_with('{'))
+            .ok_or_else(|| format!("expected '{{' to open the body of function '{}'", name))?;
+        (next, lines[next].len() - lines[next].trim_start().len())
+    } else {
+        return Err(format!(
+            "unsupported body for function '{}': expected '{{'",
+            name
+        ));
+    };
+
+    let mut lexer = ShellLexer::default();
+    for (close_line, line) in lines.iter().enumerate().skip(open_line) {
+        let start = if close_line == open_line { open_col } else { 0 };
+        if let Some(close_col) = lexer.feed(line, start) {
+            let raw = if close_line == open_line {
+                line[open_col + 1..close_col].to_string()
+            } else {
+                let mut parts = vec![&lines[open_line][open_col + 1..]];
+                parts.extend(&lines[open_line + 1..close_line]);
+                parts.push(&line[..close_col]);
+                parts.join("\n")
+            };
+
+            return Ok(Definition {
+                name,
+                cmd_type: CommandType::Function,
+                code: normalize_body(&raw),
+                end_line: close_line,
+                comment: None,
+            });
+        }
+    }
+
+    Err(format!(
+        "unterminated body for function '{}': missing '}}'",
+        name
+    ))
+}
+
+/// Drops blank lines around a function body and the padding after `{`.
+fn normalize_body(raw: &str) -> String {
+    let mut body = raw.trim_start_matches([' ', '\t']).trim_end();
+    while let Some((first, rest)) = body.split_once('\n') {
+        if !first.trim().is_empty() {
+            break;
+        }
+        body = rest;
+    }
+    body.to_string()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Quote {
+    Single,
+    Double,
+    AnsiC,
+}
+
+#[derive(Debug, Clone)]
+struct Heredoc {
+    delimiter: String,
+    strip_tabs: bool,
+}
+
+/// A line-oriented shell lexer that tracks just enough state to balance braces.
+///
+/// It understands single, double and `$'...'` quotes, backslash escapes,
+/// `#` comments, `$(( ))` arithmetic and heredocs. Quote state carries over
+/// between lines; heredoc bodies are skipped until their delimiter line.
+#[derive(Debug, Default)]
+struct ShellLexer {
+    quote: Option<Quote>,
+    heredocs: Vec<Heredoc>,
+    arithmetic: usize,
+    depth: usize,
+}
+
+impl ShellLexer {
+    /// Whether the next line continues a string or heredoc body.
+    fn is_pending(&self) -> bool {
+        self.quote.is_some() || !self.heredocs.is_empty()
+    }
+
+    /// Lexes `line` from byte offset `start`.
+    ///
+    /// Returns the byte offset of the `}` that closes the outermost brace
+    /// opened since this lexer was created, if it is on this line.
+    fn feed(&mut self, line: &str, start: usize) -> Option<usize> {
+        if let Some(heredoc) = self.heredocs.first() {
+            let candidate = if heredoc.strip_tabs {
+                line.trim_start_matches('\t')
+            } else {
+                line
+            };
+            if candidate == heredoc.delimiter {
+                self.heredocs.remove(0);
+            }
+            return None;
+        }
+
+        let bytes = line.as_bytes();
+        let mut i = start;
+        while i < bytes.len() {
+            let c = bytes[i];
+            match self.quote {
+                Some(Quote::Single) => {
+                    if c == b'\'' {
+                        self.quote = None;
+                    }
+                }
+                Some(Quote::Double) | Some(Quote::AnsiC) => {
+                    if c == b'\\' {
+                        i += 1;
+                    } else if (c == b'"' && self.quote == Some(Quote::Double))
+                        || (c == b'\'' && self.quote == Some(Quote::AnsiC))
+                    {
+                        self.quote = None;
+                    }
+                }
+                None => match c {
+                    b'\\' => i += 1,
+                    b'\'' => self.quote = Some(Quote::Single),
+                    b'"' => self.quote = Some(Quote::Double),
+                    b'$' if bytes.get(i + 1) == Some(&b'\'') => {
+                        self.quote = Some(Quote::AnsiC);
+                        i += 1;
+                    }
+                    b'#' if i == 0 || is_word_boundary(bytes[i - 1]) => break,
+                    b'(' if bytes.get(i + 1) == Some(&b'(') => {
+                        self.arithmetic += 1;
+                        i += 1;
+                    }
+                    b')' if bytes.get(i + 1) == Some(&b')') && self.arithmetic > 0 => {
+                        self.arithmetic -= 1;
+                        i += 1;
+                    }
+                    b'<' if bytes.get(i + 1) == Some(&b'<') && self.arithmetic == 0 => {
+                        if bytes.get(i + 2) == Some(&b'<') {
+                            // Herestring `<<<`: nothing to skip on later lines.
+                            i += 2;
+                        } else {
+                            i = self.read_heredoc(line, i + 2);
+                            continue;
+                        }
+                    }
+                    b'{' => self.depth += 1,
+                    b'}' if self.depth > 0 => {
+                        self.depth -= 1;
+                        if self.depth == 0 {
+                            return Some(i);
+                        }
+                    }
+                    _ => {}
+                },
+            }
+            i += 1;
+        }
+
+        None
+    }
+
+    /// Reads a heredoc delimiter starting right after `<<` and queues it.
+    ///
+    /// Returns the byte offset just past the delimiter.
+    fn read_heredoc(&mut self, line: &str, start: usize) -> usize {
+        let bytes = line.as_bytes();
+        let mut i = start;
+        let strip_tabs = bytes.get(i) == Some(&b'-');
+        if strip_tabs {
+            i += 1;
+        }
+        while i < bytes.len() && (bytes[i] == b' ' || bytes[i] == b'\t') {
+            i += 1;
+        }
+
+        // Collected as bytes so that multi-byte characters stay intact.
+        let mut delimiter = Vec::new();
+        let mut quote: Option<u8> = None;
+        while i < bytes.len() {
+            let c = bytes[i];
+            match quote {
+                Some(q) if c == q => quote = None,
+                Some(_) => delimiter.push(c),
+                None if c == b'\'' || c == b'"' => quote = Some(c),
+                None if c == b'\\' => {}
+                None if c.is_ascii_whitespace() || b";&|<>()".contains(&c) => break,
+                None => delimiter.push(c),
+            }
+            i += 1;
+        }
+
+        if !delimiter.is_empty() {
+            self.heredocs.push(Heredoc {
+                delimiter: String::from_utf8_lossy(&delimiter).into_owned(),
+                strip_tabs,
+            });
+        }
+        i
+    }
+}
+
+/// Whether a `#` following this byte starts a comment.
+fn is_word_boundary(prev: u8) -> bool {
+    prev.is_ascii_whitespace() || b";&|()".contains(&prev)
+}
@@ -1,14 +1,104 @@
-use crate::database::Command;
+use crate::database::{Command, CommandType};
 use crate::error::{LscmdError, Result};
-use regex::Regex;
+use crate::security::InputValidator;
+use regex::{Regex, RegexBuilder};
+use std::borrow::Borrow;
+use std::cmp::Reverse;
+use std::collections::VecDeque;
+use std::ops::{Deref, Range};
+use std::path::Path;
+use std::sync::Mutex;
+
+/// Points for where a query word matched, best first. Each word counts once,
+/// at its best match.
+pub const SCORE_EXACT_NAME: u32 = 100;
+pub const SCORE_NAME_PREFIX: u32 = 60;
+pub const SCORE_NAME_SUBSTRING: u32 = 40;
+pub const SCORE_DESCRIPTION: u32 = 20;
+pub const SCORE_CODE: u32 = 10;
+/// Bonus for a multi-word query matching every one of its words.
+pub const SCORE_ALL_WORDS: u32 = 50;
+
+/// How many compiled regexes a `SearchEngine` keeps for reuse.
+const REGEX_CACHE_SIZE: usize = 32;
+/// The most heap a compiled regex may use, in bytes.
+const REGEX_SIZE_LIMIT: usize = 1 << 20;
+/// The most heap the lazy DFA of a regex may use, in bytes.
+const REGEX_DFA_SIZE_LIMIT: usize = 2 << 20;
+
+/// A command matched by a search, with its relevance score.
+///
+/// `C` is `&Command` for results borrowed from the searched slice and
+/// `Command` for owned results, such as those returned by the repository.
+/// Either way the result dereferences to the command.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchResult<C = Command> {
+    pub command: C,
+    /// Higher is more relevant; see the `SCORE_*` constants.
+    pub score: u32,
+    /// Where in the command the query matched, for highlighting.
+    pub matches: Vec<FieldMatch>,
+}
+
+impl<'a> SearchResult<&'a Command> {
+    fn new(command: &'a Command, score: u32) -> Self {
+        Self {
+            command,
+            score,
+            matches: Vec::new(),
+        }
+    }
+
+    /// Clones the borrowed command into an owned result.
+    pub fn to_owned(&self) -> SearchResult {
+        SearchResult {
+            command: self.command.clone(),
+            score: self.score,
+            matches: self.matches.clone(),
+        }
+    }
+}
+
+/// The byte ranges of one field of a command that matched a query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldMatch {
+    pub field: Field,
+    /// Sorted, non-overlapping byte ranges into the field's text.
+    pub ranges: Vec<Range<usize>>,
+}
+
+impl<C: Borrow<Command>> Deref for SearchResult<C> {
+    type Target = Command;
+
+    fn deref(&self) -> &Command {
+        self.command.borrow()
+    }
+}
+
+/// How a `SearchEngine` interprets queries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SearchMode {
+    /// The query language described on `Query`.
+    #[default]
+    Query,
+    /// A regular expression.
+    Regex,
+    /// fzf-style subsequence matching; see `fuzzy_match`.
+    Fuzzy,
+}
 
 /// Defines the search engine for finding commands.
 ///
-/// The search behavior is determined by the `regex_mode` flag. All searches
-/// are performed case-insensitively, as per the project's requirements.
+/// The search behavior is determined by its `SearchMode`. All searches are
+/// performed case-insensitively, as per the project's requirements.
+///
+/// Compiled regexes are cached, so an engine kept alive across searches,
+/// such as one re-run on every keystroke, only compiles each pattern once.
 #[derive(Debug)]
 pub struct SearchEngine {
-    regex_mode: bool,
+    mode: SearchMode,
+    lenient: bool,
+    regex_cache: Mutex<RegexCache>,
 }
 
 impl SearchEngine {
@@ -17,9 +107,36 @@ impl SearchEngine {
     /// # Arguments
     ///
     /// * `regex_mode` - If true, the engine will use regular expressions for searching.
-    ///                  Otherwise, it will perform a simple word-based search.
+    ///   Otherwise, it will use the query language.
     pub fn new(regex_mode: bool) -> Self {
-        Self { regex_mode }
+        Self::with_mode(match regex_mode {
+            true => SearchMode::Regex,
+            false => SearchMode::Query,
+        })
+    }
+
+    /// Creates a new `SearchEngine` using `mode`.
+    pub fn with_mode(mode: SearchMode) -> Self {
+        Self {
+            mode,
+            lenient: false,
+            regex_cache: Mutex::new(RegexCache::new(REGEX_CACHE_SIZE)),
+        }
+    }
+
+    /// Sets whether incomplete regexes, as typed mid-edit, are searched for
+    /// instead of rejected.
+    ///
+    /// A lenient engine closes what is left open, so `(push|pu` searches for
+    /// `(push|pu)` and `[a-` for `[a-]`, and falls back to matching the
+    /// pattern literally if that is still not a valid regex.
+    pub fn lenient(mut self, lenient: bool) -> Self {
+        self.lenient = lenient;
+        self
+    }
+
+    pub fn mode(&self) -> SearchMode {
+        self.mode
     }
 
     /// Searches a slice of commands based on the configured mode.
@@ -31,58 +148,1157 @@ pub fn new(regex_mode: bool) -> Self {
     ///
     /// # Returns
     ///
-    /// A `Vec<Command>` containing the matched commands.
+    /// The matched commands, most relevant first. Commands with equal scores
+    /// keep their order from `commands`. An empty query matches everything
+    /// with a score of zero.
     ///
     /// # Errors
     ///
-    /// Returns `LscmdError::SearchPattern` if the regex is invalid.
-    pub fn search<'a>(&self, query: &str, commands: &'a [Command]) -> Result<Vec<&'a Command>> {
+    /// Returns `LscmdError::SearchPattern` if the query or regex is invalid,
+    /// or if the regex compiles to more than the size limits allow.
+    pub fn search<'a>(&self, query: &str, commands: &'a [Command]) -> Result<Vec<SearchResult<&'a Command>>> {
         if query.is_empty() {
-            return Ok(commands.iter().collect());
+            return Ok(commands.iter().map(|command| SearchResult::new(command, 0)).collect());
         }
 
-        match self.regex_mode {
-            true => self.regex_search(query, commands),
-            false => self.word_search(query, commands),
+        let mut results = match self.mode {
+            SearchMode::Query => self.query_search(query, commands)?,
+            SearchMode::Regex => self.regex_search(query, commands)?,
+            SearchMode::Fuzzy => self.fuzzy_search(query, commands),
+        };
+        results.sort_by_key(|result| Reverse(result.score));
+        Ok(results)
+    }
+
+    /// Translates a query into an FTS5 `MATCH` expression for the
+    /// trigram-indexed `commands_fts` table.
+    ///
+    /// The expression may match more commands than the query but never fewer,
+    /// so its results still need to be filtered with `search`. Returns `None`
+    /// when the index cannot narrow the query down: outside query mode, for an
+    /// invalid query, or when the query has no indexable term.
+    pub fn fts_query(&self, query: &str) -> Option<String> {
+        if self.mode != SearchMode::Query {
+            return None;
         }
+        Query::parse(query).ok()?.fts_query()
     }
 
-    /// Performs a case-insensitive regex search on command names and code.
-    fn regex_search<'a>(&self, query: &str, commands: &'a [Command]) -> Result<Vec<&'a Command>> {
-        // Prepend with `(?i)` for case-insensitivity.
-        let pattern = format!("(?i){}", query);
-        let re = Regex::new(&pattern)
-            .map_err(|e| LscmdError::SearchPattern(format!("Invalid regex: {}", e)))?;
+    /// Performs a case-insensitive regex search on command names, code and descriptions.
+    fn regex_search<'a>(&self, query: &str, commands: &'a [Command]) -> Result<Vec<SearchResult<&'a Command>>> {
+        let re = self.compile_regex(query)?;
 
         let results = commands
             .iter()
-            .filter(|cmd| re.is_match(&cmd.name) || re.is_match(&cmd.code))
+            .filter_map(|command| {
+                let name_score = re.find(&command.name).map(|m| match (m.start(), m.end()) {
+                    (0, end) if end == command.name.len() => SCORE_EXACT_NAME,
+                    (0, _) => SCORE_NAME_PREFIX,
+                    _ => SCORE_NAME_SUBSTRING,
+                });
+                let score = name_score.or_else(|| {
+                    if command.description.as_deref().is_some_and(|d| re.is_match(d)) {
+                        Some(SCORE_DESCRIPTION)
+                    } else {
+                        re.is_match(&command.code).then_some(SCORE_CODE)
+                    }
+                })?;
+
+                let mut result = SearchResult::new(command, score);
+                let fields = [
+                    (Field::Name, command.name.as_str()),
+                    (Field::Description, command.description.as_deref().unwrap_or_default()),
+                    (Field::Code, command.code.as_str()),
+                ];
+                for (field, text) in fields {
+                    // Empty matches have nothing to highlight.
+                    let ranges: Vec<_> = re.find_iter(text).map(|m| m.range()).filter(|r| !r.is_empty()).collect();
+                    if !ranges.is_empty() {
+                        add_ranges(&mut result.matches, field, ranges);
+                    }
+                }
+                Some(result)
+            })
             .collect();
 
         Ok(results)
     }
 
-    /// Performs a case-insensitive word-based OR search.
-    ///
-    /// A command matches if its name or code contains any of the whitespace-separated
-    /// words in the query.
-    fn word_search<'a>(&self, query: &str, commands: &'a [Command]) -> Result<Vec<&'a Command>> {
-        let words: Vec<String> = query.split_whitespace().map(|s| s.to_lowercase()).collect();
-        if words.is_empty() {
-            return Ok(commands.iter().collect());
+    /// Compiles `pattern` case-insensitively, reusing a cached regex if the
+    /// pattern was compiled before.
+    fn compile_regex(&self, pattern: &str) -> Result<Regex> {
+        InputValidator::validate_search_query(pattern)?;
+        let mut cache = self.regex_cache.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(re) = cache.get(pattern) {
+            return Ok(re);
         }
 
+        let re = match (build_regex(pattern), incomplete_regex(pattern)) {
+            (Ok(re), _) => re,
+            (Err(_), Some((message, column))) if !self.lenient => {
+                return Err(LscmdError::SearchPattern(format!(
+                    "Incomplete regex: {} at column {}",
+                    message, column
+                )));
+            }
+            (Err(_), Some(_)) => build_regex(&complete_regex(pattern)).or_else(|_| build_regex(&regex::escape(pattern)))?,
+            (Err(e), None) => return Err(e),
+        };
+        cache.insert(pattern, re.clone());
+        Ok(re)
+    }
+
+    /// Performs a case-insensitive fuzzy search.
+    ///
+    /// Every whitespace-separated word must fuzzy-match the name, description
+    /// or code, tried in that order; matches outside the name score half.
+    fn fuzzy_search<'a>(&self, query: &str, commands: &'a [Command]) -> Vec<SearchResult<&'a Command>> {
+        let words: Vec<&str> = query.split_whitespace().collect();
+        commands
+            .iter()
+            .filter_map(|command| {
+                let mut result = SearchResult::new(command, 0);
+                for word in &words {
+                    let fields = [
+                        (Field::Name, command.name.as_str(), 1),
+                        (Field::Description, command.description.as_deref().unwrap_or_default(), 2),
+                        (Field::Code, command.code.as_str(), 2),
+                    ];
+                    let (field, text, found, divisor) = fields
+                        .into_iter()
+                        .find_map(|(field, text, divisor)| fuzzy_match(word, text).map(|m| (field, text, m, divisor)))?;
+                    result.score += found.score.max(1) as u32 / divisor;
+                    add_ranges(&mut result.matches, field, found.ranges(text));
+                }
+                Some(result)
+            })
+            .collect()
+    }
+
+    /// Performs a case-insensitive search with the query language described
+    /// on `Query`.
+    fn query_search<'a>(&self, query: &str, commands: &'a [Command]) -> Result<Vec<SearchResult<&'a Command>>> {
+        let query = Query::parse(query)?;
         let results = commands
             .iter()
-            .filter(|cmd| {
-                let name_lower = cmd.name.to_lowercase();
-                let code_lower = cmd.code.to_lowercase();
-                words
-                    .iter()
-                    .any(|word| name_lower.contains(word) || code_lower.contains(word))
+            .filter_map(|command| {
+                let haystack = Haystack::new(command);
+                let score = query.root.score(&haystack)?;
+                let mut result = SearchResult::new(command, score);
+                query.root.collect_matches(&haystack, &mut result.matches);
+                Some(result)
             })
             .collect();
-
         Ok(results)
     }
 }
+
+/// A searchable field of a command, as named in `field:value` terms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Name,
+    Type,
+    File,
+    Code,
+    Description,
+}
+
+impl Field {
+    /// Looks up a field by the keyword used in queries (case-insensitive).
+    pub fn from_keyword(keyword: &str) -> Option<Self> {
+        match keyword.to_lowercase().as_str() {
+            "name" => Some(Field::Name),
+            "type" => Some(Field::Type),
+            "file" | "path" => Some(Field::File),
+            "code" => Some(Field::Code),
+            "desc" | "description" => Some(Field::Description),
+            _ => None,
+        }
+    }
+
+    /// The matching column of the `commands_fts` table, if it is indexed.
+    fn fts_column(self) -> Option<&'static str> {
+        match self {
+            Field::Name => Some("name"),
+            Field::Code => Some("code"),
+            Field::Description => Some("description"),
+            Field::Type | Field::File => None,
+        }
+    }
+}
+
+/// A single word or quoted phrase in a query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Term {
+    /// The field the term is restricted to, or `None` for name, description
+    /// and code alike.
+    pub field: Option<Field>,
+    /// The value as written, without quotes.
+    pub value: String,
+    /// Whether `value` is a glob: an unquoted word containing `*`.
+    pub wildcard: bool,
+    /// The 1-based column at which the term starts in the query.
+    pub column: usize,
+    folded: String,
+}
+
+impl Term {
+    fn new(field: Option<Field>, value: String, wildcard: bool, column: usize) -> Self {
+        let folded = value.to_lowercase();
+        Self { field, value, wildcard, column, folded }
+    }
+
+    /// Whether the term matches lowercased `text`: anywhere for plain terms,
+    /// the whole text for globs.
+    fn matches(&self, text: &str) -> bool {
+        match self.wildcard {
+            true => glob_match(&self.folded, text),
+            false => text.contains(&self.folded),
+        }
+    }
+
+    fn name_score(&self, name: &str) -> Option<u32> {
+        if self.wildcard {
+            return self.matches(name).then_some(SCORE_NAME_PREFIX);
+        }
+        if name == self.folded {
+            Some(SCORE_EXACT_NAME)
+        } else if name.starts_with(&self.folded) {
+            Some(SCORE_NAME_PREFIX)
+        } else {
+            name.contains(&self.folded).then_some(SCORE_NAME_SUBSTRING)
+        }
+    }
+
+    fn score(&self, haystack: &Haystack) -> Option<u32> {
+        match self.field {
+            None => self
+                .name_score(&haystack.name)
+                .or_else(|| self.matches(&haystack.description).then_some(SCORE_DESCRIPTION))
+                .or_else(|| self.matches(&haystack.code).then_some(SCORE_CODE)),
+            Some(Field::Name) => self.name_score(&haystack.name),
+            Some(Field::Description) => self.matches(&haystack.description).then_some(SCORE_DESCRIPTION),
+            Some(Field::Code) => self.matches(&haystack.code).then_some(SCORE_CODE),
+            Some(Field::Type) => (haystack.cmd_type == self.folded).then_some(0),
+            Some(Field::File) => (self.matches(&haystack.path) || self.matches(&haystack.file_name)).then_some(0),
+        }
+    }
+
+    /// The byte ranges of lowercased `text` the term matched: every
+    /// occurrence for plain terms, the literal runs between `*`s for globs.
+    fn ranges(&self, text: &str) -> Vec<Range<usize>> {
+        if self.wildcard {
+            return match self.matches(text) {
+                true => glob_ranges(&self.folded, text),
+                false => Vec::new(),
+            };
+        }
+        text.match_indices(&self.folded)
+            .map(|(start, matched)| start..start + matched.len())
+            .collect()
+    }
+
+    fn collect_matches(&self, haystack: &Haystack, matches: &mut Vec<FieldMatch>) {
+        let mut add = |field, folded: &Folded, ranges: Vec<Range<usize>>| {
+            if !ranges.is_empty() {
+                add_ranges(matches, field, ranges.into_iter().map(|r| folded.original(r)).collect());
+            }
+        };
+        match self.field {
+            None => {
+                add(Field::Name, &haystack.name, self.ranges(&haystack.name));
+                add(Field::Description, &haystack.description, self.ranges(&haystack.description));
+                add(Field::Code, &haystack.code, self.ranges(&haystack.code));
+            }
+            Some(Field::Name) => add(Field::Name, &haystack.name, self.ranges(&haystack.name)),
+            Some(Field::Description) => add(Field::Description, &haystack.description, self.ranges(&haystack.description)),
+            Some(Field::Code) => add(Field::Code, &haystack.code, self.ranges(&haystack.code)),
+            Some(Field::Type) => {
+                if haystack.cmd_type == self.folded {
+                    add(Field::Type, &haystack.cmd_type, std::iter::once(0..haystack.cmd_type.len()).collect());
+                }
+            }
+            Some(Field::File) => {
+                let mut ranges = self.ranges(&haystack.path);
+                if ranges.is_empty() {
+                    // The file name is a suffix of the path.
+                    let offset = haystack.path.len() - haystack.file_name.len();
+                    ranges = self.ranges(&haystack.file_name)
+                        .into_iter()
+                        .map(|r| r.start + offset..r.end + offset)
+                        .collect();
+                }
+                add(Field::File, &haystack.path, ranges);
+            }
+        }
+    }
+
+    /// The longest run of the value an FTS trigram phrase can look for.
+    fn fts_phrase(&self) -> Option<String> {
+        let literal = match self.wildcard {
+            true => self.value.split('*').max_by_key(|part| part.chars().count())?,
+            false => self.value.as_str(),
+        };
+        (literal.chars().count() >= 3).then(|| format!("\"{}\"", literal.replace('"', "\"\"")))
+    }
+}
+
+/// A parsed query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueryNode {
+    Term(Term),
+    And(Vec<QueryNode>),
+    Or(Vec<QueryNode>),
+    Not(Box<QueryNode>),
+}
+
+impl QueryNode {
+    /// Scores `haystack`, or returns `None` if it does not match.
+    ///
+    /// Conjunctions add up their children's scores. Disjunctions add up the
+    /// children that matched, plus `SCORE_ALL_WORDS` if all of several did.
+    /// Negations score nothing.
+    fn score(&self, haystack: &Haystack) -> Option<u32> {
+        match self {
+            QueryNode::Term(term) => term.score(haystack),
+            QueryNode::And(children) => children.iter().map(|child| child.score(haystack)).sum(),
+            QueryNode::Or(children) => {
+                let scores: Vec<u32> = children.iter().filter_map(|child| child.score(haystack)).collect();
+                if scores.is_empty() {
+                    return None;
+                }
+                let bonus = match children.len() > 1 && scores.len() == children.len() {
+                    true => SCORE_ALL_WORDS,
+                    false => 0,
+                };
+                Some(scores.iter().sum::<u32>() + bonus)
+            }
+            QueryNode::Not(child) => match child.score(haystack) {
+                Some(_) => None,
+                None => Some(0),
+            },
+        }
+    }
+
+    /// Records where the terms that make `haystack` match this node matched.
+    /// Terms under a negation never contribute.
+    fn collect_matches(&self, haystack: &Haystack, matches: &mut Vec<FieldMatch>) {
+        match self {
+            QueryNode::Term(term) => term.collect_matches(haystack, matches),
+            QueryNode::And(children) | QueryNode::Or(children) => {
+                for child in children.iter().filter(|child| child.score(haystack).is_some()) {
+                    child.collect_matches(haystack, matches);
+                }
+            }
+            QueryNode::Not(_) => {}
+        }
+    }
+
+    /// Builds an FTS5 expression matching a superset of this node, or `None`
+    /// if the index cannot narrow it down.
+    fn fts_query(&self) -> Option<String> {
+        let wrap = |node: &QueryNode, query: String| match node {
+            QueryNode::Term(_) => query,
+            _ => format!("({})", query),
+        };
+        match self {
+            QueryNode::Term(term) => {
+                let phrase = term.fts_phrase()?;
+                match term.field {
+                    None => Some(phrase),
+                    Some(field) => Some(format!("{} : {}", field.fts_column()?, phrase)),
+                }
+            }
+            // Dropping a conjunct only widens the match.
+            QueryNode::And(children) => {
+                let parts: Vec<String> = children
+                    .iter()
+                    .filter_map(|child| child.fts_query().map(|query| wrap(child, query)))
+                    .collect();
+                (!parts.is_empty()).then(|| parts.join(" AND "))
+            }
+            QueryNode::Or(children) => {
+                let parts = children
+                    .iter()
+                    .map(|child| child.fts_query().map(|query| wrap(child, query)))
+                    .collect::<Option<Vec<_>>>()?;
+                Some(parts.join(" OR "))
+            }
+            QueryNode::Not(_) => None,
+        }
+    }
+}
+
+/// A search query in lscmd's query language.
+///
+/// - Words match the name, description or code, case-insensitively. A
+///   word containing `*` is a glob that must match the whole field.
+/// - `"quoted phrases"` match literally, spaces and `*` included.
+/// - `field:value` restricts a word or phrase to one field: `name`, `type`
+///   (`alias` or `function`), `file` (also `path`), `code` or `desc` (also
+///   `description`). `file:` globs may match the path or just the file name.
+/// - `NOT term` or `-term` excludes matches; `AND`, `OR` and parentheses
+///   combine terms explicitly.
+///
+/// Without explicit operators, plain words match if any of them does, as in
+/// `git docker`, while field terms, exclusions and groups must all hold:
+/// `name:git* type:function file:docker code:"kubectl get" -deprecated`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Query {
+    root: QueryNode,
+}
+
+impl Query {
+    /// Parses `query`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `LscmdError::Validation` if the query is too long, and
+    /// `LscmdError::SearchPattern` naming the offending column if it is
+    /// malformed.
+    pub fn parse(query: &str) -> Result<Self> {
+        InputValidator::validate_search_query(query)?;
+        let tokens = tokenize(query)?;
+        let mut parser = QueryParser {
+            tokens,
+            pos: 0,
+            depth: 0,
+            end_column: query.chars().count() + 1,
+        };
+        let root = match parser.peek() {
+            None => QueryNode::And(Vec::new()),
+            Some(_) => parser.parse_or()?,
+        };
+        if let Some((_, column)) = parser.tokens.get(parser.pos) {
+            return Err(pattern_error("unmatched ')'", *column));
+        }
+        Ok(Self { root })
+    }
+
+    /// The root of the parsed query.
+    pub fn root(&self) -> &QueryNode {
+        &self.root
+    }
+
+    /// Scores `command`, or returns `None` if it does not match.
+    pub fn score(&self, command: &Command) -> Option<u32> {
+        self.root.score(&Haystack::new(command))
+    }
+
+    /// Returns where in `command` the query matched, or nothing if it does
+    /// not match.
+    pub fn matches(&self, command: &Command) -> Vec<FieldMatch> {
+        let haystack = Haystack::new(command);
+        let mut matches = Vec::new();
+        if self.root.score(&haystack).is_some() {
+            self.root.collect_matches(&haystack, &mut matches);
+        }
+        matches
+    }
+
+    /// Builds an FTS5 expression matching a superset of this query, or
+    /// `None` if the index cannot narrow it down.
+    pub fn fts_query(&self) -> Option<String> {
+        self.root.fts_query()
+    }
+}
+
+/// The lowercased fields of a command, folded once per search.
+struct Haystack {
+    name: Folded,
+    cmd_type: Folded,
+    path: Folded,
+    file_name: String,
+    code: Folded,
+    description: Folded,
+}
+
+impl Haystack {
+    fn new(command: &Command) -> Self {
+        let path = Folded::new(&command.path);
+        let file_name = Path::new(&path.text)
+            .file_name()
+            .map_or_else(String::new, |name| name.to_string_lossy().into_owned());
+        Self {
+            name: Folded::new(&command.name),
+            cmd_type: Folded::new(command.cmd_type.as_str()),
+            path,
+            file_name,
+            code: Folded::new(&command.code),
+            description: Folded::new(command.description.as_deref().unwrap_or_default()),
+        }
+    }
+}
+
+/// Lowercased text that can map its byte offsets back to the original.
+struct Folded {
+    text: String,
+    /// The original offset of each folded byte, plus one for the end; `None`
+    /// when lowercasing kept every offset, as it does for ASCII.
+    offsets: Option<Vec<usize>>,
+}
+
+impl Folded {
+    fn new(original: &str) -> Self {
+        let text = original.to_lowercase();
+        if original.is_ascii() {
+            return Self { text, offsets: None };
+        }
+
+        let mut offsets = Vec::with_capacity(text.len() + 1);
+        for (start, c) in original.char_indices() {
+            let folded_len: usize = c.to_lowercase().map(char::len_utf8).sum();
+            offsets.extend(std::iter::repeat_n(start, folded_len));
+        }
+        offsets.push(original.len());
+        Self { text, offsets: Some(offsets) }
+    }
+
+    /// Maps a range of the folded text to the original characters it covers.
+    fn original(&self, range: Range<usize>) -> Range<usize> {
+        let Some(offsets) = &self.offsets else {
+            return range;
+        };
+        // A range ending inside a character's folding covers all of it.
+        let end = offsets[range.end..].iter().find(|&&offset| offset > offsets[range.end - 1]).copied();
+        offsets[range.start]..end.unwrap_or(*offsets.last().unwrap_or(&0))
+    }
+}
+
+impl Deref for Folded {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.text
+    }
+}
+
+impl PartialEq<String> for Folded {
+    fn eq(&self, other: &String) -> bool {
+        self.text == *other
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    LParen,
+    RParen,
+    And,
+    Or,
+    Not,
+    Term(Term),
+}
+
+fn pattern_error(message: &str, column: usize) -> LscmdError {
+    LscmdError::SearchPattern(format!("{} at column {}", message, column))
+}
+
+/// Splits a query into tokens paired with their 1-based columns.
+fn tokenize(query: &str) -> Result<Vec<(Token, usize)>> {
+    let chars: Vec<char> = query.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let column = i + 1;
+        match chars[i] {
+            c if c.is_whitespace() => i += 1,
+            '(' => {
+                tokens.push((Token::LParen, column));
+                i += 1;
+            }
+            ')' => {
+                tokens.push((Token::RParen, column));
+                i += 1;
+            }
+            // `-term` excludes; a lone `-` or `--flag` is just a word.
+            '-' if chars.get(i + 1).is_some_and(|&c| !c.is_whitespace() && c != '-' && c != ')') => {
+                tokens.push((Token::Not, column));
+                i += 1;
+            }
+            _ => {
+                let field = field_prefix(&chars[i..]);
+                let value_start = i + field.map_or(0, |(_, len)| len);
+                let field = field.map(|(field, _)| field);
+
+                let (value, wildcard, end) = match chars.get(value_start) {
+                    Some('"') => {
+                        let (phrase, end) = read_phrase(&chars, value_start)?;
+                        (phrase, false, end)
+                    }
+                    _ => {
+                        let end = (value_start..chars.len())
+                            .find(|&j| chars[j].is_whitespace() || chars[j] == ')')
+                            .unwrap_or(chars.len());
+                        let word: String = chars[value_start..end].iter().collect();
+                        if word.is_empty() {
+                            let name: String = chars[i..value_start].iter().collect();
+                            return Err(pattern_error(&format!("expected a value after '{}'", name), value_start + 1));
+                        }
+                        if field.is_none() {
+                            let keyword = match word.as_str() {
+                                "AND" => Some(Token::And),
+                                "OR" => Some(Token::Or),
+                                "NOT" => Some(Token::Not),
+                                _ => None,
+                            };
+                            if let Some(keyword) = keyword {
+                                tokens.push((keyword, column));
+                                i = end;
+                                continue;
+                            }
+                        }
+                        let wildcard = word.contains('*');
+                        (word, wildcard, end)
+                    }
+                };
+
+                if field == Some(Field::Type) && value.parse::<CommandType>().is_err() {
+                    return Err(pattern_error(
+                        &format!("unknown type '{}', expected one of: {}", value, CommandType::names()),
+                        value_start + 1,
+                    ));
+                }
+                tokens.push((Token::Term(Term::new(field, value, wildcard, column)), column));
+                i = end;
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// Recognises a `field:` prefix, returning the field and the prefix length.
+fn field_prefix(chars: &[char]) -> Option<(Field, usize)> {
+    let colon = chars.iter().take_while(|c| c.is_ascii_alphabetic()).count();
+    if chars.get(colon) != Some(&':') {
+        return None;
+    }
+    let keyword: String = chars[..colon].iter().collect();
+    Field::from_keyword(&keyword).map(|field| (field, colon + 1))
+}
+
+/// Reads the phrase whose opening quote is at `start`, returning its text
+/// and the index just past the closing quote. `\"` and `\\` are escapes.
+fn read_phrase(chars: &[char], start: usize) -> Result<(String, usize)> {
+    let mut phrase = String::new();
+    let mut i = start + 1;
+    while i < chars.len() {
+        match chars[i] {
+            '"' => {
+                if phrase.is_empty() {
+                    return Err(pattern_error("empty phrase", start + 1));
+                }
+                return Ok((phrase, i + 1));
+            }
+            '\\' if matches!(chars.get(i + 1), Some('"') | Some('\\')) => {
+                phrase.push(chars[i + 1]);
+                i += 2;
+            }
+            c => {
+                phrase.push(c);
+                i += 1;
+            }
+        }
+    }
+    Err(pattern_error("unterminated quote", start + 1))
+}
+
+/// A recursive-descent parser over the tokens of a query.
+///
+/// ```text
+/// or       := and ("OR" and)*
+/// and      := implicit ("AND" implicit)*
+/// implicit := unary+        plain words OR-ed, everything else AND-ed
+/// unary    := ("NOT" | "-") unary | "(" or ")" | term
+/// ```
+struct QueryParser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    depth: usize,
+    end_column: usize,
+}
+
+impl QueryParser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(token, _)| token)
+    }
+
+    fn column(&self) -> usize {
+        self.tokens.get(self.pos).map_or(self.end_column, |(_, column)| *column)
+    }
+
+    fn parse_or(&mut self) -> Result<QueryNode> {
+        let mut children = vec![self.parse_and()?];
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            children.push(self.parse_and()?);
+        }
+        Ok(combine(children, QueryNode::Or))
+    }
+
+    fn parse_and(&mut self) -> Result<QueryNode> {
+        let mut children = vec![self.parse_implicit()?];
+        while self.peek() == Some(&Token::And) {
+            self.pos += 1;
+            children.push(self.parse_implicit()?);
+        }
+        Ok(combine(children, QueryNode::And))
+    }
+
+    fn parse_implicit(&mut self) -> Result<QueryNode> {
+        let mut words = Vec::new();
+        let mut filters = Vec::new();
+        while matches!(self.peek(), Some(Token::LParen | Token::Not | Token::Term(_))) {
+            match self.parse_unary()? {
+                QueryNode::Term(term) if term.field.is_none() => words.push(QueryNode::Term(term)),
+                node => filters.push(node),
+            }
+        }
+
+        if words.is_empty() && filters.is_empty() {
+            let message = match self.peek() {
+                None => "expected a term".to_string(),
+                Some(Token::RParen) if self.depth == 0 => "unmatched ')'".to_string(),
+                Some(Token::RParen) => "expected a term before ')'".to_string(),
+                Some(Token::And) => "expected a term before 'AND'".to_string(),
+                Some(_) => "expected a term before 'OR'".to_string(),
+            };
+            return Err(pattern_error(&message, self.column()));
+        }
+        if !words.is_empty() {
+            filters.insert(0, combine(words, QueryNode::Or));
+        }
+        Ok(combine(filters, QueryNode::And))
+    }
+
+    fn parse_unary(&mut self) -> Result<QueryNode> {
+        let Some((token, column)) = self.tokens.get(self.pos).cloned() else {
+            return Err(pattern_error("expected a term", self.end_column));
+        };
+        self.pos += 1;
+        match token {
+            Token::Not => {
+                if !matches!(self.peek(), Some(Token::LParen | Token::Not | Token::Term(_))) {
+                    return Err(pattern_error("expected a term to exclude", self.column()));
+                }
+                Ok(QueryNode::Not(Box::new(self.parse_unary()?)))
+            }
+            Token::LParen => {
+                self.depth += 1;
+                let inner = self.parse_or()?;
+                if self.peek() != Some(&Token::RParen) {
+                    return Err(pattern_error("unclosed '('", column));
+                }
+                self.pos += 1;
+                self.depth -= 1;
+                Ok(inner)
+            }
+            Token::Term(term) => Ok(QueryNode::Term(term)),
+            Token::RParen | Token::And | Token::Or => unreachable!("callers only parse unary tokens"),
+        }
+    }
+}
+
+/// Wraps several nodes in `wrap`, leaving a single node as it is.
+fn combine(mut nodes: Vec<QueryNode>, wrap: fn(Vec<QueryNode>) -> QueryNode) -> QueryNode {
+    match nodes.len() {
+        1 => nodes.remove(0),
+        _ => wrap(nodes),
+    }
+}
+
+/// Matches lowercased `text` against a lowercased glob where `*` matches any
+/// run of characters.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // The last `*` seen and the text position it is currently absorbing up to.
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if p < pattern.len() && pattern[p] == text[t] {
+            p += 1;
+            t += 1;
+        } else if let Some((star, absorbed)) = backtrack {
+            p = star + 1;
+            t = absorbed + 1;
+            backtrack = Some((star, absorbed + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Compiles `pattern` case-insensitively within the regex size limits.
+fn build_regex(pattern: &str) -> Result<Regex> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .size_limit(REGEX_SIZE_LIMIT)
+        .dfa_size_limit(REGEX_DFA_SIZE_LIMIT)
+        .build()
+        .map_err(|e| match e {
+            regex::Error::CompiledTooBig(limit) => LscmdError::SearchPattern(format!(
+                "Regex too complex: it compiles to more than {} KiB; try a simpler pattern or fewer repetitions",
+                limit / 1024
+            )),
+            e => LscmdError::SearchPattern(format!("Invalid regex: {}", e)),
+        })
+}
+
+/// The constructs a regex leaves open at its end.
+#[derive(Debug, Default)]
+struct OpenRegex {
+    /// 1-based columns of unclosed `(`s and `[`s, innermost last.
+    groups: Vec<(char, usize)>,
+    /// Where a trailing `\` or unclosed `{` starts, as a byte offset and
+    /// 1-based column.
+    dangling: Option<(usize, usize, char)>,
+}
+
+/// Scans `pattern` for groups, classes, escapes and repetitions still open at
+/// its end, as happens while a pattern is being typed.
+fn open_regex(pattern: &str) -> OpenRegex {
+    let chars: Vec<(usize, char)> = pattern.char_indices().collect();
+    let mut open = OpenRegex::default();
+    let mut i = 0;
+    while i < chars.len() {
+        let (offset, c) = chars[i];
+        let column = i + 1;
+        let in_class = open.groups.last().is_some_and(|&(open, _)| open == '[');
+        match c {
+            '\\' => {
+                match chars.get(i + 1) {
+                    None => open.dangling = Some((offset, column, '\\')),
+                    // `\p{..}` and `\x{..}` take braces.
+                    Some(&(_, 'p' | 'P' | 'x' | 'u' | 'U')) if chars.get(i + 2).is_some_and(|&(_, c)| c == '{') => {
+                        match chars[i + 3..].iter().position(|&(_, c)| c == '}') {
+                            Some(close) => i += 3 + close,
+                            None => {
+                                open.dangling = Some((offset, column, '\\'));
+                                break;
+                            }
+                        }
+                    }
+                    Some(_) => i += 1,
+                }
+            }
+            '[' if in_class && chars.get(i + 1).is_some_and(|&(_, c)| c == ':') => {
+                // A POSIX class such as `[:alpha:]`.
+                match pattern[offset..].find(":]") {
+                    Some(end) => i += pattern[offset..offset + end].chars().count() + 1,
+                    None => i += 1,
+                }
+            }
+            '[' => {
+                open.groups.push(('[', column));
+                // A `]` first in the class (after any `^`) is literal.
+                if chars.get(i + 1).is_some_and(|&(_, c)| c == '^') {
+                    i += 1;
+                }
+                if chars.get(i + 1).is_some_and(|&(_, c)| c == ']') {
+                    i += 1;
+                }
+            }
+            ']' if in_class => {
+                open.groups.pop();
+            }
+            '(' if !in_class => open.groups.push(('(', column)),
+            ')' if !in_class => {
+                open.groups.pop();
+            }
+            '{' if !in_class => match chars[i + 1..].iter().position(|&(_, c)| c == '}') {
+                Some(close) => i += 1 + close,
+                None => {
+                    open.dangling = Some((offset, column, '{'));
+                    break;
+                }
+            },
+            _ => {}
+        }
+        i += 1;
+    }
+    open
+}
+
+/// Explains why `pattern` looks unfinished, with the column to point at, or
+/// returns `None` if nothing is left open.
+fn incomplete_regex(pattern: &str) -> Option<(String, usize)> {
+    let open = open_regex(pattern);
+    if let Some((_, column, c)) = open.dangling {
+        let message = match c {
+            '{' => "unclosed '{'".to_string(),
+            _ => "unfinished escape '\\'".to_string(),
+        };
+        return Some((message, column));
+    }
+    let &(c, column) = open.groups.first()?;
+    Some((format!("unclosed '{}'", c), column))
+}
+
+/// Closes whatever `pattern` leaves open: dangling escapes and repetitions
+/// are dropped, and open classes and groups are closed innermost first.
+fn complete_regex(pattern: &str) -> String {
+    let open = open_regex(pattern);
+    let mut completed = match open.dangling {
+        Some((offset, _, _)) => pattern[..offset].to_string(),
+        None => pattern.to_string(),
+    };
+    for &(c, _) in open.groups.iter().rev() {
+        completed.push(if c == '[' { ']' } else { ')' });
+    }
+    completed
+}
+
+/// A least-recently-used cache of compiled regexes, most recent first.
+#[derive(Debug)]
+struct RegexCache {
+    entries: VecDeque<(String, Regex)>,
+    capacity: usize,
+}
+
+impl RegexCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    fn get(&mut self, pattern: &str) -> Option<Regex> {
+        let index = self.entries.iter().position(|(cached, _)| cached == pattern)?;
+        let entry = self.entries.remove(index)?;
+        let re = entry.1.clone();
+        self.entries.push_front(entry);
+        Some(re)
+    }
+
+    fn insert(&mut self, pattern: &str, re: Regex) {
+        if self.entries.len() == self.capacity {
+            self.entries.pop_back();
+        }
+        self.entries.push_front((pattern.to_string(), re));
+    }
+}
+
+/// Finds the literal runs of a glob that `glob_match` accepted in `text`,
+/// placing each run as early as the runs around it allow.
+fn glob_ranges(pattern: &str, text: &str) -> Vec<Range<usize>> {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    let last = parts.len() - 1;
+    let mut ranges = Vec::new();
+    let mut pos = 0;
+    for (index, part) in parts.iter().enumerate() {
+        if part.is_empty() {
+            continue;
+        }
+        let start = match index {
+            0 => 0,
+            // The final run is anchored at the end unless the glob ends in `*`.
+            _ if index == last => text.len() - part.len(),
+            _ => match text[pos..].find(part) {
+                Some(found) => pos + found,
+                None => return ranges,
+            },
+        };
+        ranges.push(start..start + part.len());
+        pos = start + part.len();
+    }
+    ranges
+}
+
+/// Records `ranges` as matches in `field`, merging with earlier ones.
+fn add_ranges(matches: &mut Vec<FieldMatch>, field: Field, ranges: Vec<Range<usize>>) {
+    let index = match matches.iter().position(|m| m.field == field) {
+        Some(index) => index,
+        None => {
+            matches.push(FieldMatch { field, ranges: Vec::new() });
+            matches.len() - 1
+        }
+    };
+    let merged = &mut matches[index].ranges;
+    merged.extend(ranges);
+    merged.sort_by_key(|range| range.start);
+    let mut result: Vec<Range<usize>> = Vec::with_capacity(merged.len());
+    for range in merged.drain(..) {
+        match result.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => result.push(range),
+        }
+    }
+    *merged = result;
+}
+
+// fzf's scoring constants (algo v1).
+const FUZZY_SCORE_MATCH: i32 = 16;
+const FUZZY_GAP_START: i32 = -3;
+const FUZZY_GAP_EXTENSION: i32 = -1;
+const FUZZY_BONUS_BOUNDARY: i32 = FUZZY_SCORE_MATCH / 2;
+const FUZZY_BONUS_BOUNDARY_WHITE: i32 = FUZZY_BONUS_BOUNDARY + 2;
+const FUZZY_BONUS_BOUNDARY_DELIMITER: i32 = FUZZY_BONUS_BOUNDARY + 1;
+const FUZZY_BONUS_NON_WORD: i32 = FUZZY_SCORE_MATCH / 2;
+const FUZZY_BONUS_CAMEL_123: i32 = FUZZY_BONUS_BOUNDARY + FUZZY_GAP_EXTENSION;
+const FUZZY_BONUS_CONSECUTIVE: i32 = -(FUZZY_GAP_START + FUZZY_GAP_EXTENSION);
+const FUZZY_BONUS_FIRST_CHAR_MULTIPLIER: i32 = 2;
+
+/// A fuzzy match of a pattern in some text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    /// fzf-style score; higher is better.
+    pub score: i32,
+    /// The byte offset in the text of each matched character.
+    pub positions: Vec<usize>,
+}
+
+impl FuzzyMatch {
+    /// The matched characters of `text` as byte ranges, adjacent ones merged.
+    pub fn ranges(&self, text: &str) -> Vec<Range<usize>> {
+        let mut ranges: Vec<Range<usize>> = Vec::new();
+        for &start in &self.positions {
+            let end = start + text[start..].chars().next().map_or(0, char::len_utf8);
+            match ranges.last_mut() {
+                Some(last) if last.end == start => last.end = end,
+                _ => ranges.push(start..end),
+            }
+        }
+        ranges
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CharClass {
+    White,
+    NonWord,
+    Delimiter,
+    Lower,
+    Upper,
+    Number,
+}
+
+fn char_class(c: char) -> CharClass {
+    match c {
+        c if c.is_lowercase() => CharClass::Lower,
+        c if c.is_uppercase() => CharClass::Upper,
+        c if c.is_numeric() => CharClass::Number,
+        c if c.is_alphabetic() => CharClass::Lower,
+        c if c.is_whitespace() => CharClass::White,
+        '/' | ',' | ':' | ';' | '|' => CharClass::Delimiter,
+        _ => CharClass::NonWord,
+    }
+}
+
+/// The bonus for matching a character of class `current` after `previous`:
+/// word starts after spaces, delimiters or punctuation such as `_` score
+/// highest, then camelCase humps and digit runs.
+fn position_bonus(previous: CharClass, current: CharClass) -> i32 {
+    let is_word = |class| !matches!(class, CharClass::White | CharClass::NonWord | CharClass::Delimiter);
+    if is_word(current) {
+        match previous {
+            CharClass::White => return FUZZY_BONUS_BOUNDARY_WHITE,
+            CharClass::Delimiter => return FUZZY_BONUS_BOUNDARY_DELIMITER,
+            CharClass::NonWord => return FUZZY_BONUS_BOUNDARY,
+            _ => {}
+        }
+    }
+    match (previous, current) {
+        (CharClass::Lower, CharClass::Upper) => FUZZY_BONUS_CAMEL_123,
+        (previous, CharClass::Number) if previous != CharClass::Number => FUZZY_BONUS_CAMEL_123,
+        (_, CharClass::White) => FUZZY_BONUS_BOUNDARY_WHITE,
+        (_, CharClass::NonWord | CharClass::Delimiter) => FUZZY_BONUS_NON_WORD,
+        _ => 0,
+    }
+}
+
+/// Matches `pattern` as a case-insensitive subsequence of `text`, scored the
+/// way fzf does.
+///
+/// The shortest window ending at the first complete match is scored:
+/// every matched character earns points, more so at word boundaries,
+/// camelCase humps and in consecutive runs, while gaps cost points.
+/// Returns `None` if `pattern` is empty or not a subsequence of `text`.
+pub fn fuzzy_match(pattern: &str, text: &str) -> Option<FuzzyMatch> {
+    let fold = |c: char| c.to_lowercase().next().unwrap_or(c);
+    let pattern: Vec<char> = pattern.chars().map(fold).collect();
+    if pattern.is_empty() {
+        return None;
+    }
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let folded: Vec<char> = chars.iter().map(|&(_, c)| fold(c)).collect();
+
+    // Forward: find where the first complete match ends.
+    let mut p = 0;
+    let mut end = None;
+    for (i, &c) in folded.iter().enumerate() {
+        if c == pattern[p] {
+            p += 1;
+            if p == pattern.len() {
+                end = Some(i + 1);
+                break;
+            }
+        }
+    }
+    let end = end?;
+
+    // Backward: tighten the window to the latest start of that match.
+    let mut p = pattern.len();
+    let mut start = end;
+    for i in (0..end).rev() {
+        if folded[i] == pattern[p - 1] {
+            p -= 1;
+            if p == 0 {
+                start = i;
+                break;
+            }
+        }
+    }
+
+    let mut score = 0;
+    let mut positions = Vec::with_capacity(pattern.len());
+    let mut p = 0;
+    let mut in_gap = false;
+    let mut consecutive = 0;
+    let mut first_bonus = 0;
+    let mut previous = match start {
+        0 => CharClass::White,
+        _ => char_class(chars[start - 1].1),
+    };
+    for i in start..end {
+        let class = char_class(chars[i].1);
+        if p < pattern.len() && folded[i] == pattern[p] {
+            positions.push(chars[i].0);
+            score += FUZZY_SCORE_MATCH;
+            let mut bonus = position_bonus(previous, class);
+            if consecutive == 0 {
+                first_bonus = bonus;
+            } else {
+                // A run keeps the bonus of the boundary that started it.
+                if bonus >= FUZZY_BONUS_BOUNDARY && bonus > first_bonus {
+                    first_bonus = bonus;
+                }
+                bonus = bonus.max(first_bonus).max(FUZZY_BONUS_CONSECUTIVE);
+            }
+            score += match p {
+                0 => bonus * FUZZY_BONUS_FIRST_CHAR_MULTIPLIER,
+                _ => bonus,
+            };
+            in_gap = false;
+            consecutive += 1;
+            p += 1;
+        } else {
+            score += match in_gap {
+                true => FUZZY_GAP_EXTENSION,
+                false => FUZZY_GAP_START,
+            };
+            in_gap = true;
+            consecutive = 0;
+            first_bonus = 0;
+        }
+        previous = class;
+    }
+
+    Some(FuzzyMatch { score, positions })
+}
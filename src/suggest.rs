@@ -0,0 +1,144 @@
+/// The lowest score a candidate needs to be suggested.
+const MIN_SCORE: f64 = 0.5;
+
+/// A suggested command name and how closely it matches the query, from 0 to 1.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Suggestion {
+    pub name: String,
+    pub score: f64,
+}
+
+/// Ranks command names by their similarity to a mistyped or partial name.
+///
+/// A candidate is scored by the best of three case-insensitive measures:
+/// edit distance (typos and transpositions), prefix matching (truncated
+/// names) and subsequence matching (abbreviations such as `gco` for
+/// `git_checkout`).
+#[derive(Debug, Clone)]
+pub struct NameSuggester {
+    limit: usize,
+}
+
+impl Default for NameSuggester {
+    fn default() -> Self {
+        Self::new(5)
+    }
+}
+
+impl NameSuggester {
+    /// Creates a suggester returning at most `limit` names.
+    pub fn new(limit: usize) -> Self {
+        Self { limit }
+    }
+
+    /// Returns the closest `names` to `query`, best first.
+    ///
+    /// Ties are broken alphabetically so the output is stable.
+    pub fn suggest<'a, I>(&self, query: &str, names: I) -> Vec<Suggestion>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let query = query.to_lowercase();
+        let mut suggestions: Vec<Suggestion> = names
+            .into_iter()
+            .filter_map(|name| {
+                let score = score(&query, &name.to_lowercase());
+                (score >= MIN_SCORE).then(|| Suggestion {
+                    name: name.to_string(),
+                    score,
+                })
+            })
+            .collect();
+
+        suggestions.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.name.cmp(&b.name)));
+        suggestions.dedup_by(|a, b| a.name == b.name);
+        suggestions.truncate(self.limit);
+        suggestions
+    }
+
+    /// Like `suggest`, but returns just the names.
+    pub fn suggest_names<'a, I>(&self, query: &str, names: I) -> Vec<String>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        self.suggest(query, names).into_iter().map(|s| s.name).collect()
+    }
+}
+
+/// Scores a lowercase `candidate` against a lowercase `query`.
+fn score(query: &str, candidate: &str) -> f64 {
+    if query.is_empty() || candidate.is_empty() {
+        return 0.0;
+    }
+    if query == candidate {
+        return 1.0;
+    }
+
+    let query_len = query.chars().count();
+    let candidate_len = candidate.chars().count();
+    let longest = query_len.max(candidate_len) as f64;
+
+    // Allow roughly one typo per three characters.
+    let distance = edit_distance(query, candidate);
+    let edit = if distance <= (query_len / 3).max(1) {
+        1.0 - distance as f64 / longest
+    } else {
+        0.0
+    };
+
+    let prefix = if candidate.starts_with(query) {
+        0.6 + 0.4 * query_len as f64 / candidate_len as f64
+    } else {
+        0.0
+    };
+
+    edit.max(prefix).max(subsequence_score(query, candidate).unwrap_or(0.0))
+}
+
+/// Returns the optimal string alignment distance between `a` and `b`: the
+/// number of single-character insertions, deletions, substitutions and
+/// adjacent transpositions needed to turn one into the other.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    // Three rolling rows: two rows back (for transpositions), previous, current.
+    let mut before: Vec<usize> = vec![0; b.len() + 1];
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for i in 1..=a.len() {
+        current[0] = i;
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            current[j] = (previous[j] + 1).min(current[j - 1] + 1).min(previous[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                current[j] = current[j].min(before[j - 2] + 1);
+            }
+        }
+        std::mem::swap(&mut before, &mut previous);
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+/// Scores `query` as an in-order subsequence of `candidate`.
+///
+/// Returns `None` if some character of `query` cannot be matched. The score
+/// grows with how much of the candidate the query covers, so a short query
+/// scattered across a long name scores low, and matching the candidate's
+/// first character is worth a bonus since abbreviations rarely skip it.
+pub fn subsequence_score(query: &str, candidate: &str) -> Option<f64> {
+    let mut remaining = candidate.chars();
+    for c in query.chars() {
+        remaining.find(|&candidate_char| candidate_char == c)?;
+    }
+
+    let coverage = query.chars().count() as f64 / candidate.chars().count() as f64;
+    let first = if query.chars().next() == candidate.chars().next() {
+        0.2
+    } else {
+        0.0
+    };
+    Some(0.2 + first + 0.6 * coverage)
+}
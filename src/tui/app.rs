@@ -1,2 +1,570 @@
-// TUI application state placeholder
-// Will be implemented in Phase 6
\ No newline at end of file
+use crate::database::operations::CommandRepository;
+use crate::database::{CommandType, FileRecord};
+use crate::error::{LscmdError, Result};
+use crate::search::{SearchEngine, SearchResult};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::widgets::ListState;
+use std::path::{Path, PathBuf};
+
+/// The interaction states described in UI_LOGIC.md.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AppState {
+    /// Browsing the file list.
+    Default,
+    /// Browsing the commands of the selected file.
+    FileSelected(PathBuf),
+    /// Typing a query, with live results in the left panel.
+    SearchMode(String),
+}
+
+/// The two panels that can receive focus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PanelFocus {
+    /// The command list.
+    Left,
+    /// The file list or search input.
+    Right,
+}
+
+/// What the left panel is listing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LeftPanel {
+    Empty,
+    /// The commands of a file, grouped by type.
+    File(PathBuf),
+    /// Search results, most relevant first.
+    Search,
+}
+
+/// Everything `Esc` restores when leaving search mode.
+#[derive(Debug, Clone)]
+struct Snapshot {
+    state: AppState,
+    focus: PanelFocus,
+    left: LeftPanel,
+    commands: Vec<SearchResult>,
+    command_cursor: usize,
+}
+
+/// The TUI's state machine, independent of any terminal.
+///
+/// Key presses go through `handle_key`, which moves between the states of
+/// `AppState` and loads commands from the repository as needed; `ui::draw`
+/// renders whatever the state is.
+pub struct App<'a> {
+    repo: &'a dyn CommandRepository,
+    engine: SearchEngine,
+    type_filter: Option<CommandType>,
+    state: AppState,
+    focus: PanelFocus,
+    files: Vec<FileRecord>,
+    file_cursor: usize,
+    selected_file: Option<usize>,
+    left: LeftPanel,
+    commands: Vec<SearchResult>,
+    command_cursor: usize,
+    /// The position of the input cursor in the query, in characters.
+    input_cursor: usize,
+    /// Why the current query could not be run, or why the lists stopped
+    /// refreshing.
+    message: Option<String>,
+    before_search: Option<Snapshot>,
+    code_scroll: u16,
+    should_quit: bool,
+    /// Whether Enter on a command picks it and quits, for `lscmd pick`.
+    picking: bool,
+    picked: Option<SearchResult>,
+    pub(crate) file_list: ListState,
+    pub(crate) command_list: ListState,
+}
+
+impl<'a> App<'a> {
+    /// Creates an app in the default state, listing the files tracked by
+    /// `repo`. Searches use `engine`, and only commands of `type_filter`
+    /// are shown if it is set.
+    pub fn new(repo: &'a dyn CommandRepository, engine: SearchEngine, type_filter: Option<CommandType>) -> Result<Self> {
+        Ok(Self {
+            repo,
+            engine,
+            type_filter,
+            state: AppState::Default,
+            focus: PanelFocus::Right,
+            files: repo.list_files()?,
+            file_cursor: 0,
+            selected_file: None,
+            left: LeftPanel::Empty,
+            commands: Vec::new(),
+            command_cursor: 0,
+            input_cursor: 0,
+            message: None,
+            before_search: None,
+            code_scroll: 0,
+            should_quit: false,
+            picking: false,
+            picked: None,
+            file_list: ListState::default(),
+            command_list: ListState::default(),
+        })
+    }
+
+    /// The repository the app reads from.
+    pub fn repo(&self) -> &'a dyn CommandRepository {
+        self.repo
+    }
+
+    /// Turns the app into a picker: Enter on a command picks it and quits,
+    /// and Esc with nothing to go back to quits without a pick.
+    pub fn picking(mut self, picking: bool) -> Self {
+        self.picking = picking;
+        self
+    }
+
+    pub fn is_picking(&self) -> bool {
+        self.picking
+    }
+
+    /// The command picked in picker mode, or `None` if it was cancelled.
+    pub fn picked(&self) -> Option<&SearchResult> {
+        self.picked.as_ref()
+    }
+
+    pub fn state(&self) -> &AppState {
+        &self.state
+    }
+
+    pub fn focus(&self) -> PanelFocus {
+        self.focus
+    }
+
+    pub fn left_panel(&self) -> &LeftPanel {
+        &self.left
+    }
+
+    pub fn files(&self) -> &[FileRecord] {
+        &self.files
+    }
+
+    pub fn file_cursor(&self) -> usize {
+        self.file_cursor
+    }
+
+    /// The file selected in the file list, shown with a green bar.
+    pub fn selected_file(&self) -> Option<&FileRecord> {
+        self.selected_file.and_then(|index| self.files.get(index))
+    }
+
+    /// The commands in the left panel, in display order.
+    pub fn commands(&self) -> &[SearchResult] {
+        &self.commands
+    }
+
+    pub fn command_cursor(&self) -> usize {
+        self.command_cursor
+    }
+
+    /// The command under the cursor, whose code the bottom panel shows.
+    pub fn current_command(&self) -> Option<&SearchResult> {
+        self.commands.get(self.command_cursor)
+    }
+
+    /// The query being typed, in search mode.
+    pub fn search_query(&self) -> Option<&str> {
+        match &self.state {
+            AppState::SearchMode(query) => Some(query),
+            _ => None,
+        }
+    }
+
+    /// The input cursor position in the query, in characters.
+    pub fn input_cursor(&self) -> usize {
+        self.input_cursor
+    }
+
+    /// Why the current query could not be run, if it could not.
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+
+    pub fn code_scroll(&self) -> u16 {
+        self.code_scroll
+    }
+
+    pub fn should_quit(&self) -> bool {
+        self.should_quit
+    }
+
+    /// Switches to search mode with `query` typed in, as `lscmd search` does.
+    pub fn start_search(&mut self, query: &str) -> Result<()> {
+        self.enter_search();
+        self.state = AppState::SearchMode(query.to_string());
+        self.input_cursor = query.chars().count();
+        self.refresh_search()
+    }
+
+    /// Reloads the file list and the commands on screen from the repository,
+    /// after the indexed files changed on disk.
+    ///
+    /// The cursors stay on the same file and command where those still
+    /// exist. If the selected file is gone, the app goes back to the file
+    /// list, as if it had been deselected.
+    pub fn reload(&mut self) -> Result<()> {
+        let cursor_path = self.files.get(self.file_cursor).map(|f| f.path.clone());
+        let selected_path = self.selected_file().map(|f| f.path.clone());
+        self.files = self.repo.list_files()?;
+        self.file_cursor = cursor_path
+            .and_then(|path| self.file_index(&path))
+            .unwrap_or(self.file_cursor)
+            .min(self.files.len().saturating_sub(1));
+        self.selected_file = selected_path.and_then(|path| self.file_index(&path));
+
+        if let Some(Snapshot { left: LeftPanel::File(path), .. }) = &self.before_search {
+            let commands = self.file_commands(path)?;
+            if let Some(snapshot) = self.before_search.as_mut() {
+                match commands {
+                    Some(commands) => {
+                        snapshot.command_cursor = snapshot.command_cursor.min(commands.len().saturating_sub(1));
+                        snapshot.commands = commands;
+                    }
+                    None => {
+                        snapshot.state = AppState::Default;
+                        snapshot.focus = PanelFocus::Right;
+                        snapshot.left = LeftPanel::Empty;
+                        snapshot.commands.clear();
+                        snapshot.command_cursor = 0;
+                    }
+                }
+            }
+        }
+
+        let current = self.current_command().map(|r| (r.name.clone(), r.path.clone()));
+        let code_scroll = self.code_scroll;
+        match self.left.clone() {
+            LeftPanel::Empty => return Ok(()),
+            LeftPanel::File(path) => match self.file_commands(&path)? {
+                Some(commands) => self.commands = commands,
+                None => {
+                    self.state = AppState::Default;
+                    self.focus = PanelFocus::Right;
+                    self.clear_commands();
+                    return Ok(());
+                }
+            },
+            LeftPanel::Search => self.refresh_search()?,
+        }
+
+        // Keep the cursor on the same command, even if its lines moved.
+        match current.and_then(|(name, path)| self.commands.iter().position(|r| r.name == name && r.path == path)) {
+            Some(cursor) => {
+                self.command_cursor = cursor;
+                self.code_scroll = code_scroll;
+            }
+            None => {
+                self.command_cursor = self.command_cursor.min(self.commands.len().saturating_sub(1));
+                self.code_scroll = 0;
+            }
+        }
+        if self.commands.is_empty() && self.focus == PanelFocus::Left {
+            self.focus = PanelFocus::Right;
+        }
+        Ok(())
+    }
+
+    /// Reacts to a key press following the rules in UI_LOGIC.md.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the repository fails. Invalid queries are not
+    /// errors; they are reported through `message`.
+    pub fn handle_key(&mut self, key: KeyEvent) -> Result<()> {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.should_quit = true;
+            return Ok(());
+        }
+        if self.picking && self.handle_pick_key(key) {
+            return Ok(());
+        }
+        match self.state {
+            AppState::SearchMode(_) => self.handle_search_key(key),
+            _ => self.handle_browse_key(key),
+        }
+    }
+
+    /// Handles the keys that behave differently in a picker, returning
+    /// whether `key` was one of them.
+    fn handle_pick_key(&mut self, key: KeyEvent) -> bool {
+        let searching = matches!(self.state, AppState::SearchMode(_));
+        match key.code {
+            // From the command list, or straight from the search input.
+            KeyCode::Enter if self.focus == PanelFocus::Left || searching => match self.current_command() {
+                Some(result) => {
+                    self.picked = Some(result.clone());
+                    self.should_quit = true;
+                    true
+                }
+                None => false,
+            },
+            KeyCode::Esc if self.state == AppState::Default && self.focus == PanelFocus::Right => {
+                self.should_quit = true;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn handle_browse_key(&mut self, key: KeyEvent) -> Result<()> {
+        match key.code {
+            KeyCode::Char('q') => self.should_quit = true,
+            KeyCode::Char('/') if !self.files.is_empty() => {
+                self.enter_search();
+                self.state = AppState::SearchMode(String::new());
+                self.input_cursor = 0;
+            }
+            KeyCode::Tab if self.left != LeftPanel::Empty => self.focus = self.other_focus(),
+            KeyCode::PageDown => self.code_scroll = self.code_scroll.saturating_add(1),
+            KeyCode::PageUp => self.code_scroll = self.code_scroll.saturating_sub(1),
+            _ => match self.focus {
+                PanelFocus::Right => self.handle_file_list_key(key)?,
+                PanelFocus::Left => self.handle_command_list_key(key),
+            },
+        }
+        Ok(())
+    }
+
+    fn handle_file_list_key(&mut self, key: KeyEvent) -> Result<()> {
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => self.file_cursor = self.file_cursor.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => self.file_cursor = step(self.file_cursor, 1, self.files.len()),
+            KeyCode::Home => self.file_cursor = 0,
+            KeyCode::End => self.file_cursor = self.files.len().saturating_sub(1),
+            KeyCode::Enter | KeyCode::Char(' ') => self.toggle_file()?,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn handle_command_list_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => self.move_command_cursor(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_command_cursor(1),
+            KeyCode::Home => self.move_command_cursor(isize::MIN),
+            KeyCode::End => self.move_command_cursor(isize::MAX),
+            // Back to the files, forgetting the file's commands.
+            KeyCode::Esc => {
+                self.state = AppState::Default;
+                self.focus = PanelFocus::Right;
+                self.selected_file = None;
+                self.clear_commands();
+            }
+            // Back to the files, keeping the file's commands on screen.
+            KeyCode::Right => {
+                self.state = AppState::Default;
+                self.focus = PanelFocus::Right;
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_search_key(&mut self, key: KeyEvent) -> Result<()> {
+        let AppState::SearchMode(query) = &mut self.state else {
+            return Ok(());
+        };
+        match key.code {
+            KeyCode::Esc => self.exit_search(),
+            KeyCode::Char('/') => {
+                self.before_search = None;
+                self.state = AppState::Default;
+                self.focus = PanelFocus::Right;
+                self.message = None;
+                self.clear_commands();
+            }
+            KeyCode::Up => {
+                self.focus_results();
+                self.move_command_cursor(-1);
+            }
+            KeyCode::Down => {
+                // The first press only moves focus onto the first result.
+                if self.focus == PanelFocus::Left {
+                    self.move_command_cursor(1);
+                }
+                self.focus_results();
+            }
+            KeyCode::Enter => self.focus_results(),
+            KeyCode::Tab if !self.commands.is_empty() => self.focus = self.other_focus(),
+            KeyCode::PageDown => self.code_scroll = self.code_scroll.saturating_add(1),
+            KeyCode::PageUp => self.code_scroll = self.code_scroll.saturating_sub(1),
+            KeyCode::Left => self.input_cursor = self.input_cursor.saturating_sub(1),
+            KeyCode::Right => self.input_cursor = step(self.input_cursor, 1, query.chars().count() + 1),
+            KeyCode::Home => self.input_cursor = 0,
+            KeyCode::End => self.input_cursor = query.chars().count(),
+            KeyCode::Backspace if self.input_cursor > 0 => {
+                self.input_cursor -= 1;
+                query.remove(byte_index(query, self.input_cursor));
+                self.focus = PanelFocus::Right;
+                self.refresh_search()?;
+            }
+            KeyCode::Delete if self.input_cursor < query.chars().count() => {
+                query.remove(byte_index(query, self.input_cursor));
+                self.focus = PanelFocus::Right;
+                self.refresh_search()?;
+            }
+            KeyCode::Char(c) if !key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) => {
+                query.insert(byte_index(query, self.input_cursor), c);
+                self.input_cursor += 1;
+                self.focus = PanelFocus::Right;
+                self.refresh_search()?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Selects the file under the cursor and shows its commands, or
+    /// deselects it if it already is.
+    fn toggle_file(&mut self) -> Result<()> {
+        let Some(file) = self.files.get(self.file_cursor) else {
+            return Ok(());
+        };
+        if self.selected_file == Some(self.file_cursor) {
+            self.selected_file = None;
+            self.state = AppState::Default;
+            self.clear_commands();
+            return Ok(());
+        }
+
+        let path = PathBuf::from(&file.path);
+        self.commands = self.file_commands(&path)?.unwrap_or_default();
+        self.command_cursor = 0;
+        self.code_scroll = 0;
+        self.selected_file = Some(self.file_cursor);
+        self.left = LeftPanel::File(path.clone());
+        self.state = AppState::FileSelected(path);
+        self.focus = PanelFocus::Left;
+        Ok(())
+    }
+
+    /// Loads the commands of the file at `path`, grouped by type, or `None`
+    /// if the file is no longer tracked.
+    fn file_commands(&self, path: &Path) -> Result<Option<Vec<SearchResult>>> {
+        let path = path.to_string_lossy();
+        if self.file_index(&path).is_none() {
+            return Ok(None);
+        }
+        let mut commands = self.repo.list_commands(Some(&path), self.type_filter)?;
+        // Group by type, keeping sourcing order within each group.
+        commands.sort_by_key(|command| command.cmd_type);
+        let results = commands
+            .into_iter()
+            .map(|command| SearchResult { command, score: 0, matches: Vec::new() })
+            .collect();
+        Ok(Some(results))
+    }
+
+    /// Reports in the status line that the lists no longer refresh as files
+    /// change, because watching them failed with `error`.
+    pub fn watch_failed(&mut self, error: &str) {
+        self.message = Some(format!("Live updates stopped: {}", error));
+    }
+
+    fn file_index(&self, path: &str) -> Option<usize> {
+        self.files.iter().position(|f| f.path == path)
+    }
+
+    /// Runs the query being typed and lists its results. An empty query
+    /// clears them; an invalid one keeps them and sets `message`.
+    fn refresh_search(&mut self) -> Result<()> {
+        let AppState::SearchMode(query) = &self.state else {
+            return Ok(());
+        };
+        self.left = LeftPanel::Search;
+        if query.trim().is_empty() {
+            self.message = None;
+            self.commands.clear();
+            self.command_cursor = 0;
+            return Ok(());
+        }
+
+        match self.repo.search_commands(&self.engine, query, self.type_filter) {
+            Ok(results) => {
+                self.message = None;
+                self.commands = results;
+                self.command_cursor = 0;
+                self.code_scroll = 0;
+            }
+            Err(e @ (LscmdError::SearchPattern(_) | LscmdError::Validation(_))) => self.message = Some(e.to_string()),
+            Err(e) => return Err(e),
+        }
+        Ok(())
+    }
+
+    fn enter_search(&mut self) {
+        self.before_search = Some(Snapshot {
+            state: self.state.clone(),
+            focus: self.focus,
+            left: self.left.clone(),
+            commands: std::mem::take(&mut self.commands),
+            command_cursor: self.command_cursor,
+        });
+        self.focus = PanelFocus::Right;
+        self.left = LeftPanel::Search;
+        self.command_cursor = 0;
+        self.message = None;
+    }
+
+    /// Leaves search mode, restoring the state it was entered from.
+    fn exit_search(&mut self) {
+        self.message = None;
+        self.code_scroll = 0;
+        match self.before_search.take() {
+            Some(snapshot) => {
+                self.state = snapshot.state;
+                self.focus = snapshot.focus;
+                self.left = snapshot.left;
+                self.commands = snapshot.commands;
+                self.command_cursor = snapshot.command_cursor;
+            }
+            None => {
+                self.state = AppState::Default;
+                self.focus = PanelFocus::Right;
+                self.clear_commands();
+            }
+        }
+    }
+
+    fn focus_results(&mut self) {
+        if !self.commands.is_empty() {
+            self.focus = PanelFocus::Left;
+        }
+    }
+
+    fn move_command_cursor(&mut self, delta: isize) {
+        let cursor = step(self.command_cursor, delta, self.commands.len());
+        if cursor != self.command_cursor {
+            self.command_cursor = cursor;
+            self.code_scroll = 0;
+        }
+    }
+
+    fn clear_commands(&mut self) {
+        self.left = LeftPanel::Empty;
+        self.commands.clear();
+        self.command_cursor = 0;
+        self.code_scroll = 0;
+    }
+
+    fn other_focus(&self) -> PanelFocus {
+        match self.focus {
+            PanelFocus::Left => PanelFocus::Right,
+            PanelFocus::Right => PanelFocus::Left,
+        }
+    }
+}
+
+/// Moves `index` by `delta` within `0..len`, stopping at either end.
+fn step(index: usize, delta: isize, len: usize) -> usize {
+    index.saturating_add_signed(delta).min(len.saturating_sub(1))
+}
+
+/// The byte offset of the character at `chars` in `text`.
+fn byte_index(text: &str, chars: usize) -> usize {
+    text.char_indices().nth(chars).map_or(text.len(), |(index, _)| index)
+}
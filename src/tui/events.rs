@@ -1,2 +1,30 @@
-// Event handling placeholder
-// Will be implemented in Phase 6
\ No newline at end of file
+use crate::error::Result;
+use crossterm::event::{self, KeyEvent, KeyEventKind};
+use std::time::Duration;
+
+/// The terminal events the TUI reacts to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    Key(KeyEvent),
+    /// The terminal was resized and needs a redraw.
+    Resize,
+    /// Indexed files changed on disk, so the lists need reloading.
+    Refresh,
+    /// Watching the indexed files failed with this error, so the lists no
+    /// longer refresh.
+    WatchFailed(String),
+}
+
+/// Waits up to `timeout` for the next event, returning `None` if there was
+/// none. Key releases and repeats, which some terminals report, are skipped.
+pub fn poll(timeout: Duration) -> Result<Option<Event>> {
+    if !event::poll(timeout)? {
+        return Ok(None);
+    }
+    let event = match event::read()? {
+        event::Event::Key(key) if key.kind == KeyEventKind::Press => Some(Event::Key(key)),
+        event::Event::Resize(_, _) => Some(Event::Resize),
+        _ => None,
+    };
+    Ok(event)
+}
@@ -1,3 +1,135 @@
 pub mod app;
+pub mod events;
 pub mod ui;
-pub mod events;
\ No newline at end of file
+
+pub use app::{App, AppState, LeftPanel, PanelFocus};
+
+use crate::error::Result;
+use crate::search::SearchResult;
+use crate::utils::watcher::FileWatcher;
+use crossterm::execute;
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
+use events::Event;
+use ratatui::backend::{Backend, CrosstermBackend};
+use ratatui::Terminal;
+use std::fs::File;
+use std::io::{self, Write};
+use std::time::Duration;
+
+/// How long to wait for input before redrawing anyway.
+const TICK: Duration = Duration::from_millis(250);
+
+/// Runs `app` on the terminal until the user quits.
+///
+/// The terminal is switched to raw mode on the alternate screen, and
+/// restored on the way out, even if drawing or the repository fails.
+///
+/// With a `watcher`, changes to the alias directory are reindexed while
+/// the TUI is open and the lists refresh in place. If that fails, e.g.
+/// because `lscmd update` holds the database, the watcher is dropped and
+/// the error shown, but the TUI keeps running.
+pub fn run(app: &mut App, mut watcher: Option<FileWatcher>) -> Result<()> {
+    let repo = app.repo();
+    let mut terminal = TerminalGuard::enter(io::stdout())?;
+    run_app(&mut terminal.terminal, app, || {
+        if let Some(sync) = watcher.as_mut().map(|w| w.sync(repo, Duration::ZERO)) {
+            match sync {
+                Ok(Some(_)) => return Ok(Some(Event::Refresh)),
+                Ok(None) => {}
+                Err(e) => {
+                    watcher = None;
+                    return Ok(Some(Event::WatchFailed(e.to_string())));
+                }
+            }
+        }
+        events::poll(TICK)
+    })
+}
+
+/// Runs `app` as a picker and returns the command picked, or `None` if the
+/// user cancelled.
+///
+/// The TUI is drawn on `/dev/tty` rather than stdout, so it works inside
+/// `$(...)` and the caller can print the pick once the screen is restored.
+pub fn pick(app: &mut App) -> Result<Option<SearchResult>> {
+    let tty = File::options().read(true).write(true).open("/dev/tty")?;
+    let mut terminal = TerminalGuard::enter(tty)?;
+    run_app(&mut terminal.terminal, app, || events::poll(TICK))?;
+    Ok(app.picked().cloned())
+}
+
+/// Draws `app` and feeds it the events from `next_event` until it quits.
+///
+/// `next_event` returns `None` when there was no input in time; the frame is
+/// redrawn either way.
+pub fn run_app<B, F>(terminal: &mut Terminal<B>, app: &mut App, mut next_event: F) -> Result<()>
+where
+    B: Backend,
+    F: FnMut() -> Result<Option<Event>>,
+{
+    while !app.should_quit() {
+        terminal.draw(|frame| ui::draw(frame, app))?;
+        if let Some(event) = next_event()? {
+            handle_event(app, event)?;
+        }
+    }
+    Ok(())
+}
+
+/// Draws `app`, then feeds it `events` one at a time, redrawing after each,
+/// until they run out or it quits.
+///
+/// This is `run_app` for a scripted session: on a `TestBackend`, the buffer
+/// afterwards holds exactly what the user would be looking at.
+pub fn run_script<B, I>(terminal: &mut Terminal<B>, app: &mut App, events: I) -> Result<()>
+where
+    B: Backend,
+    I: IntoIterator<Item = Event>,
+{
+    terminal.draw(|frame| ui::draw(frame, app))?;
+    for event in events {
+        if app.should_quit() {
+            break;
+        }
+        handle_event(app, event)?;
+        terminal.draw(|frame| ui::draw(frame, app))?;
+    }
+    Ok(())
+}
+
+fn handle_event(app: &mut App, event: Event) -> Result<()> {
+    match event {
+        Event::Key(key) => app.handle_key(key),
+        Event::Resize => Ok(()),
+        Event::Refresh => app.reload(),
+        Event::WatchFailed(error) => {
+            app.watch_failed(&error);
+            Ok(())
+        }
+    }
+}
+
+/// Owns the terminal while the TUI runs and restores it when dropped.
+struct TerminalGuard<W: Write> {
+    terminal: Terminal<CrosstermBackend<W>>,
+}
+
+impl<W: Write> TerminalGuard<W> {
+    /// Takes over the terminal that `out` writes to.
+    fn enter(out: W) -> Result<Self> {
+        let terminal = Terminal::new(CrosstermBackend::new(out))?;
+        enable_raw_mode()?;
+        // From here on, dropping the guard puts the terminal back.
+        let mut guard = Self { terminal };
+        execute!(guard.terminal.backend_mut(), EnterAlternateScreen)?;
+        Ok(guard)
+    }
+}
+
+impl<W: Write> Drop for TerminalGuard<W> {
+    fn drop(&mut self) {
+        let _ = disable_raw_mode();
+        let _ = execute!(self.terminal.backend_mut(), LeaveAlternateScreen);
+        let _ = self.terminal.show_cursor();
+    }
+}
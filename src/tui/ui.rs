@@ -1,2 +1,425 @@
-// UI rendering placeholder
-// Will be implemented in Phase 6
\ No newline at end of file
+use super::app::{App, AppState, LeftPanel, PanelFocus};
+use crate::database::{Command, CommandType};
+use crate::display::{shell_tokens, TokenKind};
+use crate::search::{Field, SearchResult};
+use ratatui::backend::Backend;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, BorderType, Borders, List, ListItem, Paragraph, Wrap};
+use ratatui::Frame;
+use std::path::Path;
+
+// The palette from UI_DESIGN.md.
+const BACKGROUND: Color = Color::Rgb(46, 52, 64);
+const PANEL_BACKGROUND: Color = Color::Rgb(59, 66, 82);
+const SOFT_WHITE: Color = Color::Rgb(229, 233, 240);
+const TEXT: Color = Color::Rgb(236, 239, 244);
+const CURSOR: Color = Color::Rgb(94, 129, 172);
+const SELECTED: Color = Color::Rgb(163, 190, 140);
+const ALIAS: Color = Color::Rgb(163, 190, 140);
+const FUNCTION: Color = Color::Rgb(208, 135, 112);
+const MATCH: Color = Color::Rgb(235, 203, 139);
+const COUNT: Color = Color::Rgb(129, 161, 193);
+const ERROR: Color = Color::Rgb(191, 97, 106);
+const MUTED: Color = Color::Rgb(76, 86, 106);
+
+/// Names in the command list are padded to at most this many characters.
+const MAX_NAME_WIDTH: usize = 24;
+
+/// Renders the four panels of `app` onto `frame`.
+pub fn draw<B: Backend>(frame: &mut Frame<B>, app: &mut App) {
+    let outer = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(SOFT_WHITE))
+        .style(Style::default().bg(BACKGROUND).fg(TEXT));
+    let area = outer.inner(frame.size());
+    frame.render_widget(outer, frame.size());
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(20), Constraint::Percentage(60), Constraint::Percentage(20)])
+        .split(area);
+    let middle = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(67), Constraint::Percentage(33)])
+        .split(rows[1]);
+
+    draw_shortcuts(frame, app, rows[0]);
+    draw_commands(frame, app, middle[0]);
+    match app.state() {
+        AppState::SearchMode(_) => draw_search(frame, app, middle[1]),
+        _ => draw_files(frame, app, middle[1]),
+    }
+    draw_code(frame, app, rows[2]);
+}
+
+/// A panel frame, drawn thick and white when `focused`.
+fn panel(title: String, focused: bool) -> Block<'static> {
+    let (border_type, color) = match focused {
+        true => (BorderType::Thick, Color::White),
+        false => (BorderType::Plain, SOFT_WHITE),
+    };
+    Block::default()
+        .borders(Borders::ALL)
+        .border_type(border_type)
+        .border_style(Style::default().fg(color))
+        .title(Span::styled(title, Style::default().fg(TEXT).add_modifier(Modifier::BOLD)))
+        .style(Style::default().bg(PANEL_BACKGROUND).fg(TEXT))
+}
+
+fn cursor_style() -> Style {
+    Style::default().bg(CURSOR).fg(Color::White).add_modifier(Modifier::BOLD)
+}
+
+fn draw_shortcuts<B: Backend>(frame: &mut Frame<B>, app: &App, area: Rect) {
+    let rows: &[&[(&str, &str)]] = match (app.state(), app.is_picking()) {
+        (AppState::SearchMode(_), false) => &[
+            &[("Type", "Search"), ("↑↓", "Results"), ("Enter", "Browse results")],
+            &[("Esc", "Back"), ("/", "Leave search"), ("PgUp/PgDn", "Scroll code"), ("Ctrl-C", "Quit")],
+        ],
+        (AppState::SearchMode(_), true) => &[
+            &[("Type", "Search"), ("↑↓", "Results"), ("Enter", "Pick")],
+            &[("Esc", "Back"), ("/", "Leave search"), ("PgUp/PgDn", "Scroll code"), ("Ctrl-C", "Cancel")],
+        ],
+        (_, false) => &[
+            &[("↑↓", "Navigate"), ("Enter/Space", "Select"), ("Tab", "Switch Panel")],
+            &[("/", "Search"), ("→", "Back to files"), ("Esc", "Clear"), ("q", "Quit")],
+        ],
+        (_, true) => &[
+            &[("↑↓", "Navigate"), ("Enter", "Select file / pick command"), ("Tab", "Switch Panel")],
+            &[("/", "Search"), ("→", "Back to files"), ("Esc", "Clear / cancel"), ("q", "Cancel")],
+        ],
+    };
+    let lines: Vec<Line> = rows
+        .iter()
+        .map(|row| {
+            let mut spans = Vec::new();
+            for (key, action) in row.iter() {
+                spans.push(Span::styled(format!(" {}", key), Style::default().fg(COUNT).add_modifier(Modifier::BOLD)));
+                spans.push(Span::raw(format!(": {} ", action)));
+            }
+            Line::from(spans)
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines).block(panel(" Shortcuts ".to_string(), false)), area);
+}
+
+fn draw_commands<B: Backend>(frame: &mut Frame<B>, app: &mut App, area: Rect) {
+    let focused = app.focus() == PanelFocus::Left;
+    let (title, items, selected) = match app.left_panel().clone() {
+        LeftPanel::Empty => {
+            let hint = Paragraph::new(Line::styled("Select a file or press / to search", Style::default().fg(MUTED)))
+                .block(panel(" Commands ".to_string(), focused));
+            frame.render_widget(hint, area);
+            return;
+        }
+        LeftPanel::File(path) => {
+            let title = format!(" {} ({} items) ", file_name(&path.to_string_lossy()), app.commands().len());
+            if app.selected_file().is_some_and(|file| file.parse_status == "failed") {
+                let error = Paragraph::new(Line::styled(
+                    format!("Error parsing {}", file_name(&path.to_string_lossy())),
+                    Style::default().fg(ERROR),
+                ))
+                .block(panel(title, focused));
+                frame.render_widget(error, area);
+                return;
+            }
+            let (items, selected) = grouped_items(app.commands(), app.command_cursor());
+            (title, items, selected)
+        }
+        LeftPanel::Search => {
+            let title = format!(" Search results ({}) ", app.commands().len());
+            if app.commands().is_empty() {
+                let text = match app.search_query().is_some_and(|q| !q.trim().is_empty()) {
+                    true => "No matching commands found",
+                    false => "Start typing to search",
+                };
+                let empty = Paragraph::new(Line::styled(text, Style::default().fg(MUTED))).block(panel(title, focused));
+                frame.render_widget(empty, area);
+                return;
+            }
+            let width = name_width(app.commands());
+            let items = app.commands().iter().map(|result| ListItem::new(command_line(result, width, true))).collect();
+            (title, items, app.command_cursor())
+        }
+    };
+
+    let mut list = List::new(items).block(panel(title, focused));
+    if focused {
+        list = list.highlight_style(cursor_style()).highlight_symbol("> ");
+    } else {
+        list = list.highlight_symbol("  ");
+    }
+    app.command_list.select(Some(selected));
+    frame.render_stateful_widget(list, area, &mut app.command_list);
+}
+
+/// Lists `commands`, already sorted by type, under one header per type.
+/// Returns the items and the item index of the command at `cursor`.
+fn grouped_items(commands: &[SearchResult], cursor: usize) -> (Vec<ListItem<'static>>, usize) {
+    let width = name_width(commands);
+    let mut items = Vec::new();
+    let mut selected = 0;
+    for (index, result) in commands.iter().enumerate() {
+        let cmd_type = result.cmd_type;
+        if index == 0 || commands[index - 1].cmd_type != cmd_type {
+            let count = commands.iter().filter(|c| c.cmd_type == cmd_type).count();
+            if index > 0 {
+                items.push(ListItem::new(Line::from("")));
+            }
+            items.push(ListItem::new(Line::styled(
+                format!("{} {} ({})", type_icon(cmd_type), group_title(cmd_type), count),
+                Style::default().fg(type_color(cmd_type)).add_modifier(Modifier::BOLD),
+            )));
+        }
+        if index == cursor {
+            selected = items.len();
+        }
+        items.push(ListItem::new(command_line(result, width, false)));
+    }
+    (items, selected)
+}
+
+/// One row of the command list: the name, padded to `width`, and a summary.
+/// Search results also get a type icon and their score.
+fn command_line(result: &SearchResult, width: usize, search: bool) -> Line<'static> {
+    let mut spans = Vec::new();
+    if search {
+        spans.push(Span::styled(format!("{} ", type_icon(result.cmd_type)), Style::default().fg(type_color(result.cmd_type))));
+    }
+    spans.extend(highlighted(&result.name, name_ranges(result), Style::default().fg(TEXT)));
+    let padding = width.saturating_sub(result.name.chars().count());
+    spans.push(Span::raw(" ".repeat(padding + 2)));
+    if search {
+        spans.push(Span::styled(format!("{:>4}  ", result.score), Style::default().fg(COUNT)));
+    }
+    spans.push(Span::styled(summary(result), Style::default().fg(SOFT_WHITE)));
+    Line::from(spans)
+}
+
+/// Splits `text` into spans, with the `ranges` painted as search matches.
+fn highlighted(text: &str, ranges: &[std::ops::Range<usize>], style: Style) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut end = 0;
+    for range in ranges {
+        if range.start < end || range.end > text.len() {
+            continue;
+        }
+        if range.start > end {
+            spans.push(Span::styled(text[end..range.start].to_string(), style));
+        }
+        spans.push(Span::styled(text[range.clone()].to_string(), Style::default().bg(MATCH).fg(BACKGROUND)));
+        end = range.end;
+    }
+    if end < text.len() {
+        spans.push(Span::styled(text[end..].to_string(), style));
+    }
+    spans
+}
+
+fn name_ranges(result: &SearchResult) -> &[std::ops::Range<usize>] {
+    result
+        .matches
+        .iter()
+        .find(|m| m.field == Field::Name)
+        .map_or(&[], |m| m.ranges.as_slice())
+}
+
+fn name_width(commands: &[SearchResult]) -> usize {
+    commands.iter().map(|c| c.name.chars().count()).max().unwrap_or(0).min(MAX_NAME_WIDTH)
+}
+
+/// The first line of the description, or else of the code; alias values
+/// are quoted as they would be written.
+fn summary(command: &Command) -> String {
+    if let Some(line) = command.description.as_deref().and_then(|d| d.lines().next()) {
+        return line.to_string();
+    }
+    let first = command.code.lines().map(str::trim).find(|line| !line.is_empty()).unwrap_or_default();
+    match command.cmd_type {
+        CommandType::Alias => format!("\"{}\"", first),
+        _ => first.to_string(),
+    }
+}
+
+fn draw_files<B: Backend>(frame: &mut Frame<B>, app: &mut App, area: Rect) {
+    let focused = app.focus() == PanelFocus::Right;
+    let title = format!(" Files ({}) ", app.files().len());
+    if app.files().is_empty() {
+        let empty = Paragraph::new(Line::styled("No .sh files found", Style::default().fg(MUTED))).block(panel(title, focused));
+        frame.render_widget(empty, area);
+        return;
+    }
+
+    let width = app.files().iter().map(|f| file_name(&f.path).chars().count()).max().unwrap_or(0);
+    let selected = app.selected_file().map(|file| file.path.clone());
+    let items: Vec<ListItem> = app
+        .files()
+        .iter()
+        .map(|file| {
+            let name = file_name(&file.path);
+            let padding = " ".repeat(width - name.chars().count());
+            let count = match file.parse_status.as_str() {
+                "failed" => Span::styled(" (error)", Style::default().fg(ERROR)),
+                _ => Span::styled(format!(" ({})", file.command_count), Style::default().fg(COUNT)),
+            };
+            let item = ListItem::new(Line::from(vec![Span::raw(format!("{}{}", name, padding)), count]));
+            match selected.as_deref() == Some(file.path.as_str()) {
+                true => item.style(Style::default().bg(SELECTED).fg(Color::White)),
+                false => item,
+            }
+        })
+        .collect();
+
+    let mut list = List::new(items).block(panel(title, focused));
+    if focused {
+        list = list.highlight_style(cursor_style()).highlight_symbol("> ");
+    } else {
+        list = list.highlight_symbol("  ");
+    }
+    app.file_list.select(Some(app.file_cursor()));
+    frame.render_stateful_widget(list, area, &mut app.file_list);
+}
+
+fn draw_search<B: Backend>(frame: &mut Frame<B>, app: &App, area: Rect) {
+    let focused = app.focus() == PanelFocus::Right;
+    let block = panel(" Search ".to_string(), focused);
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Length(1), Constraint::Min(0)])
+        .split(inner);
+
+    let query = app.search_query().unwrap_or_default();
+    let input = Paragraph::new(query.to_string())
+        .style(Style::default().bg(Color::White).fg(BACKGROUND))
+        .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(SOFT_WHITE)));
+    frame.render_widget(input, rows[0]);
+    if focused {
+        // Inside the input's border; long queries keep the cursor in view.
+        let column = (app.input_cursor() as u16).min(rows[0].width.saturating_sub(3));
+        frame.set_cursor(rows[0].x + 1 + column, rows[0].y + 1);
+    }
+
+    let status = match app.message() {
+        Some(message) => Line::styled(message.to_string(), Style::default().fg(ERROR)),
+        None if query.trim().is_empty() => Line::styled("Type to search", Style::default().fg(MUTED)),
+        None => Line::styled(format!("{} matches found", app.commands().len()), Style::default().fg(SELECTED)),
+    };
+    frame.render_widget(Paragraph::new(vec![Line::from(""), status]).wrap(Wrap { trim: true }), rows[1].union(rows[2]));
+}
+
+fn draw_code<B: Backend>(frame: &mut Frame<B>, app: &App, area: Rect) {
+    let Some(result) = app.current_command() else {
+        let (title, lines) = placeholder(app);
+        frame.render_widget(Paragraph::new(lines).block(panel(title, false)).wrap(Wrap { trim: false }), area);
+        return;
+    };
+
+    let name = match result.cmd_type {
+        CommandType::Function => format!("{}()", result.name),
+        _ => result.name.clone(),
+    };
+    let mut title = format!(" {} — {}:{} ", name, result.path, result.start_line);
+    if app.left_panel() == &LeftPanel::Search {
+        title.push_str(&format!("score {} ", result.score));
+    }
+
+    let mut lines: Vec<Line> = result
+        .description
+        .iter()
+        .flat_map(|d| d.lines())
+        .map(|line| Line::styled(format!("# {}", line), Style::default().fg(MUTED).add_modifier(Modifier::ITALIC)))
+        .collect();
+    lines.extend(highlight_lines(&result.code));
+    let code = Paragraph::new(lines).block(panel(title, false)).scroll((app.code_scroll(), 0));
+    frame.render_widget(code, area);
+}
+
+/// The bottom panel's content when no command is under the cursor.
+fn placeholder(app: &App) -> (String, Vec<Line<'static>>) {
+    let muted = Style::default().fg(MUTED);
+    if let (LeftPanel::File(_), Some(file)) = (app.left_panel(), app.selected_file()) {
+        if file.parse_status == "failed" {
+            let error = file.error_message.clone().unwrap_or_default();
+            let lines = error.lines().map(|line| Line::styled(line.to_string(), Style::default().fg(ERROR))).collect();
+            return (" Parse errors ".to_string(), lines);
+        }
+    }
+    if app.search_query().is_some_and(|q| !q.trim().is_empty()) {
+        let tips = [
+            "Words match names, descriptions and code: git push",
+            "Restrict to a field: name:git* type:function code:\"kubectl get\"",
+            "Exclude with -word or NOT, combine with AND, OR and parentheses",
+        ];
+        return (" Search tips ".to_string(), tips.iter().map(|tip| Line::styled(*tip, muted)).collect());
+    }
+    (
+        " lscmd ".to_string(),
+        vec![Line::styled("Welcome to lscmd: pick a file on the right, or press / to search.", muted)],
+    )
+}
+
+/// Highlights shell `code` line by line, with the colors of `lscmd show`.
+fn highlight_lines(code: &str) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    let mut spans = Vec::new();
+    for (kind, range) in shell_tokens(code) {
+        for (index, part) in code[range].split('\n').enumerate() {
+            if index > 0 {
+                lines.push(Line::from(std::mem::take(&mut spans)));
+            }
+            if !part.is_empty() {
+                spans.push(Span::styled(part.to_string(), token_style(kind)));
+            }
+        }
+    }
+    lines.push(Line::from(spans));
+    lines
+}
+
+fn token_style(kind: TokenKind) -> Style {
+    match kind {
+        TokenKind::Plain => Style::default().fg(TEXT),
+        TokenKind::Keyword => Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD),
+        TokenKind::String => Style::default().fg(SELECTED),
+        TokenKind::Variable => Style::default().fg(Color::Cyan),
+        TokenKind::Comment => Style::default().fg(MUTED),
+        TokenKind::Operator => Style::default().fg(MATCH),
+    }
+}
+
+fn type_icon(cmd_type: CommandType) -> &'static str {
+    match cmd_type {
+        CommandType::Alias => "📁",
+        CommandType::Function => "🔧",
+        CommandType::Variable => "📦",
+        CommandType::Keybinding => "⌨",
+    }
+}
+
+fn type_color(cmd_type: CommandType) -> Color {
+    match cmd_type {
+        CommandType::Alias => ALIAS,
+        CommandType::Function => FUNCTION,
+        CommandType::Variable | CommandType::Keybinding => COUNT,
+    }
+}
+
+fn group_title(cmd_type: CommandType) -> &'static str {
+    match cmd_type {
+        CommandType::Alias => "ALIASES",
+        CommandType::Function => "FUNCTIONS",
+        CommandType::Variable => "VARIABLES",
+        CommandType::Keybinding => "KEYBINDINGS",
+    }
+}
+
+fn file_name(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .map_or_else(|| path.to_string(), |name| name.to_string_lossy().into_owned())
+}
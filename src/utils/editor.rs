@@ -0,0 +1,64 @@
+use crate::error::{LscmdError, Result};
+use std::env;
+use std::path::Path;
+use std::process;
+
+/// The editor used when neither `$VISUAL` nor `$EDITOR` is set.
+const FALLBACK_EDITOR: &str = "vi";
+
+/// The user's editor, as configured through `$VISUAL` or `$EDITOR`.
+///
+/// The variable may carry arguments (e.g. `code --wait`); they are split on
+/// whitespace and passed before the file to open.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Editor {
+    pub program: String,
+    pub args: Vec<String>,
+}
+
+impl Editor {
+    /// Resolves the editor from `$VISUAL`, then `$EDITOR`, then `vi`.
+    ///
+    /// Variables that are unset or blank are skipped.
+    pub fn from_env() -> Self {
+        ["VISUAL", "EDITOR"]
+            .iter()
+            .filter_map(|var| env::var(var).ok())
+            .find_map(|spec| Self::parse(&spec))
+            .unwrap_or_else(|| Self {
+                program: FALLBACK_EDITOR.to_string(),
+                args: Vec::new(),
+            })
+    }
+
+    /// Parses an editor specification such as `nvim` or `code --wait`.
+    ///
+    /// Returns `None` if `spec` is blank.
+    pub fn parse(spec: &str) -> Option<Self> {
+        let mut words = spec.split_whitespace().map(str::to_string);
+        let program = words.next()?;
+        Some(Self {
+            program,
+            args: words.collect(),
+        })
+    }
+
+    /// Builds the process that opens `path` at the 1-based `line`, using the
+    /// `+N` convention understood by vi, emacs, nano and most other editors.
+    pub fn command(&self, path: &Path, line: usize) -> process::Command {
+        let mut command = process::Command::new(&self.program);
+        command.args(&self.args).arg(format!("+{}", line.max(1))).arg(path);
+        command
+    }
+
+    /// Opens `path` at `line` and waits for the editor to exit.
+    pub fn open(&self, path: &Path, line: usize) -> Result<()> {
+        let status = self.command(path, line).status().map_err(|e| {
+            LscmdError::Config(format!("failed to launch editor '{}': {}", self.program, e))
+        })?;
+        if !status.success() {
+            return Err(LscmdError::Config(format!("editor '{}' exited with {}", self.program, status)));
+        }
+        Ok(())
+    }
+}
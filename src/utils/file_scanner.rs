@@ -1,2 +1,419 @@
-// File discovery and monitoring placeholder
-// Will be implemented in Phase 5
\ No newline at end of file
+use crate::config::AliasRoot;
+use crate::database::operations::CommandRepository;
+use crate::database::FileRecord;
+use crate::error::{LscmdError, Result};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs::{self, Metadata};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use walkdir::WalkDir;
+
+/// A shell file discovered in an alias root.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileInfo {
+    /// The absolute path to the file.
+    pub path: PathBuf,
+    /// The last modification time of the file (Unix timestamp).
+    pub mtime: i64,
+    /// The size of the file in bytes.
+    pub size: u64,
+    /// The absolute path of the root the file was found under, or of the
+    /// rc file whose `source` statements led to it.
+    pub root: String,
+    /// The file's position in the shell's startup sequence, if it was
+    /// discovered by following `source` statements.
+    pub load_order: Option<usize>,
+}
+
+/// The outcome of walking one or more alias roots.
+///
+/// Entries that cannot be read (e.g. permission errors) are recorded in
+/// `errors` rather than failing the whole scan.
+#[derive(Debug, Default)]
+pub struct ScanResult {
+    /// All matching files found, sorted by path.
+    pub files: Vec<FileInfo>,
+    /// Errors for entries that could not be visited.
+    pub errors: Vec<LscmdError>,
+}
+
+/// Discovers the shell files below an alias root.
+#[derive(Debug, Clone)]
+pub struct FileScanner {
+    alias_dir: PathBuf,
+    /// `alias_dir` made absolute, the prefix of every path found.
+    root: PathBuf,
+    include: GlobSet,
+    exclude: GlobSet,
+    max_depth: Option<usize>,
+}
+
+impl FileScanner {
+    /// Creates a scanner for every `*.sh` file below `alias_dir`.
+    pub fn new(alias_dir: impl Into<PathBuf>) -> Self {
+        Self::for_root(&AliasRoot::new(alias_dir)).expect("the default include glob is valid")
+    }
+
+    /// Creates a scanner for `root`, honouring its globs and depth.
+    ///
+    /// # Errors
+    ///
+    /// Returns `LscmdError::Config` if one of the globs is invalid.
+    pub fn for_root(root: &AliasRoot) -> Result<Self> {
+        Ok(Self {
+            alias_dir: root.path.clone(),
+            root: std::path::absolute(&root.path).unwrap_or_else(|_| root.path.clone()),
+            include: glob_set(&root.include)?,
+            exclude: glob_set(&root.exclude)?,
+            max_depth: root.max_depth,
+        })
+    }
+
+    /// Returns the directory this scanner walks.
+    pub fn alias_dir(&self) -> &Path {
+        &self.alias_dir
+    }
+
+    /// Returns the absolute directory this scanner walks.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// The root as stored with every file found below it.
+    pub fn root_key(&self) -> String {
+        self.root.to_string_lossy().into_owned()
+    }
+
+    /// Whether `path` lies below the root and is matched by the include
+    /// globs, but neither it nor any directory above it by the excludes.
+    pub fn accepts(&self, path: &Path) -> bool {
+        let Ok(relative) = path.strip_prefix(&self.root) else {
+            return false;
+        };
+        let depth = relative.components().count();
+        depth > 0
+            && self.max_depth.is_none_or(|max| depth <= max + 1)
+            && !relative.ancestors().any(|a| !a.as_os_str().is_empty() && self.exclude.is_match(a))
+            && self.include.is_match(relative)
+    }
+
+    /// Recursively collects every matching file below the root.
+    ///
+    /// Symbolic links are followed, since alias files are commonly linked in
+    /// from a dotfiles repository.
+    ///
+    /// # Errors
+    ///
+    /// Returns `LscmdError::Config` if the alias directory does not exist or
+    /// is not a directory.
+    pub fn scan_all(&self) -> Result<ScanResult> {
+        if !self.alias_dir.is_dir() {
+            return Err(LscmdError::Config(format!(
+                "Alias directory '{}' does not exist",
+                self.alias_dir.display()
+            )));
+        }
+        Ok(self.scan_below(&self.root))
+    }
+
+    /// Collects every matching file below `dir`, which should lie inside
+    /// the root.
+    pub fn scan_below(&self, dir: &Path) -> ScanResult {
+        let mut result = ScanResult::default();
+        // Excluded directories and those past the depth limit are never entered.
+        let walk = WalkDir::new(dir).follow_links(true).into_iter().filter_entry(|entry| {
+            entry.depth() == 0 || !entry.file_type().is_dir() || self.enters(entry.path())
+        });
+        for entry in walk {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => {
+                    result.errors.push(LscmdError::Io(std::io::Error::other(e.to_string())));
+                    continue;
+                }
+            };
+            if !entry.file_type().is_file() || !self.accepts(entry.path()) {
+                continue;
+            }
+            match entry.metadata() {
+                Ok(metadata) => result.files.push(FileInfo {
+                    path: entry.into_path(),
+                    mtime: mtime_secs(&metadata),
+                    size: metadata.len(),
+                    root: self.root_key(),
+                    load_order: None,
+                }),
+                Err(e) => result
+                    .errors
+                    .push(LscmdError::Io(std::io::Error::other(e.to_string()))),
+            }
+        }
+
+        result.files.sort_by(|a, b| a.path.cmp(&b.path));
+        result
+    }
+
+    /// Whether the walk should descend into the directory at `path`.
+    fn enters(&self, path: &Path) -> bool {
+        let Ok(relative) = path.strip_prefix(&self.root) else {
+            return false;
+        };
+        self.max_depth.is_none_or(|max| relative.components().count() <= max) && !self.exclude.is_match(relative)
+    }
+}
+
+/// Scans every root in turn and merges the results.
+///
+/// A file below several roots is claimed by the first one, as if the
+/// roots were sourced in order.
+///
+/// # Errors
+///
+/// Returns `LscmdError::Config` if any root does not exist, rather than
+/// treating all of its files as deleted.
+pub fn scan_roots(scanners: &[FileScanner]) -> Result<ScanResult> {
+    let mut result = ScanResult::default();
+    for scanner in scanners {
+        let scan = scanner.scan_all()?;
+        result.files.extend(scan.files);
+        result.errors.extend(scan.errors);
+    }
+    // The sort is stable, so the first root's copy of a path comes first.
+    result.files.sort_by(|a, b| a.path.cmp(&b.path));
+    result.files.dedup_by(|later, first| later.path == first.path);
+    Ok(result)
+}
+
+/// Compiles `patterns` into a single matcher.
+fn glob_set(patterns: &[String]) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob = Glob::new(pattern).map_err(|e| LscmdError::Config(format!("Invalid glob '{}': {}", pattern, e)))?;
+        builder.add(glob);
+    }
+    builder.build().map_err(|e| LscmdError::Config(e.to_string()))
+}
+
+/// Returns the modification time from `metadata` as a Unix timestamp.
+///
+/// Falls back to `0` on platforms or filesystems that do not record it.
+pub fn mtime_secs(metadata: &Metadata) -> i64 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// Returns a stable hex digest of `bytes` for change detection.
+///
+/// This is 64-bit FNV-1a: it is not cryptographic, but it is fast, has no
+/// dependencies and, unlike `DefaultHasher`, never changes between Rust
+/// releases, so stored hashes stay comparable across upgrades.
+pub fn content_hash(bytes: &[u8]) -> String {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let hash = bytes
+        .iter()
+        .fold(OFFSET_BASIS, |hash, &b| (hash ^ b as u64).wrapping_mul(PRIME));
+    format!("{:016x}", hash)
+}
+
+/// The difference between the alias directory and the database.
+#[derive(Debug, Default)]
+pub struct ChangeSet {
+    /// Files that are not in the database yet.
+    pub added: Vec<FileInfo>,
+    /// Files whose content differs from what was indexed.
+    pub modified: Vec<FileInfo>,
+    /// Files whose content hash did not change, but whose mtime, root or
+    /// load order did, or that were indexed too early to trust their mtime.
+    pub touched: Vec<FileInfo>,
+    /// Indexed paths that no longer exist on disk.
+    pub deleted: Vec<String>,
+    /// Number of files that need no work at all.
+    pub unchanged: usize,
+}
+
+impl ChangeSet {
+    /// Whether the database is already in sync with the alias directory.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.modified.is_empty()
+            && self.touched.is_empty()
+            && self.deleted.is_empty()
+    }
+
+    /// The files that have to be reparsed.
+    pub fn to_parse(&self) -> Vec<&FileInfo> {
+        self.added.iter().chain(&self.modified).collect()
+    }
+}
+
+/// Compares scanned files against the state stored in the database.
+pub struct ChangeDetector<'a> {
+    repo: &'a dyn CommandRepository,
+    now: i64,
+}
+
+impl<'a> ChangeDetector<'a> {
+    pub fn new(repo: &'a dyn CommandRepository) -> Self {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+        Self { repo, now }
+    }
+
+    /// Sets the current time, in seconds since the epoch, that mtimes are
+    /// compared against.
+    pub fn with_now(mut self, now: i64) -> Self {
+        self.now = now;
+        self
+    }
+
+    /// Classifies every scanned file as added, modified, touched or unchanged,
+    /// and every indexed path missing from the scan as deleted, so `scan`
+    /// should cover every root (see `scan_roots`).
+    ///
+    /// An mtime match is trusted only if the file was last modified strictly
+    /// before it was indexed. Otherwise (the file changed within the same
+    /// mtime tick as the scan, or the mtime moved at all) the content hash
+    /// decides, which covers coarse-mtime filesystems and checkouts that
+    /// rewrite mtimes without changing content. Such a file is touched once
+    /// the clock has moved past its mtime, so that the next check can trust
+    /// it; a file with an mtime in the future is left as it is.
+    ///
+    /// # Errors
+    ///
+    /// Returns `LscmdError::Database` if the stored file states cannot be read.
+    pub fn detect(&self, scan: &ScanResult) -> Result<ChangeSet> {
+        let mut known: HashMap<String, FileRecord> = self
+            .repo
+            .list_files()?
+            .into_iter()
+            .map(|record| (record.path.clone(), record))
+            .collect();
+
+        let mut changes = ChangeSet::default();
+        for file in &scan.files {
+            let record = known.remove(file.path.to_string_lossy().as_ref());
+            classify(file, record.as_ref(), self.now, &mut changes);
+        }
+
+        changes.deleted = known.into_keys().collect();
+        changes.deleted.sort();
+        Ok(changes)
+    }
+
+    /// Classifies only `paths`, as reported by a file watcher, instead of
+    /// every file below `scanners`.
+    ///
+    /// A path that is a directory stands for every matching file below it,
+    /// and a path that no longer exists for every indexed file at or below
+    /// it, so moving a whole directory in or out is picked up too. Existing
+    /// files that no scanner accepts are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns `LscmdError::Database` if the stored file states cannot be read.
+    pub fn detect_paths(&self, scanners: &[FileScanner], paths: &[PathBuf]) -> Result<ChangeSet> {
+        let known: HashMap<String, FileRecord> = self
+            .repo
+            .list_files()?
+            .into_iter()
+            .map(|record| (record.path.clone(), record))
+            .collect();
+
+        let mut present = BTreeMap::new();
+        let mut deleted = BTreeSet::new();
+        for path in paths {
+            if path.is_dir() {
+                // Anything still below it is taken off `deleted` again below.
+                deleted.extend(indexed_below(&known, path));
+                for scanner in scanners.iter().filter(|s| path.starts_with(s.root())) {
+                    for file in scanner.scan_below(path).files {
+                        present.entry(file.path.clone()).or_insert(file);
+                    }
+                }
+            } else if path.is_file() {
+                // The first root to accept a file claims it, as in a full scan.
+                let file = scanners
+                    .iter()
+                    .find(|s| s.accepts(path))
+                    .and_then(|scanner| file_info(path, scanner.root_key()));
+                if let Some(file) = file {
+                    present.insert(path.clone(), file);
+                }
+            } else {
+                deleted.extend(indexed_below(&known, path));
+            }
+        }
+
+        let mut changes = ChangeSet::default();
+        for file in present.values() {
+            deleted.remove(file.path.to_string_lossy().as_ref());
+            classify(file, known.get(file.path.to_string_lossy().as_ref()), self.now, &mut changes);
+        }
+        changes.deleted = deleted.into_iter().collect();
+        Ok(changes)
+    }
+}
+
+/// Sorts `file` into `changes` by comparing it against its stored `record`.
+///
+/// An mtime match is trusted only if the file was last modified strictly
+/// before it was indexed; see `ChangeDetector::detect`.
+fn classify(file: &FileInfo, record: Option<&FileRecord>, now: i64, changes: &mut ChangeSet) {
+    let Some(record) = record else {
+        changes.added.push(file.clone());
+        return;
+    };
+
+    // Commands read their root and load order from the file row, so a file
+    // that now belongs to another root or is sourced at another point only
+    // needs that row updated.
+    let same_root = record.root.as_deref() == Some(file.root.as_str()) && record.load_order == file.load_order;
+    let trusted = record.indexed_at.is_some_and(|t| record.mtime < t);
+    if same_root && file.mtime == record.mtime && trusted {
+        changes.unchanged += 1;
+        return;
+    }
+
+    // Unreadable files count as modified so the parser reports the error.
+    let same_content = fs::read(&file.path)
+        .map(|bytes| content_hash(&bytes) == record.hash)
+        .unwrap_or(false);
+    if !same_content {
+        changes.modified.push(file.clone());
+    } else if !same_root || file.mtime != record.mtime {
+        changes.touched.push(file.clone());
+    } else if now > file.mtime {
+        // Touching it now moves `indexed_at` past the mtime, after which the
+        // mtime can be trusted.
+        changes.touched.push(file.clone());
+    } else {
+        changes.unchanged += 1;
+    }
+}
+
+/// Stats a single file found under `root`, returning `None` if it
+/// vanished in the meantime.
+pub(crate) fn file_info(path: &Path, root: String) -> Option<FileInfo> {
+    let metadata = fs::metadata(path).ok()?;
+    Some(FileInfo {
+        path: path.to_path_buf(),
+        mtime: mtime_secs(&metadata),
+        size: metadata.len(),
+        root,
+        load_order: None,
+    })
+}
+
+/// The indexed paths that are `path` itself or lie below it.
+fn indexed_below<'k>(known: &'k HashMap<String, FileRecord>, path: &'k Path) -> impl Iterator<Item = String> + 'k {
+    known.keys().filter(move |p| Path::new(p).starts_with(path)).cloned()
+}
@@ -1,2 +1,5 @@
+pub mod editor;
 pub mod file_scanner;
-pub mod parallel;
\ No newline at end of file
+pub mod parallel;
+pub mod sources;
+pub mod watcher;
\ No newline at end of file
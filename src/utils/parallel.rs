@@ -1,2 +1,77 @@
-// Parallel processing utilities placeholder
-// Will be implemented in Phase 5
\ No newline at end of file
+use crate::database::{Command, FileRecord};
+use crate::error::LscmdError;
+use crate::parser::ShellParser;
+use crate::utils::file_scanner::FileInfo;
+use rayon::prelude::*;
+use std::path::{Path, PathBuf};
+
+/// The parse outcome for a single file.
+///
+/// A file that cannot be read at all ends up with no commands, a single
+/// error and a 'failed' file record, so one bad file never prevents the
+/// others from being indexed.
+#[derive(Debug)]
+pub struct FileOutcome {
+    pub path: PathBuf,
+    pub commands: Vec<Command>,
+    pub errors: Vec<LscmdError>,
+    pub file: FileRecord,
+}
+
+/// Parses shell files concurrently on the rayon thread pool.
+#[derive(Debug, Default)]
+pub struct ParallelProcessor {
+    parser: ShellParser,
+}
+
+impl ParallelProcessor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses every file in `files` in parallel.
+    ///
+    /// Outcomes are returned in the same order as `files`, regardless of
+    /// which thread finished first.
+    pub fn process_files<P: AsRef<Path> + Sync>(&self, files: &[P]) -> Vec<FileOutcome> {
+        files
+            .par_iter()
+            .map(|path| self.process_file(path.as_ref()))
+            .collect()
+    }
+
+    /// Parses scanned files in parallel, recording the root each was found
+    /// under and its load order in its file record.
+    pub fn process_scanned(&self, files: &[&FileInfo]) -> Vec<FileOutcome> {
+        files
+            .par_iter()
+            .map(|info| {
+                let mut outcome = self.process_file(&info.path);
+                outcome.file.root = Some(info.root.clone());
+                outcome.file.load_order = info.load_order;
+                outcome
+            })
+            .collect()
+    }
+
+    fn process_file(&self, path: &Path) -> FileOutcome {
+        match self.parser.parse_file(path) {
+            Ok(result) => FileOutcome {
+                path: path.to_path_buf(),
+                commands: result.commands,
+                errors: result.errors,
+                file: result.file,
+            },
+            Err(e) => {
+                let errors = vec![e];
+                let file = FileRecord::from_parse(&path.to_string_lossy(), 0, 0, String::new(), 0, &errors);
+                FileOutcome {
+                    path: path.to_path_buf(),
+                    commands: Vec::new(),
+                    errors,
+                    file,
+                }
+            }
+        }
+    }
+}
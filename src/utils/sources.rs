@@ -0,0 +1,768 @@
+use crate::config::Config;
+use crate::error::{LscmdError, Result};
+use crate::utils::file_scanner::{file_info, FileInfo, ScanResult};
+use globset::GlobBuilder;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::env;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use walkdir::WalkDir;
+
+/// The rc files followed when none are configured: zsh's `.zshrc`, in
+/// `$ZDOTDIR` if it is set, and bash's `.bashrc`.
+pub fn default_rc_files(home: &Path, zdotdir: Option<&Path>) -> Vec<PathBuf> {
+    vec![zdotdir.unwrap_or(home).join(".zshrc"), home.join(".bashrc")]
+}
+
+/// Where a `source` or `.` statement appears.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceSite {
+    pub file: PathBuf,
+    /// The 1-based line of the statement.
+    pub line: usize,
+}
+
+/// A file the shell loads during startup.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadedFile {
+    /// The absolute path to the file.
+    pub path: PathBuf,
+    /// The rc file whose statements led to this file.
+    pub entry: PathBuf,
+    /// The statement that sources the file, or `None` for an rc file.
+    pub sourced_from: Option<SourceSite>,
+    /// How many `source` statements deep the file is; `0` for rc files.
+    pub depth: usize,
+}
+
+/// Why a `source` statement was not followed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipReason {
+    /// Nothing exists at the path, as with an optional
+    /// `[ -f ~/.local.sh ] && . ~/.local.sh`.
+    Missing,
+    /// The path depends on a variable or command that is only known when
+    /// the shell runs, e.g. `$(brew --prefix)`.
+    Unexpanded,
+}
+
+/// A `source` statement that was not followed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedSource {
+    pub site: SourceSite,
+    /// The path it sources: expanded if it is missing, as written otherwise.
+    pub argument: String,
+    pub reason: SkipReason,
+}
+
+/// The files reached by following the rc files, in load order.
+#[derive(Debug, Default)]
+pub struct LoadOrder {
+    /// Every file loaded, in the order the shell first sources it.
+    pub files: Vec<LoadedFile>,
+    /// The statements that could not be followed.
+    pub skipped: Vec<SkippedSource>,
+    /// The directories of the loaded files and of the globs expanded on the
+    /// way, where an edit can change what is loaded.
+    pub dirs: BTreeSet<PathBuf>,
+}
+
+impl LoadOrder {
+    /// The loaded files as a scan to index.
+    ///
+    /// Each file is rooted at the rc file that led to it and carries its
+    /// position in the load order. Statements that could not be expanded
+    /// are reported as `LscmdError::Parse`; missing files are not, since
+    /// sourcing optional files is common.
+    pub fn scan(&self) -> ScanResult {
+        let mut result = ScanResult::default();
+        for (index, loaded) in self.files.iter().enumerate() {
+            if let Some(mut file) = file_info(&loaded.path, loaded.entry.to_string_lossy().into_owned()) {
+                file.load_order = Some(index);
+                result.files.push(file);
+            }
+        }
+        result.files.sort_by(|a, b| a.path.cmp(&b.path));
+
+        result.errors = self
+            .skipped
+            .iter()
+            .filter(|s| s.reason == SkipReason::Unexpanded)
+            .map(|s| LscmdError::Parse {
+                file: s.site.file.to_string_lossy().into_owned(),
+                line: s.site.line,
+                message: format!("Cannot follow `source {}` without running the shell", s.argument),
+            })
+            .collect();
+        result
+    }
+
+    /// The files in `scan`, e.g. of the alias roots, that nothing sources.
+    pub fn unsourced<'s>(&self, scan: &'s ScanResult) -> Vec<&'s FileInfo> {
+        let loaded: HashSet<_> = self.files.iter().map(|f| f.path.as_path()).collect();
+        scan.files
+            .iter()
+            .filter(|f| !loaded.contains(normalize(&f.path).as_path()))
+            .collect()
+    }
+}
+
+/// Finds the files the shell loads by following `source` and `.`
+/// statements from the rc files, without running anything.
+///
+/// Paths may use `~`, variables assigned earlier or passed in with
+/// `with_var` (including `${NAME:-default}`), the sourcing script's own
+/// directory (`${0:A:h}`, `$(dirname "$0")`, `${BASH_SOURCE%/*}`) and globs,
+/// also as the list of a `for` loop whose body sources the loop variable.
+/// Relative paths are taken from `$HOME`, where a login shell starts.
+/// Statements inside function bodies are not followed, since they only run
+/// when the function is called.
+#[derive(Debug, Clone)]
+pub struct SourceDiscovery {
+    rc_files: Vec<PathBuf>,
+    home: PathBuf,
+    vars: HashMap<String, String>,
+}
+
+impl SourceDiscovery {
+    /// Creates a discovery starting from `rc_files`, in order, with `HOME`
+    /// set to `home`.
+    pub fn new(rc_files: Vec<PathBuf>, home: impl Into<PathBuf>) -> Self {
+        let home = home.into();
+        let vars = HashMap::from([("HOME".to_string(), home.to_string_lossy().into_owned())]);
+        Self { rc_files, home, vars }
+    }
+
+    /// Creates a discovery from the configured rc files, or the defaults,
+    /// seeding `ZDOTDIR` and the XDG variables from the environment.
+    ///
+    /// # Errors
+    ///
+    /// Returns `LscmdError::XdgError` if the home directory cannot be determined.
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let home = dirs::home_dir().ok_or_else(|| LscmdError::XdgError("Home directory not found".to_string()))?;
+        let rc_files = if config.rc_files.is_empty() {
+            default_rc_files(&home, env::var_os("ZDOTDIR").map(PathBuf::from).as_deref())
+        } else {
+            config.rc_files.clone()
+        };
+
+        let mut discovery = Self::new(rc_files, home);
+        for name in ["ZDOTDIR", "XDG_CONFIG_HOME", "XDG_DATA_HOME"] {
+            if let Ok(value) = env::var(name) {
+                discovery = discovery.with_var(name, value);
+            }
+        }
+        Ok(discovery)
+    }
+
+    /// Sets a variable the rc files can refer to.
+    pub fn with_var(mut self, name: &str, value: impl Into<String>) -> Self {
+        self.vars.insert(name.to_string(), value.into());
+        self
+    }
+
+    /// The rc files discovery starts from, in order.
+    pub fn rc_files(&self) -> &[PathBuf] {
+        &self.rc_files
+    }
+
+    /// Follows every existing rc file in turn. Each starts out with only the
+    /// initial variables, as they belong to different shells; a file
+    /// reached from several of them is listed once.
+    pub fn discover(&self) -> LoadOrder {
+        let mut walker = Walker {
+            home: &self.home,
+            vars: HashMap::new(),
+            seen: HashSet::new(),
+            order: LoadOrder::default(),
+        };
+        for rc in &self.rc_files {
+            let path = walker.absolute(&rc.to_string_lossy());
+            if path.is_file() {
+                walker.vars = self.vars.clone();
+                walker.visit(&path, &path, None, 0);
+            }
+        }
+        walker.order
+    }
+}
+
+/// The file a `Walker` is currently reading.
+struct Script<'p> {
+    path: &'p Path,
+    entry: &'p Path,
+    depth: usize,
+}
+
+/// Follows `source` statements depth-first, as the shell runs them.
+struct Walker<'a> {
+    home: &'a Path,
+    vars: HashMap<String, String>,
+    seen: HashSet<PathBuf>,
+    order: LoadOrder,
+}
+
+impl Walker<'_> {
+    fn visit(&mut self, path: &Path, entry: &Path, sourced_from: Option<SourceSite>, depth: usize) {
+        if !self.seen.insert(path.to_path_buf()) {
+            return;
+        }
+        if let Some(dir) = path.parent() {
+            self.order.dirs.insert(dir.to_path_buf());
+        }
+        self.order.files.push(LoadedFile {
+            path: path.to_path_buf(),
+            entry: entry.to_path_buf(),
+            sourced_from,
+            depth,
+        });
+
+        // An unreadable file stays listed, so indexing reports the error.
+        let Ok(bytes) = fs::read(path) else {
+            return;
+        };
+        let tokens = tokenize(&String::from_utf8_lossy(&bytes));
+        self.run(&tokens, &Script { path, entry, depth });
+    }
+
+    /// Runs the commands in `tokens`, following the `source` statements
+    /// and tracking variable assignments.
+    fn run(&mut self, tokens: &[Token], script: &Script) {
+        let mut i = 0;
+        let mut at_start = true;
+        while i < tokens.len() {
+            let token = &tokens[i];
+            if token.is_separator() {
+                at_start = true;
+                i += 1;
+                continue;
+            }
+            if !at_start {
+                i += 1;
+                continue;
+            }
+
+            let next = tokens.get(i + 1).filter(|t| !t.is_separator());
+            match token.text.as_str() {
+                // Keywords after which another command starts.
+                "if" | "then" | "elif" | "else" | "fi" | "while" | "until" | "do" | "done" | "{" | "}" | "(" | ")" | "!" => {}
+                "for" => {
+                    if let Some(end) = self.run_for(tokens, i, script) {
+                        i = end;
+                        continue;
+                    }
+                    at_start = false;
+                }
+                "source" | "." => {
+                    if let Some(argument) = next {
+                        self.source(argument, script);
+                    }
+                    at_start = false;
+                }
+                "export" | "typeset" | "declare" | "readonly" | "local" => {
+                    while let Some(word) = tokens.get(i + 1).filter(|t| !t.is_separator()) {
+                        self.assign(&word.text, script);
+                        i += 1;
+                    }
+                    at_start = false;
+                }
+                "function" => {
+                    i = skip_function(tokens, i);
+                    continue;
+                }
+                text if next.is_some_and(|t| t.text == "()") || (text.len() > 2 && text.ends_with("()")) => {
+                    i = skip_function(tokens, i);
+                    continue;
+                }
+                // An assignment leaves the command position open.
+                text if self.assign(text, script) => {}
+                _ => at_start = false,
+            }
+            i += 1;
+        }
+    }
+
+    /// Runs `for NAME in WORDS; do BODY; done` starting at `tokens[start]`
+    /// once per expanded word, returning the index after `done`, or `None`
+    /// if the loop has another form.
+    fn run_for(&mut self, tokens: &[Token], start: usize, script: &Script) -> Option<usize> {
+        let name = &tokens.get(start + 1)?.text;
+        if !is_name(name) || tokens.get(start + 2)?.text != "in" {
+            return None;
+        }
+
+        let mut i = start + 3;
+        let mut words = Vec::new();
+        while let Some(token) = tokens.get(i).filter(|t| !t.is_separator() && t.text != "do") {
+            words.push(token);
+            i += 1;
+        }
+        while tokens.get(i).is_some_and(|t| t.text == ";") {
+            i += 1;
+        }
+        if tokens.get(i)?.text != "do" {
+            return None;
+        }
+
+        let body_start = i + 1;
+        let mut depth = 1;
+        let body_end = (body_start..tokens.len()).find(|&j| {
+            match tokens[j].text.as_str() {
+                "do" => depth += 1,
+                "done" => depth -= 1,
+                _ => {}
+            }
+            depth == 0
+        })?;
+
+        let mut values = Vec::new();
+        for word in words {
+            match self.expand_word(&word.text, script) {
+                Some(expanded) => values.extend(expanded),
+                None => self.skip(word, word.text.clone(), SkipReason::Unexpanded, script),
+            }
+        }
+        for value in values {
+            self.vars.insert(name.clone(), value);
+            self.run(&tokens[body_start..body_end], script);
+        }
+        Some(body_end + 1)
+    }
+
+    /// Follows `source ARGUMENT`.
+    fn source(&mut self, argument: &Token, script: &Script) {
+        let Some(paths) = self.expand_word(&argument.text, script) else {
+            self.skip(argument, argument.text.clone(), SkipReason::Unexpanded, script);
+            return;
+        };
+        if paths.is_empty() {
+            self.skip(argument, argument.text.clone(), SkipReason::Missing, script);
+        }
+        for path in paths {
+            let path = self.absolute(&path);
+            if path.is_file() {
+                let site = SourceSite { file: script.path.to_path_buf(), line: argument.line };
+                self.visit(&path, script.entry, Some(site), script.depth + 1);
+            } else {
+                self.skip(argument, path.display().to_string(), SkipReason::Missing, script);
+            }
+        }
+    }
+
+    fn skip(&mut self, token: &Token, argument: String, reason: SkipReason, script: &Script) {
+        self.order.skipped.push(SkippedSource {
+            site: SourceSite { file: script.path.to_path_buf(), line: token.line },
+            argument,
+            reason,
+        });
+    }
+
+    /// Records `NAME=value`, returning whether `word` is an assignment. A
+    /// value that cannot be expanded makes the variable unknown.
+    fn assign(&mut self, word: &str, script: &Script) -> bool {
+        let Some((name, value)) = word.split_once('=').filter(|(name, _)| is_name(name)) else {
+            return false;
+        };
+        match self.expand(value, script) {
+            Some(expanded) => self.vars.insert(name.to_string(), expanded.literal),
+            None => self.vars.remove(name),
+        };
+        true
+    }
+
+    /// Expands `word` to the paths it names: one, or every match of a glob,
+    /// sorted. Returns `None` if it cannot be expanded.
+    fn expand_word(&mut self, word: &str, script: &Script) -> Option<Vec<String>> {
+        let expanded = self.expand(word, script)?;
+        if !expanded.globbed {
+            return Some(vec![expanded.literal]);
+        }
+        let pattern = strip_qualifier(&expanded.pattern);
+        let pattern = if pattern.starts_with('/') {
+            pattern.to_string()
+        } else {
+            format!("{}/{}", escape_glob(&self.home.to_string_lossy()), pattern)
+        };
+        Some(self.glob(&pattern))
+    }
+
+    /// Performs tilde, parameter and quote expansion on `word`.
+    fn expand(&self, word: &str, script: &Script) -> Option<Expanded> {
+        let chars: Vec<char> = word.chars().collect();
+        let mut out = Expanded::default();
+        let mut i = 0;
+        if chars.first() == Some(&'~') && matches!(chars.get(1), None | Some('/')) {
+            out.push_str(&self.home.to_string_lossy());
+            i = 1;
+        }
+
+        let (mut single, mut double) = (false, false);
+        while let Some(&c) = chars.get(i) {
+            i += 1;
+            match c {
+                '\'' if !double => single = !single,
+                '"' if !single => double = !double,
+                '\\' if !single => {
+                    if let Some(&escaped) = chars.get(i) {
+                        out.push(escaped);
+                        i += 1;
+                    }
+                }
+                '$' if !single => {
+                    let (value, len) = self.parameter(&chars[i..], script)?;
+                    out.push_str(&value);
+                    i += len;
+                }
+                '*' | '?' | '[' if !single && !double => {
+                    out.globbed = true;
+                    out.literal.push(c);
+                    out.pattern.push(c);
+                }
+                c => out.push(c),
+            }
+        }
+        Some(out)
+    }
+
+    /// Expands the parameter after a `$` at the start of `rest`, returning
+    /// its value and how many characters it took up.
+    fn parameter(&self, rest: &[char], script: &Script) -> Option<(String, usize)> {
+        match rest.first() {
+            Some(&open @ ('{' | '(')) => {
+                let close = if open == '{' { '}' } else { ')' };
+                let mut depth = 0;
+                let end = rest.iter().position(|&c| {
+                    if c == open {
+                        depth += 1;
+                    } else if c == close {
+                        depth -= 1;
+                    }
+                    depth == 0
+                })?;
+                let inner: String = rest[1..end].iter().collect();
+                let value = if open == '{' {
+                    self.braced_parameter(&inner, script)?
+                } else {
+                    script_command(&inner, script.path)?
+                };
+                Some((value, end + 1))
+            }
+            Some('0') => Some((script.path.to_string_lossy().into_owned(), 1)),
+            Some(c) if c.is_ascii_alphabetic() || *c == '_' => {
+                let len = rest.iter().take_while(|c| c.is_ascii_alphanumeric() || **c == '_').count();
+                let name: String = rest[..len].iter().collect();
+                Some((self.vars.get(&name)?.clone(), len))
+            }
+            _ => Some(("$".to_string(), 0)),
+        }
+    }
+
+    /// Expands `${inner}`.
+    fn braced_parameter(&self, inner: &str, script: &Script) -> Option<String> {
+        match inner {
+            "0" | "0:A" | "0:a" | "BASH_SOURCE" | "BASH_SOURCE[0]" => {
+                return Some(script.path.to_string_lossy().into_owned());
+            }
+            "0:A:h" | "0:a:h" | "0:h" | "BASH_SOURCE%/*" | "BASH_SOURCE[0]%/*" => {
+                return Some(script_dir(script.path));
+            }
+            _ => {}
+        }
+
+        let len = inner.chars().take_while(|c| c.is_ascii_alphanumeric() || *c == '_').count();
+        let (name, operator) = inner.split_at(len);
+        if !is_name(name) {
+            return None;
+        }
+        let value = self.vars.get(name);
+        let default = ["-", ":-", "=", ":="]
+            .iter()
+            .find_map(|op| operator.strip_prefix(op).map(|default| (op.starts_with(':'), default)));
+        match (operator, default) {
+            ("", _) => value.cloned(),
+            (_, Some((unless_empty, default))) => match value {
+                Some(value) if !(unless_empty && value.is_empty()) => Some(value.clone()),
+                _ => Some(self.expand(default, script)?.literal),
+            },
+            _ => None,
+        }
+    }
+
+    /// Lists the paths matching the absolute glob `pattern`, sorted, and
+    /// records the directory searched.
+    fn glob(&mut self, pattern: &str) -> Vec<String> {
+        let Ok(glob) = GlobBuilder::new(pattern).literal_separator(true).backslash_escape(true).build() else {
+            return Vec::new();
+        };
+        let matcher = glob.compile_matcher();
+
+        let components: Vec<&str> = pattern.split('/').collect();
+        let literal = components.iter().take_while(|c| !has_glob(c)).count();
+        let base = PathBuf::from(unescape_glob(&components[..literal].join("/")));
+        let rest = &components[literal..];
+        self.order.dirs.insert(base.clone());
+
+        // As in the shell, `*` does not match a leading dot.
+        let dotted = rest.iter().any(|c| c.starts_with('.'));
+        let mut walk = WalkDir::new(&base).follow_links(true).sort_by_file_name().min_depth(1);
+        if !rest.contains(&"**") {
+            walk = walk.min_depth(rest.len()).max_depth(rest.len());
+        }
+        walk.into_iter()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| matcher.is_match(entry.path()))
+            .filter(|entry| {
+                dotted
+                    || !entry.path().strip_prefix(&base).is_ok_and(|relative| {
+                        relative.components().any(|c| c.as_os_str().to_string_lossy().starts_with('.'))
+                    })
+            })
+            .map(|entry| entry.path().to_string_lossy().into_owned())
+            .collect()
+    }
+
+    /// Resolves `path` against `$HOME` and removes `.` and `..`.
+    fn absolute(&self, path: &str) -> PathBuf {
+        normalize(&self.home.join(path))
+    }
+}
+
+/// A word after expansion.
+#[derive(Debug, Default)]
+struct Expanded {
+    /// The word with every character taken literally.
+    literal: String,
+    /// The word as a glob, with quoted and expanded characters escaped.
+    pattern: String,
+    /// Whether the word contains an unquoted glob character.
+    globbed: bool,
+}
+
+impl Expanded {
+    fn push(&mut self, c: char) {
+        self.literal.push(c);
+        self.pattern.push_str(&escape_glob(&c.to_string()));
+    }
+
+    fn push_str(&mut self, s: &str) {
+        self.literal.push_str(s);
+        self.pattern.push_str(&escape_glob(s));
+    }
+}
+
+/// Evaluates the command substitutions that name the running script's
+/// directory; no other command is run.
+fn script_command(command: &str, script: &Path) -> Option<String> {
+    let words: Vec<String> = command.split_whitespace().map(|w| w.replace('"', "")).collect();
+    match words.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+        ["dirname", "$0" | "${0}" | "$BASH_SOURCE" | "${BASH_SOURCE}" | "${BASH_SOURCE[0]}"] => Some(script_dir(script)),
+        _ => None,
+    }
+}
+
+fn script_dir(script: &Path) -> String {
+    script.parent().unwrap_or(script).to_string_lossy().into_owned()
+}
+
+/// Skips the function definition starting at `tokens[start]`, returning the
+/// index after its closing brace.
+fn skip_function(tokens: &[Token], start: usize) -> usize {
+    let mut i = start + 1;
+    // `function name`, `name ()` and a line break may come before the body.
+    while tokens
+        .get(i)
+        .is_some_and(|t| t.text != "{" && (i == start + 1 || t.text == "()" || t.text == ";"))
+    {
+        i += 1;
+    }
+    if tokens.get(i).is_none_or(|t| t.text != "{") {
+        return start + 1;
+    }
+
+    let mut depth = 0;
+    for (j, token) in tokens.iter().enumerate().skip(i) {
+        match token.text.as_str() {
+            "{" => depth += 1,
+            "}" => {
+                depth -= 1;
+                if depth == 0 {
+                    return j + 1;
+                }
+            }
+            _ => {}
+        }
+    }
+    tokens.len()
+}
+
+/// A word or control operator of a shell script.
+#[derive(Debug, Clone, PartialEq)]
+struct Token {
+    /// The word as written, quotes included; line breaks become `;`.
+    text: String,
+    /// The 1-based line the token starts on.
+    line: usize,
+}
+
+impl Token {
+    fn is_separator(&self) -> bool {
+        matches!(self.text.as_str(), ";" | "&&" | "||" | "|" | "&")
+    }
+}
+
+/// Splits `content` into words and control operators, dropping comments
+/// and keeping quoted strings, `$(...)` and `${...}` in one word.
+fn tokenize(content: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut word = String::new();
+    let mut word_line = 1;
+    let mut line = 1;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if word.is_empty() {
+            word_line = line;
+        }
+        match c {
+            ' ' | '\t' | '\r' => flush(&mut tokens, &mut word, word_line),
+            '\n' => {
+                flush(&mut tokens, &mut word, word_line);
+                tokens.push(Token { text: ";".to_string(), line });
+                line += 1;
+            }
+            '\\' => match chars.next() {
+                Some('\n') => line += 1,
+                Some(escaped) => {
+                    word.push(c);
+                    word.push(escaped);
+                }
+                None => {}
+            },
+            '#' if word.is_empty() => {
+                while chars.next_if(|&next| next != '\n').is_some() {}
+            }
+            '\'' | '"' => {
+                word.push(c);
+                while let Some(next) = chars.next() {
+                    word.push(next);
+                    if next == '\n' {
+                        line += 1;
+                    }
+                    if next == '\\' && c == '"' {
+                        if let Some(escaped) = chars.next() {
+                            word.push(escaped);
+                        }
+                    } else if next == c {
+                        break;
+                    }
+                }
+            }
+            '$' if matches!(chars.peek(), Some('(' | '{')) => {
+                let open = chars.next().unwrap_or_default();
+                let close = if open == '(' { ')' } else { '}' };
+                word.push(c);
+                word.push(open);
+                let mut depth = 1;
+                for next in chars.by_ref() {
+                    word.push(next);
+                    if next == '\n' {
+                        line += 1;
+                    } else if next == open {
+                        depth += 1;
+                    } else if next == close {
+                        depth -= 1;
+                        if depth == 0 {
+                            break;
+                        }
+                    }
+                }
+            }
+            ';' | '&' | '|' => {
+                flush(&mut tokens, &mut word, word_line);
+                let doubled = chars.next_if_eq(&c).is_some();
+                let text = match (c, doubled) {
+                    (';', _) => ";".to_string(),
+                    (_, true) => format!("{c}{c}"),
+                    (_, false) => c.to_string(),
+                };
+                tokens.push(Token { text, line });
+            }
+            c => word.push(c),
+        }
+    }
+    flush(&mut tokens, &mut word, word_line);
+    tokens
+}
+
+fn flush(tokens: &mut Vec<Token>, word: &mut String, line: usize) {
+    if !word.is_empty() {
+        tokens.push(Token { text: std::mem::take(word), line });
+    }
+}
+
+/// Whether `s` is a valid shell variable name.
+fn is_name(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Drops a trailing zsh glob qualifier, such as the `(N)` that makes
+/// `*.zsh(N)` expand to nothing rather than fail when nothing matches.
+fn strip_qualifier(pattern: &str) -> &str {
+    pattern
+        .strip_suffix(')')
+        .and_then(|p| p.rsplit_once('('))
+        .filter(|(_, qualifier)| qualifier.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '@' | '^')))
+        .map_or(pattern, |(p, _)| p)
+}
+
+/// Whether the glob component `s` contains an unescaped glob character.
+fn has_glob(s: &str) -> bool {
+    let mut escaped = false;
+    for c in s.chars() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '*' | '?' | '[' | '{' => return true,
+            _ => {}
+        }
+    }
+    false
+}
+
+fn escape_glob(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        if matches!(c, '*' | '?' | '[' | ']' | '{' | '}' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn unescape_glob(s: &str) -> String {
+    let mut unescaped = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        unescaped.push(if c == '\\' { chars.next().unwrap_or(c) } else { c });
+    }
+    unescaped
+}
+
+/// Removes `.` and `..` from `path` without touching the filesystem.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
@@ -0,0 +1,307 @@
+use crate::config::AliasRoot;
+use crate::database::operations::CommandRepository;
+use crate::error::{LscmdError, Result};
+use crate::utils::file_scanner::{file_info, scan_roots, ChangeDetector, ChangeSet, FileScanner, ScanResult};
+use crate::utils::parallel::{FileOutcome, ParallelProcessor};
+use crate::utils::sources::SourceDiscovery;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+/// How long the alias roots have to be quiet before changes are applied.
+///
+/// Editors tend to save through a temporary file and a rename, and `git
+/// checkout` touches many files at once; both should end up as one batch.
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Collects changed paths until no new change has arrived for a while.
+#[derive(Debug)]
+pub struct Debouncer {
+    quiet: Duration,
+    pending: BTreeSet<PathBuf>,
+    last_change: Option<Instant>,
+}
+
+impl Debouncer {
+    /// Creates a debouncer that releases a batch after `quiet` without changes.
+    pub fn new(quiet: Duration) -> Self {
+        Self {
+            quiet,
+            pending: BTreeSet::new(),
+            last_change: None,
+        }
+    }
+
+    /// Records that `path` changed at `at`, postponing the current batch.
+    pub fn add(&mut self, path: PathBuf, at: Instant) {
+        self.pending.insert(path);
+        self.last_change = Some(at);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// When the current batch will be released if nothing else changes.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.last_change.map(|at| at + self.quiet)
+    }
+
+    /// Takes every pending path, sorted, once nothing has changed for the
+    /// quiet period as of `now`; returns `None` until then.
+    pub fn take_ready(&mut self, now: Instant) -> Option<Vec<PathBuf>> {
+        if self.deadline()? > now {
+            return None;
+        }
+        self.last_change = None;
+        Some(std::mem::take(&mut self.pending).into_iter().collect())
+    }
+}
+
+/// What one debounced batch of changes did to the database.
+#[derive(Debug, Default)]
+pub struct SyncReport {
+    pub changes: ChangeSet,
+    /// The parse outcomes of the added and modified files.
+    pub outcomes: Vec<FileOutcome>,
+}
+
+/// Watches the alias roots, or the files the rc files source, and keeps
+/// the database in sync with them.
+///
+/// Uses inotify on Linux, and the native mechanism elsewhere. For roots,
+/// only the files a batch of events touched are classified and reparsed.
+pub struct FileWatcher {
+    scanners: Vec<FileScanner>,
+    /// Set when following the rc files instead of scanning roots.
+    sources: Option<SourceDiscovery>,
+    events: Receiver<notify::Result<notify::Event>>,
+    debouncer: Debouncer,
+    // Dropping the watcher stops the events.
+    watcher: RecommendedWatcher,
+    /// The directories watched without recursion for `sources`.
+    watched: BTreeSet<PathBuf>,
+}
+
+impl FileWatcher {
+    /// Starts watching every root in `roots` recursively, batching changes
+    /// that arrive less than `quiet` apart.
+    ///
+    /// # Errors
+    ///
+    /// Returns `LscmdError::Config` if a root does not exist or has an
+    /// invalid glob, or `LscmdError::Io` if it cannot be watched.
+    pub fn new(roots: &[AliasRoot], quiet: Duration) -> Result<Self> {
+        let scanners = roots.iter().map(FileScanner::for_root).collect::<Result<Vec<_>>>()?;
+
+        let (sender, events) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(sender).map_err(watch_error)?;
+        for scanner in &scanners {
+            if !scanner.root().is_dir() {
+                return Err(LscmdError::Config(format!(
+                    "Alias directory '{}' does not exist",
+                    scanner.alias_dir().display()
+                )));
+            }
+            // Watch the same absolute root the scanner walks, so event paths
+            // match the paths stored in the database.
+            watcher.watch(scanner.root(), RecursiveMode::Recursive).map_err(watch_error)?;
+        }
+        Ok(Self {
+            scanners,
+            sources: None,
+            events,
+            debouncer: Debouncer::new(quiet),
+            watcher,
+            watched: BTreeSet::new(),
+        })
+    }
+
+    /// Starts watching the directories of the files `discovery` finds,
+    /// batching changes that arrive less than `quiet` apart.
+    ///
+    /// Every batch follows the rc files again, so files that start or stop
+    /// being sourced are picked up, and their directories watched.
+    ///
+    /// # Errors
+    ///
+    /// Returns `LscmdError::Io` if the watcher cannot be started.
+    pub fn for_sources(discovery: SourceDiscovery, quiet: Duration) -> Result<Self> {
+        let (sender, events) = mpsc::channel();
+        let mut watcher = Self {
+            scanners: Vec::new(),
+            sources: None,
+            events,
+            debouncer: Debouncer::new(quiet),
+            watcher: notify::recommended_watcher(sender).map_err(watch_error)?,
+            watched: BTreeSet::new(),
+        };
+        watcher.watch_dirs(&discovery.discover().dirs);
+        watcher.sources = Some(discovery);
+        Ok(watcher)
+    }
+
+    /// Returns the scanners for the roots being watched, in order; none when
+    /// following the rc files.
+    pub fn scanners(&self) -> &[FileScanner] {
+        &self.scanners
+    }
+
+    /// Waits up to `timeout` for a debounced batch of changed paths.
+    ///
+    /// A batch that is still settling when `timeout` runs out is kept for
+    /// the next call, so this can be polled with a zero timeout.
+    ///
+    /// # Errors
+    ///
+    /// Returns `LscmdError::Io` if the watcher reports an error or stopped.
+    pub fn next_batch(&mut self, timeout: Duration) -> Result<Option<Vec<PathBuf>>> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let now = Instant::now();
+            if let Some(batch) = self.debouncer.take_ready(now) {
+                return Ok(Some(batch));
+            }
+            if now >= deadline {
+                return Ok(None);
+            }
+
+            let until = self.debouncer.deadline().map_or(deadline, |at| at.min(deadline));
+            match self.events.recv_timeout(until.saturating_duration_since(now)) {
+                Ok(Ok(event)) => {
+                    // Opening and reading a file is not a change.
+                    if matches!(event.kind, EventKind::Access(_)) {
+                        continue;
+                    }
+                    let at = Instant::now();
+                    for path in event.paths {
+                        self.debouncer.add(path, at);
+                    }
+                }
+                Ok(Err(e)) => return Err(watch_error(e)),
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err(LscmdError::Io(std::io::Error::other("File watcher stopped")));
+                }
+            }
+        }
+    }
+
+    /// Waits up to `timeout` for a batch of changes and applies it to `repo`.
+    ///
+    /// Returns `None` if nothing changed, or if the batch turned out not to
+    /// touch any indexed file (e.g. an editor's swap file).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if watching fails or the database cannot be written.
+    pub fn sync(&mut self, repo: &dyn CommandRepository, timeout: Duration) -> Result<Option<SyncReport>> {
+        let Some(paths) = self.next_batch(timeout)? else {
+            return Ok(None);
+        };
+        let changes = match &self.sources {
+            Some(discovery) => {
+                let order = discovery.discover();
+                self.watch_dirs(&order.dirs);
+                ChangeDetector::new(repo).detect(&order.scan())?
+            }
+            None => ChangeDetector::new(repo).detect_paths(&self.scanners, &paths)?,
+        };
+        if changes.is_empty() {
+            return Ok(None);
+        }
+        let outcomes = apply_changes(repo, &changes)?;
+        Ok(Some(SyncReport { changes, outcomes }))
+    }
+
+    /// Starts watching each of `dirs` not watched yet. A directory that
+    /// cannot be watched, e.g. because it was just removed, is skipped.
+    fn watch_dirs(&mut self, dirs: &BTreeSet<PathBuf>) {
+        for dir in dirs {
+            if !self.watched.contains(dir) && self.watcher.watch(dir, RecursiveMode::NonRecursive).is_ok() {
+                self.watched.insert(dir.clone());
+            }
+        }
+    }
+}
+
+/// Reparses the added and modified files in `changes` and writes them,
+/// together with the deletions, to `repo` in a single transaction.
+///
+/// A file that cannot be read keeps its previously indexed commands, as
+/// with `lscmd update`.
+///
+/// # Errors
+///
+/// Returns `LscmdError::Database` if the changes cannot be written.
+pub fn apply_changes(repo: &dyn CommandRepository, changes: &ChangeSet) -> Result<Vec<FileOutcome>> {
+    for file in &changes.touched {
+        repo.touch_file(&file.path.to_string_lossy(), file.mtime, &file.root, file.load_order)?;
+    }
+
+    let outcomes = ParallelProcessor::new().process_scanned(&changes.to_parse());
+    let readable: Vec<_> = outcomes
+        .iter()
+        .filter(|o| !o.errors.iter().any(|e| matches!(e, LscmdError::Io(_))))
+        .collect();
+    let files: Vec<_> = readable.iter().map(|o| o.file.clone()).collect();
+    let commands: Vec<_> = readable.iter().flat_map(|o| o.commands.iter().cloned()).collect();
+    repo.replace_files(&files, &commands, &changes.deleted)?;
+    Ok(outcomes)
+}
+
+/// Indexes the files the root `root` claims among `scanners`, replacing
+/// whatever was indexed under it.
+///
+/// Every root is scanned, so a file an earlier root also covers stays with
+/// that root, as in a full scan. Returns the claimed files and their parse
+/// outcomes.
+///
+/// # Errors
+///
+/// Returns `LscmdError::Config` if a root does not exist, or
+/// `LscmdError::Database` if the files cannot be written.
+pub fn index_root(
+    repo: &dyn CommandRepository,
+    scanners: &[FileScanner],
+    root: &str,
+) -> Result<(ScanResult, Vec<FileOutcome>)> {
+    let mut scan = scan_roots(scanners)?;
+    scan.files.retain(|file| file.root == root);
+
+    let outcomes = ParallelProcessor::new().process_scanned(&scan.files.iter().collect::<Vec<_>>());
+    let files: Vec<_> = outcomes.iter().map(|o| o.file.clone()).collect();
+    let commands: Vec<_> = outcomes.iter().flat_map(|o| o.commands.iter().cloned()).collect();
+    repo.replace_root(root, &files, &commands)?;
+    Ok((scan, outcomes))
+}
+
+/// Drops the files indexed under the root `root`, except those one of the
+/// remaining `scanners` still covers: these are reindexed under the first
+/// such root instead.
+///
+/// # Errors
+///
+/// Returns `LscmdError::Database` if the changes cannot be written.
+pub fn unindex_root(repo: &dyn CommandRepository, root: &str, scanners: &[FileScanner]) -> Result<SyncReport> {
+    let mut changes = ChangeSet::default();
+    for record in repo.list_files()?.into_iter().filter(|f| f.root.as_deref() == Some(root)) {
+        let path = PathBuf::from(&record.path);
+        let claimed = scanners
+            .iter()
+            .find(|s| s.accepts(&path))
+            .and_then(|scanner| file_info(&path, scanner.root_key()));
+        match claimed {
+            Some(file) => changes.modified.push(file),
+            None => changes.deleted.push(record.path),
+        }
+    }
+    let outcomes = apply_changes(repo, &changes)?;
+    Ok(SyncReport { changes, outcomes })
+}
+
+fn watch_error(err: notify::Error) -> LscmdError {
+    LscmdError::Io(std::io::Error::other(err.to_string()))
+}
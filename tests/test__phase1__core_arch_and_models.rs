@@ -1,7 +1,7 @@
 // tests/phase1.rs
 
-use lscmd::config::{Config, XdgPaths};
-use lscmd::database::Command;
+use lscmd::config::{AliasRoot, Config, XdgPaths};
+use lscmd::database::{Command, CommandType};
 use lscmd::error::LscmdError;
 use std::env;
 use std::fs;
@@ -26,11 +26,16 @@ fn setup_test_env() -> (tempfile::TempDir, PathBuf, PathBuf) {
 fn test_command_validation_success() {
     let cmd = Command {
         name: "my_func".to_string(),
-        cmd_type: "function".to_string(),
+        cmd_type: CommandType::Function,
         path: "/path/to/file.sh".to_string(),
+        start_line: 1,
+        end_line: 1,
+        byte_offset: 0,
         code: "echo hello".to_string(),
+        description: None,
         file_mtime: 12345,
         created_at: None,
+        root: None,
     };
     assert!(cmd.validate().is_ok());
 }
@@ -40,21 +45,25 @@ fn test_command_validation_failures() {
     // Empty name
     let mut cmd = Command {
         name: "".to_string(),
-        cmd_type: "function".to_string(),
+        cmd_type: CommandType::Function,
         path: "/path/to/file.sh".to_string(),
+        start_line: 1,
+        end_line: 1,
+        byte_offset: 0,
         code: "echo hello".to_string(),
+        description: None,
         file_mtime: 12345,
         created_at: None,
+        root: None,
     };
     assert!(matches!(cmd.validate(), Err(LscmdError::Validation(_))));
 
-    // Invalid type
+    // Invalid type: unknown names cannot become a `CommandType`
     cmd.name = "my_func".to_string();
-    cmd.cmd_type = "invalid_type".to_string();
-    assert!(matches!(cmd.validate(), Err(LscmdError::Validation(_))));
+    assert!(matches!("invalid_type".parse::<CommandType>(), Err(LscmdError::Validation(_))));
 
     // Empty path
-    cmd.cmd_type = "alias".to_string();
+    cmd.cmd_type = CommandType::Alias;
     cmd.path = "".to_string();
     assert!(matches!(cmd.validate(), Err(LscmdError::Validation(_))));
 
@@ -64,6 +73,29 @@ fn test_command_validation_failures() {
     assert!(matches!(cmd.validate(), Err(LscmdError::Validation(_))));
 }
 
+#[test]
+fn test_command_body_is_dedented() {
+    let mut cmd = Command {
+        name: "deploy".to_string(),
+        cmd_type: CommandType::Function,
+        path: "/path/to/file.sh".to_string(),
+        start_line: 1,
+        end_line: 6,
+        byte_offset: 0,
+        code: "\n    if [ -n \"$1\" ]; then\n\n        ./deploy.sh \"$1\"\n    fi\n".to_string(),
+        description: None,
+        file_mtime: 12345,
+        created_at: None,
+        root: None,
+    };
+    assert_eq!(cmd.body(), "if [ -n \"$1\" ]; then\n\n    ./deploy.sh \"$1\"\nfi");
+
+    // One-line bodies and alias values are left as they are.
+    cmd.cmd_type = CommandType::Alias;
+    cmd.code = "git log --oneline".to_string();
+    assert_eq!(cmd.body(), "git log --oneline");
+}
+
 #[test]
 #[cfg(unix)]
 fn test_xdg_paths_unix() {
@@ -108,16 +140,54 @@ fn test_config_load_and_save() {
     // Test creating a default config
     let config = Config::load().unwrap();
     let home = dirs::home_dir().unwrap();
-    assert_eq!(config.alias_path, home.join(".aliases"));
+    assert_eq!(config.roots, vec![AliasRoot::new(home.join(".aliases"))]);
 
     // Test saving and loading
     let mut new_config = config;
-    new_config.alias_path = PathBuf::from("/tmp/new_aliases");
+    let mut team = AliasRoot::new("/tmp/team_aliases");
+    team.label = Some("team".to_string());
+    team.exclude = vec!["private/**".to_string()];
+    team.max_depth = Some(1);
+    new_config.roots[0].path = PathBuf::from("/tmp/new_aliases");
+    new_config.add_root(team.clone()).unwrap();
     new_config.save().unwrap();
 
     let loaded_config = Config::load().unwrap();
-    assert_eq!(loaded_config.alias_path, new_config.alias_path);
+    assert_eq!(loaded_config.roots, new_config.roots);
     assert_eq!(loaded_config.version, new_config.version);
+    assert_eq!(loaded_config.find_root("team"), Some(&team));
+
+    // Configs from before roots existed keep their alias directory.
+    let config_path = XdgPaths::new().unwrap().config_file_path().unwrap();
+    fs::write(&config_path, r#"{"alias_path": "/tmp/old_aliases", "version": "0.1.0"}"#).unwrap();
+    let legacy = Config::load().unwrap();
+    assert_eq!(legacy.roots, vec![AliasRoot::new("/tmp/old_aliases")]);
+    legacy.save().unwrap();
+    assert!(!fs::read_to_string(&config_path).unwrap().contains("alias_path"));
+}
+
+#[test]
+fn test_config_roots_are_unique() {
+    let mut config: Config = serde_json::from_str(r#"{"roots": [{"path": "/a", "label": "team"}], "version": "0.1.0"}"#).unwrap();
+    assert_eq!(config.roots[0].include, vec!["*.sh"]);
+
+    assert!(matches!(config.add_root(AliasRoot::new("/a")), Err(LscmdError::Validation(_))));
+    let mut relabelled = AliasRoot::new("/b");
+    relabelled.label = Some("team".to_string());
+    assert!(matches!(config.add_root(relabelled), Err(LscmdError::Validation(_))));
+
+    config.add_root(AliasRoot::new("/b")).unwrap();
+    assert_eq!(config.remove_root("team").unwrap().path, PathBuf::from("/a"));
+    assert_eq!(config.remove_root("/b").unwrap().name(), "/b");
+    assert!(config.remove_root("/b").is_err());
+
+    // Roots are added with absolute paths but can be named relatively.
+    config.add_root(AliasRoot::new(std::path::absolute("team").unwrap())).unwrap();
+    config.add_root(AliasRoot::new(std::path::absolute("work/aliases").unwrap())).unwrap();
+    assert!(config.find_root("./team").is_some());
+    assert_eq!(config.remove_root("team").unwrap().path, std::path::absolute("team").unwrap());
+    assert!(config.remove_root("./work/aliases").is_ok());
+    assert!(config.roots.is_empty());
 }
 
 #[test]
@@ -134,3 +204,23 @@ fn test_error_conversions() {
     assert!(matches!(lscmd_err, LscmdError::Config(_)));
     assert!(lscmd_err.to_string().starts_with("Config error: missing field"));
 }
+
+#[test]
+fn test_command_type_conversions() {
+    assert_eq!("Alias".parse::<CommandType>().unwrap(), CommandType::Alias);
+    assert_eq!("keybinding".parse::<CommandType>().unwrap(), CommandType::Keybinding);
+    assert_eq!(format!("[{:<9}]", CommandType::Function), "[function ]");
+    assert_eq!(CommandType::names(), "alias, function, variable, keybinding");
+
+    // Serialized as the lowercase name, like in the database.
+    assert_eq!(serde_json::to_string(&CommandType::Variable).unwrap(), "\"variable\"");
+    assert_eq!(serde_json::from_str::<CommandType>("\"function\"").unwrap(), CommandType::Function);
+    assert!(serde_json::from_str::<CommandType>("\"script\"").is_err());
+
+    let conn = rusqlite::Connection::open_in_memory().unwrap();
+    let stored: String = conn.query_row("SELECT ?1", [CommandType::Keybinding], |row| row.get(0)).unwrap();
+    assert_eq!(stored, "keybinding");
+    let read: CommandType = conn.query_row("SELECT 'alias'", [], |row| row.get(0)).unwrap();
+    assert_eq!(read, CommandType::Alias);
+    assert!(conn.query_row("SELECT 'script'", [], |row| row.get::<_, CommandType>(0)).is_err());
+}
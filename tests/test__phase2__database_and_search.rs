@@ -1,8 +1,8 @@
 //! Integration tests for Phase 2: Database and Search.
 
 use lscmd::database::operations::{CommandRepository, SqliteCommandRepository};
-use lscmd::database::Command;
-use lscmd::error::Result;
+use lscmd::database::{Command, CommandType};
+use lscmd::error::{LscmdError, Result};
 use lscmd::search::SearchEngine;
 
 // --- Test Setup ---
@@ -23,27 +23,42 @@ fn get_mock_commands() -> Vec<Command> {
     vec![
         Command {
             name: "GitCommit".to_string(),
-            cmd_type: "alias".to_string(),
+            cmd_type: CommandType::Alias,
             path: "/files/g.sh".to_string(),
+            start_line: 1,
+            end_line: 1,
+            byte_offset: 0,
             code: "git commit -m".to_string(),
+            description: None,
             file_mtime: 100,
             created_at: None,
+            root: None,
         },
         Command {
             name: "DockerRun".to_string(),
-            cmd_type: "function".to_string(),
+            cmd_type: CommandType::Function,
             path: "/files/d.sh".to_string(),
+            start_line: 1,
+            end_line: 1,
+            byte_offset: 0,
             code: "docker run --rm -it".to_string(),
+            description: None,
             file_mtime: 200,
             created_at: None,
+            root: None,
         },
         Command {
             name: "rust_analyzer_status".to_string(),
-            cmd_type: "function".to_string(),
+            cmd_type: CommandType::Function,
             path: "/files/g.sh".to_string(), // Same path as GitCommit
+            start_line: 1,
+            end_line: 1,
+            byte_offset: 0,
             code: "echo 'Rust is running'".to_string(),
+            description: None,
             file_mtime: 100,
             created_at: None,
+            root: None,
         },
     ]
 }
@@ -81,6 +96,77 @@ fn test_search_engine_logic() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_search_matches_descriptions() -> Result<()> {
+    let mut commands = get_mock_commands();
+    commands[1].description = Some("Start a throwaway container".to_string());
+    let repo = setup_in_memory_db()?;
+    repo.batch_insert(&commands)?;
+
+    for engine in [SearchEngine::new(false), SearchEngine::new(true)] {
+        let found = repo.search_commands(&engine, "THROWAWAY", None)?;
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].name, "DockerRun");
+        assert_eq!(found[0].description.as_deref(), Some("Start a throwaway container"));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_search_results_are_ranked() -> Result<()> {
+    use lscmd::search::{SCORE_ALL_WORDS, SCORE_CODE, SCORE_DESCRIPTION, SCORE_EXACT_NAME, SCORE_NAME_PREFIX, SCORE_NAME_SUBSTRING};
+    let make = |name: &str, description: Option<&str>, code: &str| {
+        let mut command = get_mock_commands()[0].clone();
+        command.name = name.to_string();
+        command.description = description.map(str::to_string);
+        command.code = code.to_string();
+        command
+    };
+    let commands = vec![
+        make("deploy", None, &format!("{}git push", "echo step\n".repeat(39))),
+        make("sync", Some("Pull with git"), "git pull --rebase"),
+        make("legit", None, "echo"),
+        make("gitlog", None, "git log"),
+        make("git", None, "hub"),
+    ];
+
+    let names = |results: Vec<lscmd::search::SearchResult<&Command>>| -> Vec<(String, u32)> {
+        results.iter().map(|r| (r.name.clone(), r.score)).collect()
+    };
+    let engine = SearchEngine::new(false);
+    assert_eq!(
+        names(engine.search("git", &commands)?),
+        vec![
+            ("git".to_string(), SCORE_EXACT_NAME),
+            ("gitlog".to_string(), SCORE_NAME_PREFIX),
+            ("legit".to_string(), SCORE_NAME_SUBSTRING),
+            ("sync".to_string(), SCORE_DESCRIPTION),
+            ("deploy".to_string(), SCORE_CODE),
+        ]
+    );
+
+    // Matching every word beats a single strong match.
+    let results = engine.search("gitlog log", &commands)?;
+    assert_eq!((results[0].name.as_str(), results[0].score), ("gitlog", SCORE_EXACT_NAME + SCORE_NAME_SUBSTRING + SCORE_ALL_WORDS));
+    let results = engine.search("push deploy", &commands)?;
+    assert_eq!(results[0].score, SCORE_EXACT_NAME + SCORE_CODE + SCORE_ALL_WORDS);
+
+    // Regex mode ranks by where the pattern matched, too.
+    let results = SearchEngine::new(true).search("^git$|pull", &commands)?;
+    assert_eq!(names(results), vec![("git".to_string(), SCORE_EXACT_NAME), ("sync".to_string(), SCORE_DESCRIPTION)]);
+
+    // The repository returns the same ranking, with owned commands.
+    let repo = setup_in_memory_db()?;
+    let mut stored = commands.clone();
+    for (line, command) in stored.iter_mut().enumerate() {
+        command.start_line = line + 1;
+    }
+    repo.batch_insert(&stored)?;
+    let ranked: Vec<_> = repo.search_commands(&engine, "git", None)?.into_iter().map(|r| r.command.name).collect();
+    assert_eq!(ranked, vec!["git", "gitlog", "legit", "sync", "deploy"]);
+    Ok(())
+}
+
 // --- CRUD and Repository Tests ---
 
 #[test]
@@ -168,7 +254,7 @@ fn test_repo_search_integration() -> Result<()> {
 
     // Search using the repo, which integrates the search engine
     let engine = SearchEngine::new(false); // Word mode
-    let results = repo.search_commands(&engine, "RUST DOCKER")?;
+    let results = repo.search_commands(&engine, "RUST DOCKER", None)?;
 
     assert_eq!(results.len(), 2);
     let names: Vec<_> = results.iter().map(|c| c.name.as_str()).collect();
@@ -176,4 +262,362 @@ fn test_repo_search_integration() -> Result<()> {
     assert!(names.contains(&"rust_analyzer_status"));
 
     Ok(())
-}
\ No newline at end of file
+}
+
+// --- Suggestion Tests ---
+
+#[test]
+fn test_edit_distance() {
+    use lscmd::suggest::edit_distance;
+    assert_eq!(edit_distance("gco", "gco"), 0);
+    assert_eq!(edit_distance("gcp", "gco"), 1);
+    assert_eq!(edit_distance("gti", "git"), 1); // transposition
+    assert_eq!(edit_distance("", "abc"), 3);
+    assert_eq!(edit_distance("kitten", "sitting"), 3);
+}
+
+#[test]
+fn test_name_suggestions() -> Result<()> {
+    use lscmd::suggest::NameSuggester;
+    let names = ["gco", "gc", "git_checkout", "gst", "deploy", "docker_run", "ls"];
+    let suggester = NameSuggester::new(3);
+
+    // Typos and transpositions
+    assert_eq!(suggester.suggest_names("gcp", names), vec!["gc", "gco"]);
+    assert_eq!(suggester.suggest_names("DEPLOI", names), vec!["deploy"]);
+    // Truncated names and abbreviations
+    assert_eq!(suggester.suggest_names("dock", names), vec!["docker_run"]);
+    assert_eq!(suggester.suggest_names("gchk", names), vec!["git_checkout"]);
+    // Nothing close enough
+    assert!(suggester.suggest_names("zzz", names).is_empty());
+    assert!(suggester.suggest_names("gs", ["git_stash_list_all"]).is_empty());
+
+    // Scores are ordered and bounded.
+    let scored = NameSuggester::default().suggest("gco", names);
+    assert_eq!(scored[0].name, "gco");
+    assert!(scored.windows(2).all(|w| w[0].score >= w[1].score && w[0].score <= 1.0));
+
+    // Works straight off the repository's names.
+    let repo = setup_in_memory_db()?;
+    repo.batch_insert(&get_mock_commands())?;
+    let stored = repo.list_names()?;
+    assert_eq!(suggester.suggest_names("gitcomit", stored.iter().map(String::as_str)), vec!["GitCommit"]);
+    Ok(())
+}
+
+// --- Full-text Search Tests ---
+
+#[test]
+fn test_fts_query_translation() {
+    let engine = SearchEngine::new(false);
+    assert_eq!(engine.fts_query("git  Docker").as_deref(), Some("\"git\" OR \"Docker\""));
+    assert_eq!(engine.fts_query("say\"hi NEAR").as_deref(), Some("\"say\"\"hi\" OR \"NEAR\""));
+    // Too short for the trigram index, empty, or not a word query.
+    assert_eq!(engine.fts_query("git ls"), None);
+    assert_eq!(engine.fts_query("  "), None);
+    assert_eq!(SearchEngine::new(true).fts_query("git"), None);
+}
+
+#[test]
+fn test_fts_search_matches_fallback_and_stays_in_sync() -> Result<()> {
+    let repo = setup_in_memory_db()?;
+    let mut commands = Vec::new();
+    for i in 0..2000 {
+        let mut command = get_mock_commands()[i % 3].clone();
+        command.name = format!("{}{}", command.name, i);
+        command.path = format!("/files/{}.sh", i % 50);
+        commands.push(command);
+    }
+    repo.batch_insert(&commands)?;
+
+    // The index finds exactly what filtering every row in Rust finds.
+    let engine = SearchEngine::new(false);
+    let indexed = repo.search_commands(&engine, "RUST commit", None)?;
+    let mut expected: Vec<_> = engine.search("RUST commit", &commands)?.into_iter().map(|c| c.name.clone()).collect();
+    let mut found: Vec<_> = indexed.iter().map(|c| c.name.clone()).collect();
+    expected.sort();
+    found.sort();
+    assert_eq!(found.len(), 1333);
+    assert_eq!(found, expected);
+
+    // Name matches rank above code-only matches.
+    let ranked = repo.search_commands(&engine, "docker", None)?;
+    assert!(ranked.iter().all(|c| c.name.starts_with("DockerRun")));
+    let mut code_only = commands[1].clone();
+    code_only.name = "run_container".to_string();
+    repo.insert_command(&code_only)?;
+    let ranked = repo.search_commands(&engine, "docker", None)?;
+    assert_eq!(ranked.last().unwrap().name, "run_container");
+
+    // Updates and deletes reach the index through the triggers.
+    let mut edited = code_only.clone();
+    edited.code = "podman run --rm -it".to_string();
+    repo.insert_command(&edited)?;
+    assert!(repo.search_commands(&engine, "podman", None)?.iter().any(|c| c.name == "run_container"));
+    assert!(!repo.search_commands(&engine, "docker", None)?.iter().any(|c| c.name == "run_container"));
+    repo.delete_by_path(&edited.path)?;
+    assert!(repo.search_commands(&engine, "podman", None)?.is_empty());
+    Ok(())
+}
+
+// --- Query Language Tests ---
+
+#[test]
+fn test_query_parsing() -> Result<()> {
+    use lscmd::search::{Field, Query, QueryNode};
+
+    let describe = |node: &QueryNode| -> String {
+        fn walk(node: &QueryNode) -> String {
+            match node {
+                QueryNode::Term(term) => match term.field {
+                    Some(field) => format!("{:?}:{}{}", field, term.value, if term.wildcard { "*?" } else { "" }),
+                    None => term.value.clone(),
+                },
+                QueryNode::And(children) => format!("AND({})", children.iter().map(walk).collect::<Vec<_>>().join(", ")),
+                QueryNode::Or(children) => format!("OR({})", children.iter().map(walk).collect::<Vec<_>>().join(", ")),
+                QueryNode::Not(child) => format!("NOT({})", walk(child)),
+            }
+        }
+        walk(node)
+    };
+    let parse = |query: &str| Query::parse(query).map(|q| describe(q.root()));
+
+    assert_eq!(parse("git docker")?, "OR(git, docker)");
+    assert_eq!(
+        parse(r#"name:git* type:function file:docker code:"kubectl get" -deprecated"#)?,
+        "AND(Name:git**?, Type:function, File:docker, Code:kubectl get, NOT(deprecated))"
+    );
+    assert_eq!(parse("git AND (push OR pull) NOT force")?, "AND(git, AND(OR(push, pull), NOT(force)))");
+    assert_eq!(parse("a b OR c")?, "OR(OR(a, b), c)");
+    assert_eq!(parse(r#""say \"hi\"" --force http://x desc:"a b""#)?, r#"AND(OR(say "hi", --force, http://x), Description:a b)"#);
+
+    // Errors point at the offending column.
+    let error = |query: &str| match Query::parse(query) {
+        Err(LscmdError::SearchPattern(message)) => message,
+        other => panic!("expected a search pattern error for {:?}, got {:?}", query, other),
+    };
+    assert_eq!(error("git)"), "unmatched ')' at column 4");
+    assert_eq!(error("git (push OR pull"), "unclosed '(' at column 5");
+    assert_eq!(error("git OR"), "expected a term at column 7");
+    assert_eq!(error("AND git"), "expected a term before 'AND' at column 1");
+    assert_eq!(error("git ()"), "expected a term before ')' at column 6");
+    assert_eq!(error("code:\"unclosed"), "unterminated quote at column 6");
+    assert_eq!(error("name: git"), "expected a value after 'name:' at column 6");
+    assert_eq!(error("git NOT"), "expected a term to exclude at column 8");
+    assert_eq!(error("type:script"), "unknown type 'script', expected one of: alias, function, variable, keybinding at column 6");
+    assert!(matches!(Query::parse(&"q".repeat(2000)), Err(LscmdError::Validation(_))));
+    assert_eq!(Field::from_keyword("DESC"), Some(Field::Description));
+    Ok(())
+}
+
+#[test]
+fn test_query_matching() -> Result<()> {
+    let mut commands = get_mock_commands();
+    commands[1].description = Some("Deprecated: use podman".to_string());
+    let mut kube = commands[1].clone();
+    kube.name = "kpods".to_string();
+    kube.path = "/files/docker.sh".to_string();
+    kube.code = "kubectl get pods".to_string();
+    kube.description = None;
+    commands.push(kube);
+
+    let engine = SearchEngine::new(false);
+    let names = |query: &str| -> Result<Vec<String>> {
+        Ok(engine.search(query, &commands)?.iter().map(|r| r.name.clone()).collect())
+    };
+
+    assert_eq!(names(r#"type:function file:docker code:"kubectl get" -deprecated"#)?, vec!["kpods"]);
+    assert_eq!(names("type:function")?, vec!["DockerRun", "rust_analyzer_status", "kpods"]);
+    assert_eq!(names("type:function -deprecated -rust")?, vec!["kpods"]);
+    assert_eq!(names("name:docker*")?, vec!["DockerRun"]);
+    assert_eq!(names("name:*status")?, vec!["rust_analyzer_status"]);
+    assert_eq!(names("file:d*.sh")?, vec!["DockerRun", "kpods"]);
+    assert_eq!(names("desc:podman OR name:git*")?, vec!["GitCommit", "DockerRun"]);
+    assert_eq!(names("docker AND NOT (kubectl OR podman)")?, Vec::<String>::new());
+    assert_eq!(names("\"run --rm\"")?, vec!["DockerRun"]);
+
+    // Every condition narrows the full-text prefilter without losing matches.
+    let repo = setup_in_memory_db()?;
+    let mut stored = commands.clone();
+    for (line, command) in stored.iter_mut().enumerate() {
+        command.start_line = line + 1;
+    }
+    repo.batch_insert(&stored)?;
+    for query in ["type:function -deprecated -rust", "name:docker*", "desc:podman OR name:git*", "\"kubectl get\" file:docker"] {
+        let from_repo: Vec<_> = repo.search_commands(&engine, query, None)?.into_iter().map(|r| r.command.name).collect();
+        assert_eq!(from_repo, names(query)?, "{}", query);
+    }
+    assert_eq!(engine.fts_query("name:git* -deprecated file:x").as_deref(), Some("name : \"git\""));
+    assert_eq!(engine.fts_query("(kubectl OR podman) type:function").as_deref(), Some("(\"kubectl\" OR \"podman\")"));
+    assert_eq!(engine.fts_query("type:alias -old"), None);
+    Ok(())
+}
+
+#[test]
+fn test_fuzzy_match_scoring() {
+    use lscmd::search::fuzzy_match;
+
+    let found = fuzzy_match("gco", "git_checkout").unwrap();
+    assert_eq!(found.positions, vec![0, 4, 9]);
+    assert_eq!(found.ranges("git_checkout"), vec![0..1, 4..5, 9..10]);
+    assert!(fuzzy_match("GCO", "git_checkout").is_some());
+    assert!(fuzzy_match("ocg", "git_checkout").is_none());
+    assert!(fuzzy_match("", "git_checkout").is_none());
+
+    // Word boundaries and camel humps beat letters buried inside words.
+    let score = |pattern: &str, text: &str| fuzzy_match(pattern, text).unwrap().score;
+    assert!(score("gc", "git_commit") > score("gc", "logcat"));
+    assert!(score("gc", "GitCommit") > score("gc", "bigcat_x"));
+    // Consecutive characters beat scattered ones.
+    assert!(score("dock", "docker_run") > score("dock", "d_o_c_k"));
+    // The tightest window is scored, and multi-byte text keeps byte offsets.
+    assert_eq!(fuzzy_match("ab", "a-x-ab").unwrap().positions, vec![4, 5]);
+    assert_eq!(fuzzy_match("éb", "café_bar").unwrap().ranges("café_bar"), vec![3..5, 6..7]);
+}
+
+#[test]
+fn test_fuzzy_search_mode() -> Result<()> {
+    use lscmd::search::{Field, SearchMode};
+
+    let mut commands = get_mock_commands();
+    commands[1].description = Some("Start a throwaway container".to_string());
+    let engine = SearchEngine::with_mode(SearchMode::Fuzzy);
+    assert_eq!(engine.mode(), SearchMode::Fuzzy);
+    assert_eq!(SearchEngine::new(true).mode(), SearchMode::Regex);
+    assert_eq!(engine.fts_query("gc"), None);
+
+    let results = engine.search("gc", &commands)?;
+    let names: Vec<_> = results.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, vec!["GitCommit"]);
+    assert_eq!(results[0].matches[0].field, Field::Name);
+    assert_eq!(results[0].matches[0].ranges, vec![0..1, 3..4]);
+
+    // Every word must match; outside the name they still count.
+    let results = engine.search("drun throwaway", &commands)?;
+    assert_eq!(results.len(), 1);
+    let fields: Vec<_> = results[0].matches.iter().map(|m| m.field).collect();
+    assert_eq!(fields, vec![Field::Name, Field::Description]);
+    assert!(engine.search("drun xyz", &commands)?.is_empty());
+
+    // The repository falls back to a full scan and keeps the ranges.
+    let repo = setup_in_memory_db()?;
+    repo.batch_insert(&commands)?;
+    let results = repo.search_commands(&engine, "ras", None)?;
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].name, "rust_analyzer_status");
+    assert_eq!(results[0].matches[0].ranges, vec![0..1, 5..6, 14..15]);
+    Ok(())
+}
+
+#[test]
+#[allow(clippy::single_range_in_vec_init)]
+fn test_search_match_spans() -> Result<()> {
+    use lscmd::search::{Field, FieldMatch, Query};
+
+    let mut commands = get_mock_commands();
+    commands[1].description = Some("Run a Docker container".to_string());
+    let spans = |engine: &SearchEngine, query: &str, name: &str| -> Result<Vec<FieldMatch>> {
+        let results = engine.search(query, &commands)?;
+        let result = results.iter().find(|r| r.name == name).expect(name);
+        Ok(result.matches.clone())
+    };
+    let field = |field, ranges| FieldMatch { field, ranges };
+
+    // Word queries highlight every occurrence in every text field.
+    let words = SearchEngine::new(false);
+    assert_eq!(
+        spans(&words, "docker", "DockerRun")?,
+        vec![field(Field::Name, vec![0..6]), field(Field::Description, vec![6..12]), field(Field::Code, vec![0..6])]
+    );
+    // Field terms stay in their field; negated terms and unmatched
+    // alternatives contribute nothing.
+    assert_eq!(
+        spans(&words, "name:d*r*n type:function -git", "DockerRun")?,
+        vec![field(Field::Name, vec![0..1, 5..6, 8..9]), field(Field::Type, vec![0..8])]
+    );
+    assert_eq!(spans(&words, "rm OR podman", "DockerRun")?, vec![field(Field::Code, vec![13..15])]);
+    assert_eq!(spans(&words, "file:d.sh", "DockerRun")?, vec![field(Field::File, vec![7..11])]);
+
+    // Regex spans come from the original text.
+    let regex = SearchEngine::new(true);
+    assert_eq!(
+        spans(&regex, "r[a-z]+", "rust_analyzer_status")?,
+        vec![field(Field::Name, vec![0..4]), field(Field::Code, vec![6..10, 14..21])]
+    );
+
+    // Offsets map back across characters whose lowercase form is longer.
+    let mut command = commands[0].clone();
+    command.name = "İstanbul_tour".to_string();
+    assert_eq!(Query::parse("tour")?.matches(&command), vec![field(Field::Name, vec![10..14])]);
+    assert_eq!(Query::parse("nothing")?.matches(&command), Vec::new());
+
+    // Owned results from the repository keep their spans.
+    let repo = setup_in_memory_db()?;
+    repo.batch_insert(&commands)?;
+    let results = repo.search_commands(&words, "commit", None)?;
+    assert_eq!(results[0].matches, vec![field(Field::Name, vec![3..9]), field(Field::Code, vec![4..10])]);
+    Ok(())
+}
+
+#[test]
+fn test_regex_limits_and_incomplete_patterns() -> Result<()> {
+    let commands = get_mock_commands();
+    let strict = SearchEngine::new(true);
+    let error = |query: &str| match strict.search(query, &commands) {
+        Err(LscmdError::SearchPattern(message)) => message,
+        other => panic!("expected a search pattern error for {:?}, got {:?}", query, other),
+    };
+
+    assert_eq!(error("(comm|pu"), "Incomplete regex: unclosed '(' at column 1");
+    assert_eq!(error("git [a-"), "Incomplete regex: unclosed '[' at column 5");
+    assert_eq!(error("commit\\"), "Incomplete regex: unfinished escape '\\' at column 7");
+    assert_eq!(error("\\p{Gree"), "Incomplete regex: unfinished escape '\\' at column 1");
+    assert_eq!(error("o{2,"), "Incomplete regex: unclosed '{' at column 2");
+    assert!(error("*git").starts_with("Invalid regex:"));
+    assert!(error("\\w{1000}{1000}").starts_with("Regex too complex:"));
+    // A pattern that is merely long is still subject to the validator.
+    assert!(matches!(strict.search(&"a".repeat(2000), &commands), Err(LscmdError::Validation(_))));
+
+    // Lenient engines search for what has been typed so far.
+    let lenient = SearchEngine::new(true).lenient(true);
+    let names = |query: &str| -> Result<Vec<String>> {
+        Ok(lenient.search(query, &commands)?.iter().map(|r| r.name.clone()).collect())
+    };
+    assert_eq!(names("(comm|dock")?, vec!["DockerRun", "GitCommit"]);
+    assert_eq!(names("docker [r-")?, vec!["DockerRun"]);
+    assert_eq!(names("commit\\")?, vec!["GitCommit"]);
+    assert_eq!(names("[[:alpha:]]_an(al")?, vec!["rust_analyzer_status"]);
+    assert_eq!(names("(?")?, Vec::<String>::new());
+    // Complete patterns are unaffected, and cached ones give the same answer.
+    for _ in 0..2 {
+        assert_eq!(names("^git|status$")?, vec!["GitCommit", "rust_analyzer_status"]);
+    }
+    assert!(lenient.search("*git", &commands).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_search_filters_by_type() -> Result<()> {
+    let repo = setup_in_memory_db()?;
+    let mut commands = get_mock_commands();
+    let mut editor = commands[0].clone();
+    editor.name = "EDITOR".to_string();
+    editor.cmd_type = CommandType::Variable;
+    editor.code = "export EDITOR=vim".to_string();
+    commands.push(editor);
+    repo.batch_insert(&commands)?;
+
+    let names = |engine: &SearchEngine, query: &str, cmd_type| -> Result<Vec<String>> {
+        Ok(repo.search_commands(engine, query, cmd_type)?.into_iter().map(|r| r.command.name).collect())
+    };
+    let words = SearchEngine::new(false);
+    assert_eq!(names(&words, "", Some(CommandType::Alias))?, vec!["GitCommit"]);
+    assert_eq!(names(&words, "", Some(CommandType::Variable))?, vec!["EDITOR"]);
+    assert!(names(&words, "", Some(CommandType::Keybinding))?.is_empty());
+    // Both the full-text path and the fallback apply the filter.
+    assert_eq!(names(&words, "run", Some(CommandType::Function))?, vec!["DockerRun", "rust_analyzer_status"]);
+    assert_eq!(names(&words, "git", Some(CommandType::Function))?, Vec::<String>::new());
+    assert_eq!(names(&SearchEngine::new(true), "^e", Some(CommandType::Variable))?, vec!["EDITOR"]);
+    assert_eq!(names(&words, "type:variable", None)?, vec!["EDITOR"]);
+    Ok(())
+}
@@ -0,0 +1,171 @@
+//! Tests for schema versioning and migrations of `commands.db`.
+
+use lscmd::database::{init_db, CommandType};
+use lscmd::database::operations::{CommandRepository, SqliteCommandRepository};
+use lscmd::database::schema::{get_schema, migrate, SCHEMA_VERSION};
+use lscmd::error::{LscmdError, Result};
+use rusqlite::Connection;
+
+/// The layout written by lscmd before schema versioning existed.
+const V1_SCHEMA: &str = r#"
+CREATE TABLE commands (
+    name TEXT PRIMARY KEY NOT NULL,
+    cmd_type TEXT NOT NULL,
+    path TEXT NOT NULL,
+    code TEXT NOT NULL,
+    file_mtime INTEGER NOT NULL,
+    created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+);
+CREATE INDEX idx_type ON commands(cmd_type);
+CREATE INDEX idx_path ON commands(path);
+CREATE INDEX idx_name_lower ON commands(LOWER(name));
+INSERT INTO commands (name, cmd_type, path, code, file_mtime) VALUES
+    ('gs', 'alias', '/a/git.sh', 'git status', 100),
+    ('glog', 'function', '/a/git.sh', 'git log', 100),
+    ('dps', 'alias', '/a/docker.sh', 'docker ps', 200);
+"#;
+
+fn user_version(conn: &Connection) -> u32 {
+    conn.pragma_query_value(None, "user_version", |row| row.get(0)).unwrap()
+}
+
+/// Describes every table's columns and every index, for schema comparisons.
+fn describe_schema(conn: &Connection) -> Vec<String> {
+    let mut stmt = conn
+        .prepare("SELECT type, name, tbl_name FROM sqlite_master WHERE name NOT LIKE 'sqlite_%' ORDER BY type, name")
+        .unwrap();
+    let objects: Vec<(String, String, String)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+        .unwrap()
+        .collect::<std::result::Result<_, _>>()
+        .unwrap();
+
+    let mut description = Vec::new();
+    for (kind, name, table) in objects {
+        description.push(format!("{} {} on {}", kind, name, table));
+        if kind == "table" {
+            let mut columns = conn.prepare(&format!("PRAGMA table_info({})", name)).unwrap();
+            let columns: Vec<String> = columns
+                .query_map([], |row| {
+                    Ok(format!("  {} {} notnull={} pk={}", row.get::<_, String>(1)?, row.get::<_, String>(2)?, row.get::<_, i64>(3)?, row.get::<_, i64>(5)?))
+                })
+                .unwrap()
+                .collect::<std::result::Result<_, _>>()
+                .unwrap();
+            description.extend(columns);
+        }
+    }
+    description
+}
+
+#[test]
+fn test_new_database_gets_current_version() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let db_path = dir.path().join("commands.db");
+    let conn = init_db(&db_path)?;
+
+    assert_eq!(user_version(&conn), SCHEMA_VERSION);
+    assert!(!dir.path().join("commands.db.v1.bak").exists());
+
+    // Reopening is a no-op.
+    drop(conn);
+    let conn = init_db(&db_path)?;
+    assert_eq!(user_version(&conn), SCHEMA_VERSION);
+    Ok(())
+}
+
+#[test]
+fn test_legacy_database_is_migrated_with_backup() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let db_path = dir.path().join("commands.db");
+    Connection::open(&db_path)
+        .and_then(|conn| conn.execute_batch(V1_SCHEMA))
+        .map_err(|e| LscmdError::Database(e.to_string()))?;
+
+    let repo = SqliteCommandRepository::new(init_db(&db_path)?);
+
+    // Existing commands survive, and their files are now tracked.
+    assert_eq!(repo.get_command_by_name("glog")?.unwrap().file_mtime, 100);
+    let files = repo.list_files()?;
+    let summary: Vec<_> = files.iter().map(|f| (f.path.as_str(), f.mtime, f.command_count)).collect();
+    assert_eq!(summary, vec![("/a/docker.sh", 200, 1), ("/a/git.sh", 100, 2)]);
+
+    // Legacy rows carry no line numbers, so their files are left to be reparsed.
+    assert_eq!(repo.get_command_by_name("glog")?.unwrap().start_line, 0);
+    assert!(files.iter().all(|f| f.hash.is_empty() && f.indexed_at == Some(f.mtime)));
+
+    // The pre-migration database was preserved.
+    let backup = Connection::open(dir.path().join("commands.db.v1.bak"))
+        .map_err(|e| LscmdError::Database(e.to_string()))?;
+    let count: i64 = backup
+        .query_row("SELECT COUNT(*) FROM commands", [], |row| row.get(0))
+        .map_err(|e| LscmdError::Database(e.to_string()))?;
+    assert_eq!(count, 3);
+    Ok(())
+}
+
+#[test]
+fn test_migrated_schema_matches_fresh_schema() -> Result<()> {
+    let mut migrated = Connection::open_in_memory().map_err(|e| LscmdError::Database(e.to_string()))?;
+    migrated
+        .execute_batch(V1_SCHEMA)
+        .map_err(|e| LscmdError::Database(e.to_string()))?;
+    migrate(&mut migrated, None)?;
+
+    let fresh = Connection::open_in_memory().map_err(|e| LscmdError::Database(e.to_string()))?;
+    fresh
+        .execute_batch(get_schema())
+        .map_err(|e| LscmdError::Database(e.to_string()))?;
+
+    assert_eq!(user_version(&migrated), SCHEMA_VERSION);
+    assert_eq!(describe_schema(&migrated), describe_schema(&fresh));
+    Ok(())
+}
+
+#[test]
+fn test_newer_database_is_rejected() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let db_path = dir.path().join("commands.db");
+    Connection::open(&db_path)
+        .and_then(|conn| conn.pragma_update(None, "user_version", SCHEMA_VERSION + 1))
+        .map_err(|e| LscmdError::Database(e.to_string()))?;
+
+    match init_db(&db_path) {
+        Err(LscmdError::Database(message)) => {
+            assert!(message.contains("newer than the newest version"), "{}", message);
+        }
+        other => panic!("expected a database error, got {:?}", other.map(|_| ())),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_unknown_command_types_are_dropped() -> Result<()> {
+    let mut conn = Connection::open_in_memory().map_err(|e| LscmdError::Database(e.to_string()))?;
+    conn.execute_batch(V1_SCHEMA)
+        .and_then(|_| {
+            conn.execute_batch(
+                "INSERT INTO commands (name, cmd_type, path, code, file_mtime) VALUES \
+                 ('gp', 'Alias', '/a/git.sh', 'git push', 100), \
+                 ('build', 'script', '/a/git.sh', 'make', 100);",
+            )
+        })
+        .map_err(|e| LscmdError::Database(e.to_string()))?;
+    migrate(&mut conn, None)?;
+
+    // Types are normalised to lowercase, and unknown ones cannot be stored.
+    let rejected = conn.execute(
+        "INSERT INTO commands (name, cmd_type, path, start_line, end_line, byte_offset, code) \
+         VALUES ('x', 'script', '/a/git.sh', 1, 1, 0, 'x')",
+        [],
+    );
+    assert!(rejected.is_err());
+
+    let repo = SqliteCommandRepository::new(conn);
+    assert_eq!(repo.get_command_by_name("gp")?.unwrap().cmd_type, CommandType::Alias);
+    assert!(repo.get_command_by_name("build")?.is_none());
+    let files = repo.list_files()?;
+    let counts: Vec<_> = files.iter().map(|f| (f.path.as_str(), f.command_count)).collect();
+    assert_eq!(counts, vec![("/a/docker.sh", 1), ("/a/git.sh", 3)]);
+    Ok(())
+}
@@ -0,0 +1,262 @@
+//! Integration tests for Phase 3: Shell script parser.
+
+use lscmd::database::CommandType;
+use lscmd::error::{LscmdError, Result};
+use lscmd::parser::ShellParser;
+use std::fs;
+
+fn parse(content: &str) -> lscmd::parser::ParseResult {
+    ShellParser::new().parse_str(content, "/files/test.sh", 42)
+}
+
+#[test]
+fn test_parse_aliases() {
+    let result = parse(
+        r#"
+alias gco='git checkout'
+alias gs="git status" # inline comment
+  alias -g G='| grep'
+alias x='echo "hello"'
+alias q="echo \"quoted\""
+alias concat='it'\''s'
+alias bare=ls
+"#,
+    );
+
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    let pairs: Vec<_> = result
+        .commands
+        .iter()
+        .map(|c| (c.name.as_str(), c.code.as_str()))
+        .collect();
+    assert_eq!(
+        pairs,
+        vec![
+            ("gco", "git checkout"),
+            ("gs", "git status"),
+            ("G", "| grep"),
+            ("x", "echo \"hello\""),
+            ("q", "echo \"quoted\""),
+            ("concat", "it's"),
+            ("bare", "ls"),
+        ]
+    );
+    assert!(result.commands.iter().all(|c| c.cmd_type == CommandType::Alias));
+    assert!(result.commands.iter().all(|c| c.path == "/files/test.sh"));
+    assert!(result.commands.iter().all(|c| c.file_mtime == 42));
+}
+
+#[test]
+fn test_parse_function_forms() {
+    let result = parse(
+        r#"
+function one() { echo one; }
+function two { echo two; }
+three() { echo three; }
+four ()
+{
+    echo four
+}
+"#,
+    );
+
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    let pairs: Vec<_> = result
+        .commands
+        .iter()
+        .map(|c| (c.name.as_str(), c.code.as_str()))
+        .collect();
+    assert_eq!(
+        pairs,
+        vec![
+            ("one", "echo one;"),
+            ("two", "echo two;"),
+            ("three", "echo three;"),
+            ("four", "    echo four"),
+        ]
+    );
+    assert!(result.commands.iter().all(|c| c.cmd_type == CommandType::Function));
+}
+
+#[test]
+fn test_parse_multiline_body_with_tricky_braces() {
+    let result = parse(
+        r#"
+deploy() {
+    # a comment with an unbalanced } brace
+    local msg="closing } in a string"
+    echo '{ single }' ${#args} "${HOME}"
+    if [[ -n "$1" ]]; then
+        echo "$1"
+    fi
+}
+
+alias after='true'
+"#,
+    );
+
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    assert_eq!(result.commands.len(), 2);
+    let deploy = &result.commands[0];
+    assert_eq!(deploy.name, "deploy");
+    assert!(deploy.code.starts_with("    # a comment"));
+    assert!(deploy.code.ends_with("    fi"));
+    assert_eq!(result.commands[1].name, "after");
+}
+
+#[test]
+fn test_parse_heredoc_inside_function() {
+    let result = parse(
+        r#"
+usage() {
+    cat <<EOF
+usage: tool { options }
+}
+alias not_real='nope'
+EOF
+    cat <<-'END'
+	}
+	END
+    echo $(( 1 << 2 ))
+}
+alias real='yes'
+"#,
+    );
+
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    let names: Vec<_> = result.commands.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!["usage", "real"]);
+    assert!(result.commands[0].code.contains("alias not_real='nope'"));
+    assert!(result.commands[0].code.trim_end().ends_with("echo $(( 1 << 2 ))"));
+}
+
+#[test]
+fn test_parse_top_level_heredoc_is_skipped() {
+    let result = parse("cat <<EOF\nalias fake='x'\nEOF\nalias real='y'\n");
+    let names: Vec<_> = result.commands.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!["real"]);
+
+    // Non-ASCII delimiters end the heredoc too.
+    let result = parse("greet() {\n    cat <<ÉOF\n}\nÉOF\n}\nalias après='y'\n");
+    let names: Vec<_> = result.commands.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!["greet", "après"]);
+}
+
+#[test]
+fn test_parse_errors_do_not_abort_file() {
+    let result = parse(
+        r#"alias ok1='fine'
+alias broken="never closed
+broken_fn() ( subshell )
+alias ok2='also fine'
+alias empty=
+dangling() {
+    echo never closed
+"#,
+    );
+
+    let names: Vec<_> = result.commands.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!["ok1", "ok2"]);
+
+    let lines: Vec<_> = result
+        .errors
+        .iter()
+        .map(|e| match e {
+            LscmdError::Parse { file, line, .. } => {
+                assert_eq!(file, "/files/test.sh");
+                *line
+            }
+            other => panic!("unexpected error: {:?}", other),
+        })
+        .collect();
+    assert_eq!(lines, vec![2, 3, 5, 6]);
+}
+
+#[test]
+fn test_parse_file_reads_mtime() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("git.sh");
+    fs::write(&path, "alias gst='git status'\n")?;
+
+    let result = ShellParser::new().parse_file(&path)?;
+    assert_eq!(result.commands.len(), 1);
+    assert_eq!(result.commands[0].path, path.to_string_lossy());
+    assert!(result.commands[0].file_mtime > 0);
+
+    assert!(ShellParser::new().parse_file(&dir.path().join("missing.sh")).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_parse_records_line_ranges_and_offsets() {
+    let content = "# header\r\nalias gs='git status'\n\ndeploy() {\n    echo hi\n}\nalias multi='one\ntwo'\n";
+    let result = parse(content);
+
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    let ranges: Vec<_> = result
+        .commands
+        .iter()
+        .map(|c| (c.name.as_str(), c.start_line, c.end_line))
+        .collect();
+    assert_eq!(ranges, vec![("gs", 2, 2), ("deploy", 4, 6), ("multi", 7, 8)]);
+    for command in &result.commands {
+        let rest = &content[command.byte_offset as usize..];
+        assert!(rest.contains(&command.name) && !rest.starts_with('\n'), "{:?}", command);
+    }
+    assert_eq!(result.commands[0].byte_offset, 10);
+}
+
+#[test]
+fn test_parse_offsets_count_raw_bytes() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("latin1.sh");
+    // `\xe9` alone is invalid UTF-8 and decodes to a 3-byte U+FFFD.
+    let content = b"# caf\xe9 \xff\xfe\nalias gs='git status'\ng() {\n    echo \xe9\n}\nalias ll='ls -l'\n";
+    fs::write(&path, content)?;
+
+    let result = ShellParser::new().parse_file(&path)?;
+    let offsets: Vec<_> = result.commands.iter().map(|c| (c.name.as_str(), c.byte_offset)).collect();
+    assert_eq!(offsets, vec![("gs", 10), ("g", 32), ("ll", 51)]);
+    assert!(content[51..].starts_with(b"alias ll="));
+    Ok(())
+}
+
+#[test]
+fn test_parse_descriptions_from_comments() {
+    let result = parse(
+        r#"#!/usr/bin/env bash
+# Git shortcuts
+
+##########
+# Switch branches
+#   (falls back to checkout)
+alias gco='git checkout' # needs git >= 2.23
+alias gs='git status'   ;  # short status
+alias plain='ls'
+# not attached
+
+# Deploy the app
+#
+deploy() {
+    # body comments are not descriptions
+    ./deploy.sh
+}
+"#,
+    );
+
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    let descriptions: Vec<_> = result
+        .commands
+        .iter()
+        .map(|c| (c.name.as_str(), c.description.as_deref()))
+        .collect();
+    assert_eq!(
+        descriptions,
+        vec![
+            ("gco", Some("Switch branches\n(falls back to checkout)\nneeds git >= 2.23")),
+            ("gs", Some("short status")),
+            ("plain", None),
+            ("deploy", Some("Deploy the app")),
+        ]
+    );
+}
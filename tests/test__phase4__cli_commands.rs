@@ -0,0 +1,30 @@
+//! Integration tests for Phase 4: CLI command helpers.
+
+use lscmd::utils::editor::Editor;
+use std::ffi::OsStr;
+use std::path::Path;
+
+#[test]
+fn test_editor_spec_parsing() {
+    assert_eq!(Editor::parse("  "), None);
+    assert_eq!(
+        Editor::parse("code --wait  -n"),
+        Some(Editor {
+            program: "code".to_string(),
+            args: vec!["--wait".to_string(), "-n".to_string()],
+        })
+    );
+}
+
+#[test]
+fn test_editor_command_opens_at_line() {
+    let editor = Editor::parse("emacs -nw").unwrap();
+    let command = editor.command(Path::new("/aliases/git.sh"), 12);
+    assert_eq!(command.get_program(), "emacs");
+    let args: Vec<_> = command.get_args().collect();
+    assert_eq!(args, vec![OsStr::new("-nw"), OsStr::new("+12"), OsStr::new("/aliases/git.sh")]);
+
+    // Legacy rows without line numbers still open at the top of the file.
+    let command = editor.command(Path::new("/aliases/git.sh"), 0);
+    assert!(command.get_args().any(|arg| arg == "+1"));
+}
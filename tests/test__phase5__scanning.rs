@@ -0,0 +1,712 @@
+//! Integration tests for Phase 5: File scanning, parallel full rebuild, incremental updates, watching
+//! and discovery from shell rc files.
+
+use lscmd::database::operations::{CommandRepository, SqliteCommandRepository};
+use lscmd::error::{LscmdError, Result};
+use lscmd::search::SearchEngine;
+use lscmd::config::AliasRoot;
+use lscmd::utils::file_scanner::{content_hash, scan_roots, ChangeDetector, FileScanner};
+use lscmd::utils::parallel::ParallelProcessor;
+use lscmd::utils::sources::{SkipReason, SourceDiscovery};
+use lscmd::utils::watcher::{index_root, unindex_root, Debouncer, FileWatcher, SyncReport};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+// --- Test Setup ---
+
+fn setup_in_memory_db() -> Result<SqliteCommandRepository> {
+    use rusqlite::Connection;
+    let conn = Connection::open(":memory:").map_err(|e| LscmdError::Database(e.to_string()))?;
+    conn.execute_batch(lscmd::database::schema::get_schema())
+        .map_err(|e| LscmdError::Database(e.to_string()))?;
+    Ok(SqliteCommandRepository::new(conn))
+}
+
+fn setup_alias_dir() -> Result<tempfile::TempDir> {
+    let dir = tempfile::tempdir()?;
+    fs::create_dir_all(dir.path().join("nested"))?;
+    fs::write(dir.path().join("git.sh"), "alias gs='git status'\nglog() {\n    git log\n}\n")?;
+    fs::write(dir.path().join("nested/docker.sh"), "alias dps='docker ps'\nbroken() (\n")?;
+    fs::write(dir.path().join("notes.txt"), "alias ignored='yes'\n")?;
+    Ok(dir)
+}
+
+fn set_mtime(path: &Path, secs: u64) -> Result<()> {
+    let file = fs::File::options().write(true).open(path)?;
+    file.set_modified(UNIX_EPOCH + Duration::from_secs(secs))?;
+    Ok(())
+}
+
+/// Indexes `files` one file per transaction, each under its own directory
+/// as the root.
+fn index_files(repo: &SqliteCommandRepository, files: &[&Path]) {
+    for mut outcome in ParallelProcessor::new().process_files(files) {
+        let root = std::path::absolute(outcome.path.parent().unwrap()).unwrap();
+        outcome.file.root = Some(root.to_string_lossy().into_owned());
+        repo.replace_file(&outcome.file, &outcome.commands).unwrap();
+    }
+}
+
+/// Performs the same full rebuild as `lscmd scan`.
+fn rebuild(repo: &SqliteCommandRepository, alias_dir: &Path) -> Result<()> {
+    let scan = scan_roots(&[FileScanner::new(alias_dir)])?;
+    let outcomes = ParallelProcessor::new().process_scanned(&scan.files.iter().collect::<Vec<_>>());
+    let files: Vec<_> = outcomes.iter().map(|o| o.file.clone()).collect();
+    let commands: Vec<_> = outcomes.into_iter().flat_map(|o| o.commands).collect();
+    repo.replace_all(&files, &commands)
+}
+
+// --- FileScanner Tests ---
+
+#[test]
+fn test_scanner_finds_shell_files_recursively() -> Result<()> {
+    let dir = setup_alias_dir()?;
+    let scan = FileScanner::new(dir.path()).scan_all()?;
+
+    let names: Vec<_> = scan
+        .files
+        .iter()
+        .map(|f| f.path.strip_prefix(dir.path()).unwrap().to_string_lossy().into_owned())
+        .collect();
+    assert_eq!(names, vec!["git.sh", "nested/docker.sh"]);
+    assert!(scan.files.iter().all(|f| f.path.is_absolute() && f.mtime > 0 && f.size > 0));
+    assert!(scan.errors.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_scanner_missing_directory() {
+    let scanner = FileScanner::new("/definitely/not/a/real/alias/dir");
+    assert!(matches!(scanner.scan_all(), Err(LscmdError::Config(_))));
+}
+
+// --- ParallelProcessor Tests ---
+
+#[test]
+fn test_parallel_processing_isolates_errors() -> Result<()> {
+    let dir = setup_alias_dir()?;
+    let files = vec![
+        dir.path().join("git.sh"),
+        dir.path().join("nested/docker.sh"),
+        dir.path().join("missing.sh"),
+    ];
+
+    let outcomes = ParallelProcessor::new().process_files(&files);
+    assert_eq!(outcomes.len(), 3);
+
+    // Order matches the input regardless of scheduling.
+    assert_eq!(outcomes[0].path, files[0]);
+    assert_eq!(outcomes[0].commands.len(), 2);
+    assert!(outcomes[0].errors.is_empty());
+
+    assert_eq!(outcomes[1].commands.len(), 1);
+    assert!(matches!(outcomes[1].errors[..], [LscmdError::Parse { line: 2, .. }]));
+
+    assert!(outcomes[2].commands.is_empty());
+    assert!(matches!(outcomes[2].errors[..], [LscmdError::Io(_)]));
+    Ok(())
+}
+
+// --- Full Rebuild Tests ---
+
+#[test]
+fn test_replace_all_swaps_table_contents() -> Result<()> {
+    let repo = setup_in_memory_db()?;
+    let dir = setup_alias_dir()?;
+
+    rebuild(&repo, dir.path())?;
+    assert!(repo.get_command_by_name("glog")?.is_some());
+
+    // A second rebuild without `git.sh` drops its commands.
+    fs::remove_file(dir.path().join("git.sh"))?;
+    rebuild(&repo, dir.path())?;
+
+    assert!(repo.get_command_by_name("glog")?.is_none());
+    let all = repo.search_commands(&SearchEngine::new(false), "", None)?;
+    let names: Vec<_> = all.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!["dps"]);
+    Ok(())
+}
+
+#[test]
+fn test_init_db_uses_wal_for_concurrent_readers() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let conn = lscmd::database::init_db(&dir.path().join("commands.db"))?;
+    let mode: String = conn
+        .query_row("PRAGMA journal_mode", [], |row| row.get(0))
+        .map_err(|e| LscmdError::Database(e.to_string()))?;
+    assert_eq!(mode, "wal");
+    Ok(())
+}
+
+// --- Incremental Update Tests ---
+
+#[test]
+fn test_content_hash_is_stable() {
+    assert_eq!(content_hash(b""), "cbf29ce484222325");
+    assert_eq!(content_hash(b"alias a='b'"), content_hash(b"alias a='b'"));
+    assert_ne!(content_hash(b"alias a='b'"), content_hash(b"alias a='c'"));
+}
+
+#[test]
+fn test_change_detector_classifies_files() -> Result<()> {
+    let repo = setup_in_memory_db()?;
+    let dir = tempfile::tempdir()?;
+    let keep = dir.path().join("keep.sh");
+    let touch = dir.path().join("touch.sh");
+    let edit = dir.path().join("edit.sh");
+    let gone = dir.path().join("gone.sh");
+    for path in [&keep, &touch, &edit, &gone] {
+        let name = path.file_stem().unwrap().to_string_lossy();
+        fs::write(path, format!("alias {}='1'\n", name))?;
+        set_mtime(path, 1_000)?;
+    }
+    index_files(&repo, &[&keep, &touch, &edit, &gone]);
+
+    // touch: new mtime, same content. edit: new content. gone: deleted.
+    set_mtime(&touch, 2_000)?;
+    fs::write(&edit, "alias edit='2'\n")?;
+    fs::remove_file(&gone)?;
+    let added = dir.path().join("added.sh");
+    fs::write(&added, "alias added='1'\n")?;
+
+    let scan = FileScanner::new(dir.path()).scan_all()?;
+    let changes = ChangeDetector::new(&repo).detect(&scan)?;
+
+    assert_eq!(changes.added.len(), 1);
+    assert!(changes.added[0].path.ends_with("added.sh"));
+    assert_eq!(changes.modified.len(), 1);
+    assert!(changes.modified[0].path.ends_with("edit.sh"));
+    assert_eq!(changes.touched.len(), 1);
+    assert!(changes.touched[0].path.ends_with("touch.sh"));
+    assert_eq!(changes.deleted.len(), 1);
+    assert!(changes.deleted[0].ends_with("gone.sh"));
+    assert_eq!(changes.unchanged, 1);
+    Ok(())
+}
+
+#[test]
+fn test_change_detector_catches_same_mtime_edit() -> Result<()> {
+    let repo = setup_in_memory_db()?;
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("racy.sh");
+
+    // Indexed no later than the last write, as within the same mtime tick:
+    // the mtime cannot be trusted, so an edit that keeps the mtime must
+    // still be noticed. The mtime lies ahead so the clock cannot pass it.
+    let mtime = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() + 3600;
+    fs::write(&path, "alias a='1'\n")?;
+    set_mtime(&path, mtime)?;
+    index_files(&repo, &[&path]);
+    fs::write(&path, "alias a='2'\n")?;
+    set_mtime(&path, mtime)?;
+
+    let scan = FileScanner::new(dir.path()).scan_all()?;
+    let changes = ChangeDetector::new(&repo).detect(&scan)?;
+    assert_eq!(changes.modified.len(), 1);
+
+    // After reindexing, the unchanged file is recognised as such until the
+    // clock passes its mtime; then it is touched so `indexed_at` does too.
+    index_files(&repo, &[&path]);
+    let changes = ChangeDetector::new(&repo).detect(&scan)?;
+    assert!(changes.is_empty());
+    assert_eq!(changes.unchanged, 1);
+    let changes = ChangeDetector::new(&repo).with_now(mtime as i64 + 1).detect(&scan)?;
+    assert_eq!(changes.touched.len(), 1);
+    assert_eq!(changes.unchanged, 0);
+    assert_eq!(repo.get_command_by_name("a")?.unwrap().code, "2");
+    Ok(())
+}
+
+#[test]
+fn test_future_mtime_is_touched_once() -> Result<()> {
+    let repo = setup_in_memory_db()?;
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("future.sh");
+    fs::write(&path, "alias a='1'\n")?;
+    index_files(&repo, &[&path]);
+
+    // A checkout or clock skew moves the mtime ahead of the clock: the new
+    // mtime is recorded once, and later checks leave the row alone.
+    set_mtime(&path, 4_000_000_000)?;
+    let scan = FileScanner::new(dir.path()).scan_all()?;
+    let changes = ChangeDetector::new(&repo).detect(&scan)?;
+    assert_eq!(changes.touched.len(), 1);
+    let file = &changes.touched[0];
+    repo.touch_file(&file.path.to_string_lossy(), file.mtime, &file.root, file.load_order)?;
+
+    let changes = ChangeDetector::new(&repo).detect(&scan)?;
+    assert!(changes.is_empty());
+    assert_eq!(changes.unchanged, 1);
+    Ok(())
+}
+
+// --- Files Table Tests ---
+
+#[test]
+fn test_files_table_tracks_empty_and_broken_files() -> Result<()> {
+    let repo = setup_in_memory_db()?;
+    let dir = setup_alias_dir()?;
+    fs::write(dir.path().join("empty.sh"), "# nothing here yet\n")?;
+    fs::write(dir.path().join("broken.sh"), "alias bad=\"unterminated\n")?;
+    rebuild(&repo, dir.path())?;
+
+    let files = repo.list_files()?;
+    let summary: Vec<_> = files
+        .iter()
+        .map(|f| {
+            let name = Path::new(&f.path).file_name().unwrap().to_string_lossy().into_owned();
+            (name, f.parse_status.as_str(), f.command_count)
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("broken.sh".to_string(), "failed", 0),
+            ("empty.sh".to_string(), "ok", 0),
+            ("git.sh".to_string(), "ok", 2),
+            ("docker.sh".to_string(), "partial", 1),
+        ]
+    );
+
+    let broken = &files[0];
+    assert!(broken.error_message.as_deref().unwrap().contains("unterminated quote"));
+    assert!(broken.size > 0 && !broken.hash.is_empty() && broken.indexed_at.is_some());
+
+    // Zero-command files are tracked, so they are not re-added on every update.
+    let scan = FileScanner::new(dir.path()).scan_all()?;
+    let changes = ChangeDetector::new(&repo).detect(&scan)?;
+    assert!(changes.added.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_deleting_file_removes_its_commands() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let conn = lscmd::database::init_db(&dir.path().join("commands.db"))?;
+    let repo = SqliteCommandRepository::new(conn);
+    let alias_dir = setup_alias_dir()?;
+    rebuild(&repo, alias_dir.path())?;
+
+    let git = alias_dir.path().join("git.sh");
+    let record = repo.get_file(&git.to_string_lossy())?.unwrap();
+    assert_eq!(record.command_count, 2);
+    assert_eq!(repo.get_file_mtime(&record.path)?, Some(record.mtime));
+
+    assert_eq!(repo.delete_by_path(&record.path)?, 2);
+    assert!(repo.get_file(&record.path)?.is_none());
+    assert!(repo.get_command_by_name("gs")?.is_none());
+    assert!(repo.get_command_by_name("dps")?.is_some());
+    Ok(())
+}
+
+#[test]
+fn test_duplicate_names_are_kept_and_shadowing_reported() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    fs::write(dir.path().join("a_git.sh"), "alias gco='git checkout'\ngco() {\n    git checkout \"$@\"\n}\n")?;
+    fs::write(dir.path().join("b_work.sh"), "alias gco='git checkout --no-guess'\nalias gs='git status'\n")?;
+    fs::write(dir.path().join("c_fn.sh"), "mk() { mkdir -p \"$1\"; }\nmk() { mkdir -pv \"$1\"; }\n")?;
+
+    let repo = setup_in_memory_db()?;
+    rebuild(&repo, dir.path())?;
+
+    // Every definition survives, in sourcing order.
+    let definitions = repo.get_definitions("GCO")?;
+    let places: Vec<_> = definitions
+        .iter()
+        .map(|c| (Path::new(&c.path).file_name().unwrap().to_str().unwrap(), c.start_line, c.cmd_type.as_str()))
+        .collect();
+    assert_eq!(places, vec![("a_git.sh", 1, "alias"), ("a_git.sh", 2, "function"), ("b_work.sh", 1, "alias")]);
+    assert_eq!((definitions[1].end_line, definitions[1].byte_offset), (4, 25));
+
+    // The last alias wins over functions; among functions the last one wins.
+    assert_eq!(repo.get_command_by_name("gco")?.unwrap().code, "git checkout --no-guess");
+    let mk = repo.get_command_by_name("mk")?.unwrap();
+    assert_eq!((mk.start_line, mk.code.as_str()), (2, "mkdir -pv \"$1\";"));
+
+    let conflicts: Vec<_> = repo
+        .list_conflicts()?
+        .iter()
+        .map(|group| (group[0].name.clone(), group.len()))
+        .collect();
+    assert_eq!(conflicts, vec![("gco".to_string(), 3), ("mk".to_string(), 2)]);
+
+    // Reindexing one file leaves the other definitions alone.
+    fs::write(dir.path().join("b_work.sh"), "alias gs='git status'\n")?;
+    index_files(&repo, &[&dir.path().join("b_work.sh")]);
+    assert_eq!(repo.get_definitions("gco")?.len(), 2);
+    assert_eq!(repo.get_command_by_name("gco")?.unwrap().code, "git checkout");
+    Ok(())
+}
+
+// --- Watch Mode Tests ---
+
+/// Syncs `watcher` until a batch was applied, giving up after a few seconds.
+fn wait_for_sync(watcher: &mut FileWatcher, repo: &SqliteCommandRepository) -> Result<SyncReport> {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while Instant::now() < deadline {
+        if let Some(report) = watcher.sync(repo, Duration::from_millis(100))? {
+            return Ok(report);
+        }
+    }
+    panic!("no changes were picked up");
+}
+
+#[test]
+fn test_debouncer_waits_for_quiet_period() {
+    let start = Instant::now();
+    let mut debouncer = Debouncer::new(Duration::from_millis(100));
+    assert!(debouncer.take_ready(start).is_none());
+
+    debouncer.add(PathBuf::from("/a/b.sh"), start);
+    debouncer.add(PathBuf::from("/a/a.sh"), start + Duration::from_millis(80));
+    debouncer.add(PathBuf::from("/a/b.sh"), start + Duration::from_millis(90));
+
+    // Every change restarts the quiet period.
+    assert!(debouncer.take_ready(start + Duration::from_millis(150)).is_none());
+    let batch = debouncer.take_ready(start + Duration::from_millis(190)).unwrap();
+    assert_eq!(batch, vec![PathBuf::from("/a/a.sh"), PathBuf::from("/a/b.sh")]);
+    assert!(debouncer.is_empty() && debouncer.deadline().is_none());
+}
+
+#[test]
+fn test_detect_paths_only_classifies_given_paths() -> Result<()> {
+    let repo = setup_in_memory_db()?;
+    let dir = setup_alias_dir()?;
+    let root = std::path::absolute(dir.path())?;
+    rebuild(&repo, &root)?;
+
+    fs::write(root.join("git.sh"), "alias gs='git status -sb'\n")?;
+    fs::write(root.join("other.sh"), "alias o='1'\n")?;
+    fs::remove_dir_all(root.join("nested"))?;
+    fs::write(root.join("notes.txt"), "changed\n")?;
+
+    let scanners = [FileScanner::new(&root)];
+    let paths = vec![root.join("nested"), root.join("git.sh"), root.join("notes.txt")];
+    let changes = ChangeDetector::new(&repo).detect_paths(&scanners, &paths)?;
+
+    // `other.sh` was not reported, so it is left for a later batch.
+    assert!(changes.added.is_empty());
+    assert_eq!(changes.modified.len(), 1);
+    assert!(changes.modified[0].path.ends_with("git.sh"));
+    assert_eq!(changes.deleted, vec![root.join("nested/docker.sh").to_string_lossy().into_owned()]);
+
+    // A directory moved in stands for the files below it.
+    fs::create_dir(root.join("moved"))?;
+    fs::write(root.join("moved/k8s.sh"), "alias k='kubectl'\n")?;
+    let changes = ChangeDetector::new(&repo).detect_paths(&scanners, &[root.join("moved")])?;
+    assert_eq!(changes.added.len(), 1);
+    assert!(changes.added[0].path.ends_with("moved/k8s.sh"));
+    Ok(())
+}
+
+#[test]
+fn test_watcher_reindexes_saved_files() -> Result<()> {
+    let repo = setup_in_memory_db()?;
+    let dir = setup_alias_dir()?;
+    let mut watcher = FileWatcher::new(&[AliasRoot::new(dir.path())], Duration::from_millis(50))?;
+    let root = watcher.scanners()[0].root().to_path_buf();
+    rebuild(&repo, &root)?;
+
+    // Saving through a temporary file and a rename, as editors do.
+    fs::write(root.join(".git.sh.tmp"), "alias gs='git status -sb'\n")?;
+    fs::rename(root.join(".git.sh.tmp"), root.join("git.sh"))?;
+    fs::remove_file(root.join("nested/docker.sh"))?;
+
+    let report = wait_for_sync(&mut watcher, &repo)?;
+    assert_eq!(report.changes.modified.len(), 1);
+    assert_eq!(report.changes.deleted.len(), 1);
+    assert_eq!(repo.get_command_by_name("gs")?.unwrap().code, "git status -sb");
+    assert!(repo.get_command_by_name("glog")?.is_none());
+    assert!(repo.get_command_by_name("dps")?.is_none());
+
+    fs::write(root.join("nested/new.sh"), "alias n='new'\n")?;
+    let report = wait_for_sync(&mut watcher, &repo)?;
+    assert_eq!(report.changes.added.len(), 1);
+    assert!(repo.get_command_by_name("n")?.is_some());
+    Ok(())
+}
+
+#[test]
+fn test_watcher_missing_directory() {
+    let watcher = FileWatcher::new(&[AliasRoot::new("/definitely/not/a/real/alias/dir")], Duration::ZERO);
+    assert!(matches!(watcher, Err(LscmdError::Config(_))));
+}
+
+// --- Alias Root Tests ---
+
+#[test]
+fn test_root_globs_and_depth_filter_files() -> Result<()> {
+    let dir = setup_alias_dir()?;
+    fs::create_dir_all(dir.path().join("nested/deeper"))?;
+    fs::write(dir.path().join("nested/deeper/k8s.sh"), "alias k='kubectl'\n")?;
+    fs::create_dir_all(dir.path().join("private"))?;
+    fs::write(dir.path().join("private/secret.sh"), "alias s='1'\n")?;
+    fs::write(dir.path().join("zshrc.zsh"), "alias z='1'\n")?;
+
+    let names = |root: &AliasRoot| -> Result<Vec<String>> {
+        let scanner = FileScanner::for_root(root)?;
+        Ok(scanner
+            .scan_all()?
+            .files
+            .iter()
+            .map(|f| f.path.strip_prefix(scanner.root()).unwrap().to_string_lossy().into_owned())
+            .collect())
+    };
+
+    let mut root = AliasRoot::new(dir.path());
+    root.include = vec!["*.sh".to_string(), "*.zsh".to_string()];
+    root.exclude = vec!["private".to_string()];
+    assert_eq!(names(&root)?, vec!["git.sh", "nested/deeper/k8s.sh", "nested/docker.sh", "zshrc.zsh"]);
+
+    root.max_depth = Some(1);
+    assert_eq!(names(&root)?, vec!["git.sh", "nested/docker.sh", "zshrc.zsh"]);
+    root.max_depth = Some(0);
+    assert_eq!(names(&root)?, vec!["git.sh", "zshrc.zsh"]);
+
+    root.include = vec!["[".to_string()];
+    assert!(matches!(FileScanner::for_root(&root), Err(LscmdError::Config(_))));
+    Ok(())
+}
+
+#[test]
+fn test_commands_remember_their_root_and_roots_rescan_alone() -> Result<()> {
+    let repo = setup_in_memory_db()?;
+    let team = setup_alias_dir()?;
+    let personal = tempfile::tempdir()?;
+    fs::write(personal.path().join("mine.sh"), "alias gs='git status -sb'\n")?;
+
+    let scanners = [FileScanner::new(team.path()), FileScanner::new(personal.path())];
+    let scan = scan_roots(&scanners)?;
+    let outcomes = ParallelProcessor::new().process_scanned(&scan.files.iter().collect::<Vec<_>>());
+    let files: Vec<_> = outcomes.iter().map(|o| o.file.clone()).collect();
+    let commands: Vec<_> = outcomes.into_iter().flat_map(|o| o.commands).collect();
+    repo.replace_all(&files, &commands)?;
+
+    let definitions = repo.get_definitions("gs")?;
+    let mut roots: Vec<_> = definitions.iter().map(|c| c.root.clone().unwrap()).collect();
+    let mut expected = vec![scanners[0].root_key(), scanners[1].root_key()];
+    roots.sort();
+    expected.sort();
+    assert_eq!(roots, expected);
+
+    // Rescanning one root leaves the other alone.
+    fs::write(personal.path().join("mine.sh"), "alias gl='git log'\n")?;
+    let scan = scanners[1].scan_all()?;
+    let outcomes = ParallelProcessor::new().process_scanned(&scan.files.iter().collect::<Vec<_>>());
+    let files: Vec<_> = outcomes.iter().map(|o| o.file.clone()).collect();
+    let commands: Vec<_> = outcomes.into_iter().flat_map(|o| o.commands).collect();
+    repo.replace_root(&scanners[1].root_key(), &files, &commands)?;
+    assert_eq!(repo.get_definitions("gs")?.len(), 1);
+    assert!(repo.get_command_by_name("gl")?.is_some());
+    assert!(repo.get_command_by_name("glog")?.is_some());
+
+    // Removing a root only drops its own commands.
+    assert_eq!(repo.delete_root(&scanners[0].root_key())?, 3);
+    let remaining: Vec<_> = repo.list_files()?.into_iter().map(|f| f.path).collect();
+    assert_eq!(remaining, vec![personal.path().join("mine.sh").to_string_lossy().into_owned()]);
+    Ok(())
+}
+
+#[test]
+fn test_files_moving_between_roots_are_not_reparsed() -> Result<()> {
+    let repo = setup_in_memory_db()?;
+    let dir = setup_alias_dir()?;
+    rebuild(&repo, &dir.path().join("nested"))?;
+
+    // The parent directory now claims `nested/docker.sh` first.
+    let scanners = [FileScanner::new(dir.path()), FileScanner::new(dir.path().join("nested"))];
+    let changes = ChangeDetector::new(&repo).detect(&scan_roots(&scanners)?)?;
+    assert_eq!(changes.added.len(), 1);
+    assert!(changes.modified.is_empty());
+    assert_eq!(changes.touched.len(), 1);
+
+    lscmd::utils::watcher::apply_changes(&repo, &changes)?;
+    assert_eq!(repo.get_command_by_name("dps")?.unwrap().root, Some(scanners[0].root_key()));
+    Ok(())
+}
+
+#[test]
+fn test_nested_roots_keep_files_with_the_first_root() -> Result<()> {
+    let repo = setup_in_memory_db()?;
+    let dir = setup_alias_dir()?;
+    let outer = FileScanner::new(dir.path());
+    let inner = FileScanner::new(dir.path().join("nested"));
+    index_root(&repo, std::slice::from_ref(&outer), &outer.root_key())?;
+
+    // Adding a root inside an existing one claims nothing it already covers.
+    let (scan, outcomes) = index_root(&repo, &[outer.clone(), inner.clone()], &inner.root_key())?;
+    assert!(scan.files.is_empty() && outcomes.is_empty());
+    assert_eq!(repo.get_command_by_name("dps")?.unwrap().root, Some(outer.root_key()));
+
+    // Removing it leaves the outer root's commands alone.
+    let report = unindex_root(&repo, &inner.root_key(), std::slice::from_ref(&outer))?;
+    assert!(report.changes.is_empty());
+    assert_eq!(repo.list_files()?.len(), 2);
+
+    // With the inner root first, it owns its files until it is removed; then
+    // they move to the outer root instead of disappearing.
+    index_root(&repo, &[inner.clone(), outer.clone()], &inner.root_key())?;
+    index_root(&repo, &[inner.clone(), outer.clone()], &outer.root_key())?;
+    assert_eq!(repo.get_command_by_name("dps")?.unwrap().root, Some(inner.root_key()));
+    let report = unindex_root(&repo, &inner.root_key(), std::slice::from_ref(&outer))?;
+    assert_eq!((report.changes.modified.len(), report.changes.deleted.len()), (1, 0));
+    assert_eq!(repo.get_command_by_name("dps")?.unwrap().root, Some(outer.root_key()));
+
+    // Files no remaining root covers are dropped.
+    let report = unindex_root(&repo, &outer.root_key(), &[])?;
+    assert_eq!(report.changes.deleted.len(), 2);
+    assert!(repo.list_files()?.is_empty());
+    Ok(())
+}
+
+// --- Source Discovery Tests ---
+
+/// Lays out a home directory whose `.zshrc` loads aliases in several ways.
+fn setup_home() -> Result<tempfile::TempDir> {
+    let home = tempfile::tempdir()?;
+    let h = home.path();
+    fs::create_dir_all(h.join(".aliases/sub"))?;
+    fs::create_dir_all(h.join(".config/zsh"))?;
+    fs::write(
+        h.join(".zshrc"),
+        "# Load the aliases\n\
+         export ZDOTDIR=\"${ZDOTDIR:-$HOME/.config/zsh}\"\n\
+         alias ll='ls -l'\n\
+         for f in ~/.aliases/*.sh(N); do\n\
+         \x20 source \"$f\"\n\
+         done\n\
+         [ -f ~/.local.sh ] && . ~/.local.sh\n\
+         source $ZDOTDIR/extra.zsh\n\
+         source \"$(brew --prefix)/etc/profile.sh\"\n\
+         lazy() {\n\
+         \x20 source ~/.aliases/unused/lazy.sh\n\
+         }\n",
+    )?;
+    fs::write(h.join(".aliases/b_git.sh"), "alias gs='git status'\n")?;
+    fs::write(h.join(".aliases/a_dev.sh"), "alias dev='cd ~/dev'\n. \"${0:A:h}/sub/nested.sh\"\n")?;
+    // Sourcing the rc file again must not loop.
+    fs::write(h.join(".aliases/sub/nested.sh"), "alias n='1'\nsource ~/.zshrc\n")?;
+    fs::create_dir(h.join(".aliases/unused"))?;
+    fs::write(h.join(".aliases/unused/lazy.sh"), "alias lazy_alias='1'\n")?;
+    fs::write(h.join(".config/zsh/extra.zsh"), "alias gs='git status -sb'\n")?;
+    fs::write(h.join(".bashrc"), "alias b='1'\nsource ~/.aliases/b_git.sh\n")?;
+    Ok(home)
+}
+
+fn discovery(home: &Path) -> SourceDiscovery {
+    SourceDiscovery::new(vec![home.join(".zshrc"), home.join(".bashrc")], home)
+}
+
+#[test]
+fn test_sources_are_followed_in_load_order() -> Result<()> {
+    let home = setup_home()?;
+    let h = home.path();
+    let order = discovery(h).discover();
+
+    let loaded: Vec<_> = order
+        .files
+        .iter()
+        .map(|f| (f.path.strip_prefix(h).unwrap().to_string_lossy().into_owned(), f.depth))
+        .collect();
+    assert_eq!(
+        loaded,
+        vec![
+            (".zshrc".to_string(), 0),
+            (".aliases/a_dev.sh".to_string(), 1),
+            (".aliases/sub/nested.sh".to_string(), 2),
+            (".aliases/b_git.sh".to_string(), 1),
+            (".config/zsh/extra.zsh".to_string(), 1),
+            // `b_git.sh` was already loaded by zsh.
+            (".bashrc".to_string(), 0),
+        ]
+    );
+    let nested = order.files[2].sourced_from.as_ref().unwrap();
+    assert_eq!((nested.file.as_path(), nested.line), (h.join(".aliases/a_dev.sh").as_path(), 2));
+    assert_eq!(order.files[4].entry, h.join(".zshrc"));
+
+    let skipped: Vec<_> = order.skipped.iter().map(|s| (s.site.line, s.reason)).collect();
+    assert_eq!(skipped, vec![(7, SkipReason::Missing), (9, SkipReason::Unexpanded)]);
+    assert!(order.dirs.contains(&h.join(".aliases")));
+
+    // Only the statements that need a running shell are reported.
+    let scan = order.scan();
+    assert_eq!(scan.files.len(), 6);
+    assert_eq!(scan.errors.len(), 1);
+    assert!(matches!(&scan.errors[0], LscmdError::Parse { line: 9, .. }));
+    Ok(())
+}
+
+#[test]
+fn test_unsourced_alias_files_are_flagged() -> Result<()> {
+    let home = setup_home()?;
+    let h = home.path();
+    let order = discovery(h).discover();
+
+    let roots = scan_roots(&[FileScanner::new(h.join(".aliases"))])?;
+    let unsourced: Vec<_> = order.unsourced(&roots).iter().map(|f| f.path.clone()).collect();
+    assert_eq!(unsourced, vec![h.join(".aliases/unused/lazy.sh")]);
+
+    // Variables can also come from the environment.
+    fs::write(h.join(".zshrc"), "source $ALIASES/unused/lazy.sh\n")?;
+    let order = discovery(h).with_var("ALIASES", h.join(".aliases").to_string_lossy()).discover();
+    assert_eq!(order.unsourced(&roots).len(), 2);
+    assert!(order.files.iter().any(|f| f.path.ends_with("lazy.sh")));
+    Ok(())
+}
+
+#[test]
+fn test_load_order_decides_which_definition_wins() -> Result<()> {
+    let repo = setup_in_memory_db()?;
+    let home = setup_home()?;
+    let h = home.path();
+
+    let scan = discovery(h).discover().scan();
+    let outcomes = ParallelProcessor::new().process_scanned(&scan.files.iter().collect::<Vec<_>>());
+    let files: Vec<_> = outcomes.iter().map(|o| o.file.clone()).collect();
+    let commands: Vec<_> = outcomes.into_iter().flat_map(|o| o.commands).collect();
+    repo.replace_all(&files, &commands)?;
+
+    // `extra.zsh` sorts before `b_git.sh` by path but is sourced after it.
+    let gs = repo.get_command_by_name("gs")?.unwrap();
+    assert_eq!(gs.code, "git status -sb");
+    assert_eq!(gs.root, Some(h.join(".zshrc").to_string_lossy().into_owned()));
+    let record = repo.get_file(&h.join(".config/zsh/extra.zsh").to_string_lossy())?.unwrap();
+    assert_eq!(record.load_order, Some(4));
+
+    // Reordering the rc file moves files without reparsing them.
+    fs::write(h.join(".zshrc"), "source ~/.config/zsh/extra.zsh\nsource ~/.aliases/b_git.sh\n")?;
+    fs::remove_file(h.join(".bashrc"))?;
+    let changes = ChangeDetector::new(&repo).detect(&discovery(h).discover().scan())?;
+    assert_eq!(changes.modified.len(), 1);
+    assert_eq!(changes.touched.len(), 2);
+    assert_eq!(changes.deleted.len(), 3);
+    lscmd::utils::watcher::apply_changes(&repo, &changes)?;
+    assert_eq!(repo.get_command_by_name("gs")?.unwrap().code, "git status");
+    Ok(())
+}
+
+#[test]
+fn test_watcher_follows_newly_sourced_files() -> Result<()> {
+    let repo = setup_in_memory_db()?;
+    let home = setup_home()?;
+    let h = home.path();
+    let mut watcher = FileWatcher::for_sources(discovery(h), Duration::from_millis(50))?;
+    let changes = ChangeDetector::new(&repo).detect(&discovery(h).discover().scan())?;
+    lscmd::utils::watcher::apply_changes(&repo, &changes)?;
+
+    // A new file picked up by the glob loop is indexed.
+    fs::write(h.join(".aliases/c_k8s.sh"), "alias k='kubectl'\n")?;
+    let report = wait_for_sync(&mut watcher, &repo)?;
+    assert_eq!(report.changes.added.len(), 1);
+    assert!(repo.get_command_by_name("k")?.is_some());
+
+    // So is a file once a newly added statement sources it.
+    fs::write(h.join(".bashrc"), "source ~/.aliases/unused/lazy.sh\n")?;
+    let report = wait_for_sync(&mut watcher, &repo)?;
+    assert_eq!(report.changes.added.len(), 1);
+    assert!(repo.get_command_by_name("lazy_alias")?.is_some());
+    assert!(repo.get_command_by_name("b")?.is_none());
+    Ok(())
+}
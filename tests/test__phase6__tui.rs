@@ -0,0 +1,375 @@
+//! Integration tests for Phase 6: The TUI state machine.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use lscmd::database::operations::{CommandRepository, SqliteCommandRepository};
+use lscmd::database::CommandType;
+use lscmd::error::{LscmdError, Result};
+use lscmd::search::SearchEngine;
+use lscmd::tui::{App, AppState, LeftPanel, PanelFocus};
+use lscmd::utils::parallel::ParallelProcessor;
+use std::fs;
+use std::path::PathBuf;
+
+// --- Test Setup ---
+
+fn setup_in_memory_db() -> Result<SqliteCommandRepository> {
+    use rusqlite::Connection;
+    let conn = Connection::open(":memory:").map_err(|e| LscmdError::Database(e.to_string()))?;
+    conn.execute_batch(lscmd::database::schema::get_schema())
+        .map_err(|e| LscmdError::Database(e.to_string()))?;
+    Ok(SqliteCommandRepository::new(conn))
+}
+
+/// Indexes two alias files, `docker.sh` and `git.sh`.
+fn setup_indexed_repo() -> Result<(tempfile::TempDir, SqliteCommandRepository)> {
+    let dir = tempfile::tempdir()?;
+    let docker = dir.path().join("docker.sh");
+    let git = dir.path().join("git.sh");
+    fs::write(&docker, "alias dps='docker ps'\n")?;
+    fs::write(&git, "glog() {\n    git log\n}\nalias gs='git status'\nalias gp='git push'\n")?;
+
+    let repo = setup_in_memory_db()?;
+    for outcome in ParallelProcessor::new().process_files(&[&docker, &git]) {
+        repo.replace_file(&outcome.file, &outcome.commands)?;
+    }
+    Ok((dir, repo))
+}
+
+fn key(code: KeyCode) -> KeyEvent {
+    KeyEvent::new(code, KeyModifiers::NONE)
+}
+
+fn press(app: &mut App, codes: &[KeyCode]) -> Result<()> {
+    codes.iter().try_for_each(|&code| app.handle_key(key(code)))
+}
+
+fn type_text(app: &mut App, text: &str) -> Result<()> {
+    text.chars().try_for_each(|c| app.handle_key(key(KeyCode::Char(c))))
+}
+
+fn command_names(app: &App) -> Vec<String> {
+    app.commands().iter().map(|r| r.name.clone()).collect()
+}
+
+// --- Browsing Tests ---
+
+#[test]
+fn test_app_starts_on_file_list() -> Result<()> {
+    let (_dir, repo) = setup_indexed_repo()?;
+    let app = App::new(&repo, SearchEngine::new(false), None)?;
+
+    assert_eq!(app.state(), &AppState::Default);
+    assert_eq!(app.focus(), PanelFocus::Right);
+    assert_eq!(app.left_panel(), &LeftPanel::Empty);
+    assert_eq!(app.files().len(), 2);
+    assert!(app.commands().is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_selecting_a_file_lists_its_commands_by_type() -> Result<()> {
+    let (dir, repo) = setup_indexed_repo()?;
+    let mut app = App::new(&repo, SearchEngine::new(false), None)?;
+
+    press(&mut app, &[KeyCode::Down, KeyCode::Enter])?;
+    let git = dir.path().join("git.sh");
+    assert_eq!(app.state(), &AppState::FileSelected(git.clone()));
+    assert_eq!(app.left_panel(), &LeftPanel::File(git));
+    assert_eq!(app.focus(), PanelFocus::Left);
+    // Aliases come first, each group in sourcing order.
+    assert_eq!(command_names(&app), vec!["gs", "gp", "glog"]);
+
+    press(&mut app, &[KeyCode::Char('j'), KeyCode::End])?;
+    assert_eq!(app.current_command().unwrap().name, "glog");
+    press(&mut app, &[KeyCode::Home])?;
+    assert_eq!(app.current_command().unwrap().name, "gs");
+    Ok(())
+}
+
+#[test]
+fn test_type_filter_limits_file_commands() -> Result<()> {
+    let (_dir, repo) = setup_indexed_repo()?;
+    let mut app = App::new(&repo, SearchEngine::new(false), Some(CommandType::Function))?;
+
+    press(&mut app, &[KeyCode::Down, KeyCode::Char(' ')])?;
+    assert_eq!(command_names(&app), vec!["glog"]);
+    Ok(())
+}
+
+#[test]
+fn test_leaving_the_command_list() -> Result<()> {
+    let (_dir, repo) = setup_indexed_repo()?;
+    let mut app = App::new(&repo, SearchEngine::new(false), None)?;
+
+    // Right arrow returns to the files and keeps the commands on screen.
+    press(&mut app, &[KeyCode::Enter, KeyCode::Right])?;
+    assert_eq!(app.state(), &AppState::Default);
+    assert_eq!(app.focus(), PanelFocus::Right);
+    assert_eq!(command_names(&app), vec!["dps"]);
+
+    // Selecting the same file again deselects it.
+    press(&mut app, &[KeyCode::Enter])?;
+    assert!(app.selected_file().is_none());
+    assert_eq!(app.left_panel(), &LeftPanel::Empty);
+
+    // Esc returns to the files and clears the panel.
+    press(&mut app, &[KeyCode::Enter, KeyCode::Esc])?;
+    assert_eq!(app.state(), &AppState::Default);
+    assert!(app.selected_file().is_none());
+    assert!(app.commands().is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_quit_keys() -> Result<()> {
+    let (_dir, repo) = setup_indexed_repo()?;
+    let mut app = App::new(&repo, SearchEngine::new(false), None)?;
+    press(&mut app, &[KeyCode::Char('q')])?;
+    assert!(app.should_quit());
+
+    // In search mode `q` is just text, but Ctrl-C still quits.
+    let mut app = App::new(&repo, SearchEngine::new(false), None)?;
+    press(&mut app, &[KeyCode::Char('/'), KeyCode::Char('q')])?;
+    assert!(!app.should_quit());
+    assert_eq!(app.search_query(), Some("q"));
+    app.handle_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL))?;
+    assert!(app.should_quit());
+    Ok(())
+}
+
+// --- Search Tests ---
+
+#[test]
+fn test_live_search_and_editing() -> Result<()> {
+    let (_dir, repo) = setup_indexed_repo()?;
+    let mut app = App::new(&repo, SearchEngine::new(false), None)?;
+
+    press(&mut app, &[KeyCode::Char('/')])?;
+    assert_eq!(app.state(), &AppState::SearchMode(String::new()));
+    assert_eq!(app.left_panel(), &LeftPanel::Search);
+
+    type_text(&mut app, "push")?;
+    assert_eq!(command_names(&app), vec!["gp"]);
+
+    // Editing in the middle of the query refreshes the results.
+    press(&mut app, &[KeyCode::Home, KeyCode::Delete])?;
+    assert_eq!(app.search_query(), Some("ush"));
+    assert_eq!(app.input_cursor(), 0);
+    press(&mut app, &[KeyCode::End, KeyCode::Backspace, KeyCode::Backspace, KeyCode::Backspace])?;
+    assert_eq!(app.search_query(), Some(""));
+    assert!(app.commands().is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_arrows_move_focus_onto_results() -> Result<()> {
+    let (_dir, repo) = setup_indexed_repo()?;
+    let mut app = App::new(&repo, SearchEngine::new(false), None)?;
+    app.start_search("git")?;
+    assert_eq!(app.focus(), PanelFocus::Right);
+    assert!(app.commands().len() > 1);
+
+    // The first Down only focuses the list; the next one moves.
+    press(&mut app, &[KeyCode::Down])?;
+    assert_eq!(app.focus(), PanelFocus::Left);
+    assert_eq!(app.command_cursor(), 0);
+    press(&mut app, &[KeyCode::Down])?;
+    assert_eq!(app.command_cursor(), 1);
+
+    // Typing returns focus to the input.
+    type_text(&mut app, " ")?;
+    assert_eq!(app.focus(), PanelFocus::Right);
+    Ok(())
+}
+
+#[test]
+fn test_esc_restores_state_before_search() -> Result<()> {
+    let (dir, repo) = setup_indexed_repo()?;
+    let mut app = App::new(&repo, SearchEngine::new(false), None)?;
+
+    press(&mut app, &[KeyCode::Down, KeyCode::Enter, KeyCode::Down])?;
+    press(&mut app, &[KeyCode::Char('/')])?;
+    type_text(&mut app, "docker")?;
+    assert_eq!(command_names(&app), vec!["dps"]);
+
+    press(&mut app, &[KeyCode::Esc])?;
+    assert_eq!(app.state(), &AppState::FileSelected(dir.path().join("git.sh")));
+    assert_eq!(app.focus(), PanelFocus::Left);
+    assert_eq!(command_names(&app), vec!["gs", "gp", "glog"]);
+    assert_eq!(app.command_cursor(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_slash_leaves_search_and_clears() -> Result<()> {
+    let (_dir, repo) = setup_indexed_repo()?;
+    let mut app = App::new(&repo, SearchEngine::new(false), None)?;
+
+    press(&mut app, &[KeyCode::Enter, KeyCode::Char('/')])?;
+    type_text(&mut app, "git/")?;
+    assert_eq!(app.state(), &AppState::Default);
+    assert_eq!(app.left_panel(), &LeftPanel::Empty);
+    assert!(app.commands().is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_invalid_query_is_reported_not_raised() -> Result<()> {
+    let (_dir, repo) = setup_indexed_repo()?;
+    let mut app = App::new(&repo, SearchEngine::new(false), None)?;
+
+    app.start_search("git")?;
+    let before = command_names(&app);
+    assert!(!before.is_empty());
+
+    // An unbalanced query keeps the last results on screen.
+    type_text(&mut app, ")")?;
+    assert_eq!(app.message(), Some("Search pattern error: unmatched ')' at column 4"));
+    assert_eq!(command_names(&app), before);
+
+    press(&mut app, &[KeyCode::Backspace])?;
+    assert!(app.message().is_none());
+    Ok(())
+}
+
+#[test]
+fn test_search_needs_indexed_files() -> Result<()> {
+    let repo = setup_in_memory_db()?;
+    let mut app = App::new(&repo, SearchEngine::new(false), None)?;
+    press(&mut app, &[KeyCode::Char('/')])?;
+    assert_eq!(app.state(), &AppState::Default);
+    Ok(())
+}
+
+#[test]
+fn test_selected_file_path() -> Result<()> {
+    let (dir, repo) = setup_indexed_repo()?;
+    let mut app = App::new(&repo, SearchEngine::new(false), None)?;
+    press(&mut app, &[KeyCode::Enter])?;
+    assert_eq!(PathBuf::from(&app.selected_file().unwrap().path), dir.path().join("docker.sh"));
+    Ok(())
+}
+
+// --- Live Reload Tests ---
+
+/// Rewrites `path` and reindexes it, as the file watcher would.
+fn save(repo: &SqliteCommandRepository, path: &std::path::Path, content: &str) -> Result<()> {
+    fs::write(path, content)?;
+    for outcome in ParallelProcessor::new().process_files(&[path]) {
+        repo.replace_file(&outcome.file, &outcome.commands)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn test_reload_keeps_cursor_on_same_command() -> Result<()> {
+    let (dir, repo) = setup_indexed_repo()?;
+    let mut app = App::new(&repo, SearchEngine::new(false), None)?;
+    press(&mut app, &[KeyCode::Down, KeyCode::Enter, KeyCode::Down])?;
+    assert_eq!(app.current_command().unwrap().name, "gp");
+
+    save(&repo, &dir.path().join("git.sh"), "alias ga='git add'\nalias gs='git status'\nalias gp='git push'\n")?;
+    save(&repo, &dir.path().join("aaa.sh"), "alias a='1'\n")?;
+    app.reload()?;
+
+    assert_eq!(app.files().len(), 3);
+    assert_eq!(app.file_cursor(), 2);
+    assert!(app.selected_file().unwrap().path.ends_with("git.sh"));
+    assert_eq!(command_names(&app), vec!["ga", "gs", "gp"]);
+    assert_eq!(app.current_command().unwrap().name, "gp");
+    assert_eq!(app.focus(), PanelFocus::Left);
+    Ok(())
+}
+
+#[test]
+fn test_reload_drops_deleted_file_and_reruns_search() -> Result<()> {
+    let (dir, repo) = setup_indexed_repo()?;
+    let git = dir.path().join("git.sh");
+    let mut app = App::new(&repo, SearchEngine::new(false), None)?;
+    press(&mut app, &[KeyCode::Down, KeyCode::Enter, KeyCode::Char('/')])?;
+    type_text(&mut app, "git")?;
+    assert_eq!(command_names(&app).len(), 3);
+
+    repo.delete_by_path(&git.to_string_lossy())?;
+    app.reload()?;
+    assert!(command_names(&app).is_empty());
+
+    // The file the search was started from is gone too.
+    press(&mut app, &[KeyCode::Esc])?;
+    assert_eq!(app.state(), &AppState::Default);
+    assert_eq!(app.left_panel(), &LeftPanel::Empty);
+    assert!(app.selected_file().is_none());
+    assert_eq!(app.files().len(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_watch_failure_is_reported_not_raised() -> Result<()> {
+    let (_dir, repo) = setup_indexed_repo()?;
+    let mut app = App::new(&repo, SearchEngine::new(false), None)?;
+    app.watch_failed("database is locked");
+    assert_eq!(app.message(), Some("Live updates stopped: database is locked"));
+    assert!(!app.should_quit());
+
+    // The app keeps working without live updates.
+    press(&mut app, &[KeyCode::Down, KeyCode::Enter])?;
+    assert_eq!(command_names(&app), vec!["gs", "gp", "glog"]);
+    Ok(())
+}
+
+// --- Picker Tests ---
+
+#[test]
+fn test_enter_picks_command_in_picker() -> Result<()> {
+    let (_dir, repo) = setup_indexed_repo()?;
+    let mut app = App::new(&repo, SearchEngine::new(false), None)?.picking(true);
+
+    // Enter on a file still selects it; Enter on a command picks it.
+    press(&mut app, &[KeyCode::Down, KeyCode::Enter])?;
+    assert!(!app.should_quit());
+    press(&mut app, &[KeyCode::Down, KeyCode::Enter])?;
+    assert!(app.should_quit());
+    assert_eq!(app.picked().unwrap().name, "gp");
+    Ok(())
+}
+
+#[test]
+fn test_enter_picks_search_result_from_input() -> Result<()> {
+    let (_dir, repo) = setup_indexed_repo()?;
+    let mut app = App::new(&repo, SearchEngine::new(false), None)?.picking(true);
+
+    // Nothing to pick yet, so Enter does nothing.
+    press(&mut app, &[KeyCode::Char('/'), KeyCode::Enter])?;
+    assert!(!app.should_quit());
+
+    type_text(&mut app, "docker")?;
+    press(&mut app, &[KeyCode::Enter])?;
+    assert!(app.should_quit());
+    assert_eq!(app.picked().unwrap().name, "dps");
+    Ok(())
+}
+
+#[test]
+fn test_esc_cancels_picker() -> Result<()> {
+    let (_dir, repo) = setup_indexed_repo()?;
+    let mut app = App::new(&repo, SearchEngine::new(false), None)?.picking(true);
+    app.start_search("git")?;
+
+    // The first Esc leaves the search, the second cancels.
+    press(&mut app, &[KeyCode::Esc])?;
+    assert!(!app.should_quit());
+    press(&mut app, &[KeyCode::Esc])?;
+    assert!(app.should_quit());
+    assert!(app.picked().is_none());
+    Ok(())
+}
+
+#[test]
+fn test_enter_does_not_pick_outside_picker() -> Result<()> {
+    let (_dir, repo) = setup_indexed_repo()?;
+    let mut app = App::new(&repo, SearchEngine::new(false), None)?;
+    press(&mut app, &[KeyCode::Enter, KeyCode::Enter, KeyCode::Esc, KeyCode::Esc])?;
+    assert!(!app.should_quit());
+    assert!(app.picked().is_none());
+    Ok(())
+}
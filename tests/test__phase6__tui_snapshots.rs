@@ -0,0 +1,361 @@
+//! Snapshot tests for Phase 6: Scripted TUI sessions rendered headlessly.
+//!
+//! Each test drives the app with key events against an in-memory database,
+//! renders it on a `TestBackend` and compares the screen with a text file in
+//! `tests/snapshots/`. Run with `UPDATE_SNAPSHOTS=1` to write the files after
+//! an intended change to the layout, then review the diff.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use lscmd::database::operations::{CommandRepository, SqliteCommandRepository};
+use lscmd::database::{Command, CommandType, FileRecord};
+use lscmd::error::{LscmdError, Result};
+use lscmd::search::SearchEngine;
+use lscmd::tui::events::Event;
+use lscmd::tui::{self, App, AppState};
+use ratatui::backend::TestBackend;
+use ratatui::buffer::Buffer;
+use ratatui::style::Color;
+use ratatui::Terminal;
+use std::env;
+use std::fs;
+use std::path::Path;
+use unicode_width::UnicodeWidthStr;
+
+const WIDTH: u16 = 100;
+const HEIGHT: u16 = 30;
+
+// --- Test Setup ---
+
+fn setup_in_memory_db() -> Result<SqliteCommandRepository> {
+    use rusqlite::Connection;
+    let conn = Connection::open(":memory:").map_err(|e| LscmdError::Database(e.to_string()))?;
+    conn.execute_batch(lscmd::database::schema::get_schema())
+        .map_err(|e| LscmdError::Database(e.to_string()))?;
+    Ok(SqliteCommandRepository::new(conn))
+}
+
+fn command(name: &str, cmd_type: CommandType, path: &str, start_line: usize, code: &str) -> Command {
+    Command {
+        name: name.to_string(),
+        cmd_type,
+        path: path.to_string(),
+        start_line,
+        end_line: start_line + code.lines().count().saturating_sub(1),
+        byte_offset: 0,
+        code: code.to_string(),
+        description: None,
+        file_mtime: 0,
+        created_at: None,
+        root: None,
+    }
+}
+
+/// Indexes three files under fixed paths, so that rendered titles are stable:
+/// `docker.sh`, `git.sh` and `broken.sh`, which failed to parse.
+fn setup_mock_repo() -> Result<SqliteCommandRepository> {
+    let mut glog = command("glog", CommandType::Function, "/aliases/git.sh", 1, "glog() {\n    git log --oneline \"$@\"\n}");
+    glog.description = Some("Compact git history".to_string());
+    let commands = vec![
+        command("dps", CommandType::Alias, "/aliases/docker.sh", 1, "docker ps"),
+        command("drun", CommandType::Alias, "/aliases/docker.sh", 2, "docker run --rm -it"),
+        glog,
+        command("gs", CommandType::Alias, "/aliases/git.sh", 4, "git status"),
+        command("gp", CommandType::Alias, "/aliases/git.sh", 5, "git push"),
+    ];
+
+    let parse_error = LscmdError::Parse {
+        file: "/aliases/broken.sh".to_string(),
+        line: 2,
+        message: "unclosed '('".to_string(),
+    };
+    let files = vec![
+        FileRecord::from_parse("/aliases/broken.sh", 0, 12, String::new(), 0, &[parse_error]),
+        FileRecord::from_parse("/aliases/docker.sh", 0, 40, String::new(), 2, &[]),
+        FileRecord::from_parse("/aliases/git.sh", 0, 80, String::new(), 3, &[]),
+    ];
+
+    let repo = setup_in_memory_db()?;
+    repo.replace_all(&files, &commands)?;
+    Ok(repo)
+}
+
+fn key(code: KeyCode) -> Event {
+    Event::Key(KeyEvent::new(code, KeyModifiers::NONE))
+}
+
+fn text(text: &str) -> Vec<Event> {
+    text.chars().map(|c| key(KeyCode::Char(c))).collect()
+}
+
+/// Plays `events` on a fresh headless terminal and returns it.
+fn play(app: &mut App, events: Vec<Event>) -> Result<Terminal<TestBackend>> {
+    let mut terminal = Terminal::new(TestBackend::new(WIDTH, HEIGHT))?;
+    tui::run_script(&mut terminal, app, events)?;
+    Ok(terminal)
+}
+
+/// The screen as text, one line per row, without trailing spaces.
+///
+/// The cells hidden behind a wide character are skipped: the terminal only
+/// sends them when they change, so the backend may still hold stale symbols
+/// there.
+fn screen(buffer: &Buffer) -> String {
+    let mut screen = String::new();
+    for row in buffer.content.chunks(buffer.area.width as usize) {
+        let mut line = String::new();
+        let mut hidden = 0;
+        for cell in row {
+            if hidden > 0 {
+                hidden -= 1;
+                continue;
+            }
+            line.push_str(&cell.symbol);
+            hidden = cell.symbol.width().saturating_sub(1);
+        }
+        screen.push_str(line.trim_end());
+        screen.push('\n');
+    }
+    screen
+}
+
+fn assert_snapshot(name: &str, terminal: &Terminal<TestBackend>) {
+    let actual = screen(terminal.backend().buffer());
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/snapshots").join(format!("{}.txt", name));
+    if env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, &actual).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(&path)
+        .unwrap_or_else(|_| panic!("missing snapshot {}; run with UPDATE_SNAPSHOTS=1 to create it", path.display()));
+    assert!(
+        actual == expected,
+        "snapshot {} differs; rerun with UPDATE_SNAPSHOTS=1 to accept\n--- expected\n{}--- actual\n{}",
+        name,
+        expected,
+        actual
+    );
+}
+
+/// The background behind the first place `text` appears on screen.
+fn background_of(terminal: &Terminal<TestBackend>, text: &str) -> Color {
+    let buffer = terminal.backend().buffer();
+    let symbols: Vec<String> = text.chars().map(String::from).collect();
+    buffer
+        .content
+        .windows(symbols.len())
+        .find(|cells| cells.iter().zip(&symbols).all(|(cell, symbol)| cell.symbol == *symbol))
+        .map(|cells| cells[0].bg)
+        .unwrap_or_else(|| panic!("{:?} is not on screen:\n{}", text, screen(buffer)))
+}
+
+// --- Default State ---
+
+#[test]
+fn test_initial_screen() -> Result<()> {
+    let repo = setup_mock_repo()?;
+    let mut app = App::new(&repo, SearchEngine::new(false), None)?;
+    let terminal = play(&mut app, vec![])?;
+    assert_snapshot("initial", &terminal);
+    Ok(())
+}
+
+#[test]
+fn test_no_files_disables_search() -> Result<()> {
+    let repo = setup_in_memory_db()?;
+    let mut app = App::new(&repo, SearchEngine::new(false), None)?;
+    let terminal = play(&mut app, vec![key(KeyCode::Char('/'))])?;
+    assert_eq!(app.state(), &AppState::Default);
+    assert_snapshot("no_files", &terminal);
+    Ok(())
+}
+
+#[test]
+fn test_quit_stops_the_script() -> Result<()> {
+    let repo = setup_mock_repo()?;
+    let mut app = App::new(&repo, SearchEngine::new(false), None)?;
+    // Keys after `q` are never handled.
+    play(&mut app, vec![key(KeyCode::Char('q')), key(KeyCode::Enter)])?;
+    assert!(app.should_quit());
+    assert!(app.selected_file().is_none());
+    Ok(())
+}
+
+// --- File Selected State ---
+
+#[test]
+fn test_enter_selects_file() -> Result<()> {
+    let repo = setup_mock_repo()?;
+    let mut app = App::new(&repo, SearchEngine::new(false), None)?;
+    let terminal = play(&mut app, vec![key(KeyCode::End), key(KeyCode::Enter)])?;
+    assert_snapshot("git_selected", &terminal);
+    assert_eq!(background_of(&terminal, "git.sh    (3)"), Color::Rgb(163, 190, 140));
+    Ok(())
+}
+
+#[test]
+fn test_space_selects_file_like_enter() -> Result<()> {
+    let repo = setup_mock_repo()?;
+    let mut app = App::new(&repo, SearchEngine::new(false), None)?;
+    let terminal = play(&mut app, vec![key(KeyCode::Down), key(KeyCode::Down), key(KeyCode::Char(' '))])?;
+    assert_snapshot("git_selected", &terminal);
+    Ok(())
+}
+
+#[test]
+fn test_arrows_move_through_commands() -> Result<()> {
+    let repo = setup_mock_repo()?;
+    let mut app = App::new(&repo, SearchEngine::new(false), None)?;
+    let terminal = play(&mut app, vec![key(KeyCode::End), key(KeyCode::Enter), key(KeyCode::Down), key(KeyCode::Down)])?;
+    assert_snapshot("git_function_under_cursor", &terminal);
+
+    // Up goes back through the groups, skipping their headers.
+    let terminal = play(&mut app, vec![key(KeyCode::Up), key(KeyCode::Up)])?;
+    assert_snapshot("git_selected", &terminal);
+    Ok(())
+}
+
+#[test]
+fn test_right_arrow_keeps_commands() -> Result<()> {
+    let repo = setup_mock_repo()?;
+    let mut app = App::new(&repo, SearchEngine::new(false), None)?;
+    let terminal = play(&mut app, vec![key(KeyCode::End), key(KeyCode::Enter), key(KeyCode::Right)])?;
+    assert_snapshot("git_kept_files_focused", &terminal);
+    Ok(())
+}
+
+#[test]
+fn test_esc_clears_commands() -> Result<()> {
+    let repo = setup_mock_repo()?;
+    let mut app = App::new(&repo, SearchEngine::new(false), None)?;
+    let terminal = play(&mut app, vec![key(KeyCode::End), key(KeyCode::Enter), key(KeyCode::Esc)])?;
+    assert_snapshot("git_cleared", &terminal);
+    assert_eq!(background_of(&terminal, "git.sh    (3)"), Color::Rgb(94, 129, 172));
+    Ok(())
+}
+
+#[test]
+fn test_toggling_selected_file_off() -> Result<()> {
+    let repo = setup_mock_repo()?;
+    let mut app = App::new(&repo, SearchEngine::new(false), None)?;
+    let terminal = play(&mut app, vec![key(KeyCode::End), key(KeyCode::Enter), key(KeyCode::Right), key(KeyCode::Enter)])?;
+    assert_snapshot("git_cleared", &terminal);
+    Ok(())
+}
+
+#[test]
+fn test_failed_file_shows_parse_errors() -> Result<()> {
+    let repo = setup_mock_repo()?;
+    let mut app = App::new(&repo, SearchEngine::new(false), None)?;
+    let terminal = play(&mut app, vec![key(KeyCode::Enter)])?;
+    assert_snapshot("broken_selected", &terminal);
+    Ok(())
+}
+
+// --- Search Mode ---
+
+#[test]
+fn test_slash_opens_search_input() -> Result<()> {
+    let repo = setup_mock_repo()?;
+    let mut app = App::new(&repo, SearchEngine::new(false), None)?;
+    let terminal = play(&mut app, vec![key(KeyCode::Char('/'))])?;
+    assert_snapshot("search_empty", &terminal);
+    Ok(())
+}
+
+#[test]
+fn test_typing_searches_live() -> Result<()> {
+    let repo = setup_mock_repo()?;
+    let mut app = App::new(&repo, SearchEngine::new(false), None)?;
+    let mut events = vec![key(KeyCode::Char('/'))];
+    events.extend(text("git"));
+    let terminal = play(&mut app, events)?;
+    assert_snapshot("search_git", &terminal);
+    Ok(())
+}
+
+#[test]
+fn test_arrows_browse_search_results() -> Result<()> {
+    let repo = setup_mock_repo()?;
+    let mut app = App::new(&repo, SearchEngine::new(false), None)?;
+    let mut events = vec![key(KeyCode::Char('/'))];
+    events.extend(text("git"));
+    events.extend([key(KeyCode::Down), key(KeyCode::Down)]);
+    let terminal = play(&mut app, events)?;
+    assert_snapshot("search_git_second_result", &terminal);
+    Ok(())
+}
+
+#[test]
+fn test_search_without_results() -> Result<()> {
+    let repo = setup_mock_repo()?;
+    let mut app = App::new(&repo, SearchEngine::new(false), None)?;
+    let mut events = vec![key(KeyCode::Char('/'))];
+    events.extend(text("kubectl"));
+    let terminal = play(&mut app, events)?;
+    assert_snapshot("search_no_results", &terminal);
+    Ok(())
+}
+
+#[test]
+fn test_invalid_query_keeps_results() -> Result<()> {
+    let repo = setup_mock_repo()?;
+    let mut app = App::new(&repo, SearchEngine::new(false), None)?;
+    let mut events = vec![key(KeyCode::Char('/'))];
+    events.extend(text("git)"));
+    let terminal = play(&mut app, events)?;
+    assert_snapshot("search_invalid", &terminal);
+    Ok(())
+}
+
+#[test]
+fn test_clearing_query_clears_results() -> Result<()> {
+    let repo = setup_mock_repo()?;
+    let mut app = App::new(&repo, SearchEngine::new(false), None)?;
+    let mut events = vec![key(KeyCode::Char('/'))];
+    events.extend(text("git"));
+    events.extend([key(KeyCode::Backspace), key(KeyCode::Backspace), key(KeyCode::Backspace)]);
+    let terminal = play(&mut app, events)?;
+    assert_snapshot("search_empty", &terminal);
+    Ok(())
+}
+
+#[test]
+fn test_esc_returns_to_state_before_search() -> Result<()> {
+    let repo = setup_mock_repo()?;
+    let mut app = App::new(&repo, SearchEngine::new(false), None)?;
+    let mut events = vec![key(KeyCode::End), key(KeyCode::Enter), key(KeyCode::Char('/'))];
+    events.extend(text("docker"));
+    events.push(key(KeyCode::Esc));
+    let terminal = play(&mut app, events)?;
+    assert_snapshot("git_selected", &terminal);
+    Ok(())
+}
+
+#[test]
+fn test_slash_toggles_search_off() -> Result<()> {
+    let repo = setup_mock_repo()?;
+    let mut app = App::new(&repo, SearchEngine::new(false), None)?;
+    let mut events = vec![key(KeyCode::End), key(KeyCode::Enter), key(KeyCode::Char('/'))];
+    events.extend(text("docker/"));
+    let terminal = play(&mut app, events)?;
+    assert_eq!(app.state(), &AppState::Default);
+    assert_snapshot("git_cleared", &terminal);
+    Ok(())
+}
+
+// --- Picker ---
+
+#[test]
+fn test_picker_shows_pick_shortcuts() -> Result<()> {
+    let repo = setup_mock_repo()?;
+    let mut app = App::new(&repo, SearchEngine::new(false), None)?.picking(true);
+    app.start_search("git")?;
+    let terminal = play(&mut app, vec![])?;
+    assert_snapshot("picker_search_git", &terminal);
+
+    // Enter picks the top result without leaving the input.
+    play(&mut app, vec![key(KeyCode::Enter)])?;
+    assert_eq!(app.picked().map(|result| result.name.as_str()), Some("glog"));
+    Ok(())
+}
@@ -0,0 +1,207 @@
+//! Integration tests for Phase 7: Display formatting.
+
+use lscmd::database::{Command, CommandType};
+use lscmd::display::{shell_tokens, DisplayFormatter, TokenKind};
+use lscmd::error::LscmdError;
+use lscmd::output::{OutputFormat, Records, JSON_SCHEMA_VERSION};
+use lscmd::search::{SearchEngine, SearchMode};
+
+fn command(name: &str, path: &str, start_line: usize, code: &str) -> Command {
+    Command {
+        name: name.to_string(),
+        cmd_type: CommandType::Function,
+        path: path.to_string(),
+        start_line,
+        end_line: start_line + code.lines().count() + 1,
+        byte_offset: 0,
+        code: code.to_string(),
+        description: None,
+        file_mtime: 0,
+        created_at: None,
+        root: None,
+    }
+}
+
+#[test]
+fn test_plain_show_output() {
+    let mut active = command("deploy", "/aliases/b.sh", 3, "    ./deploy.sh \"$1\"");
+    active.description = Some("Deploy the app\nneeds VPN".to_string());
+    let shadowed = command("deploy", "/aliases/a.sh", 1, "    echo old");
+
+    let output = DisplayFormatter::new(false).format_definitions(&[shadowed, active], 1);
+    assert_eq!(
+        output,
+        concat!(
+            "deploy (function) [active]\n",
+            "  file:  /aliases/b.sh:3-5\n",
+            "  about: Deploy the app\n",
+            "         needs VPN\n",
+            "\n",
+            "        ./deploy.sh \"$1\"\n",
+            "\n",
+            "deploy (function) [shadowed]\n",
+            "  file:  /aliases/a.sh:1-3\n",
+            "\n",
+            "        echo old\n",
+        )
+    );
+    assert!(!output.contains('\x1b'));
+}
+
+#[test]
+fn test_highlighting() {
+    colored::control::set_override(true);
+    let formatter = DisplayFormatter::new(true);
+    let highlighted = formatter.highlight("if true; then echo \"$HOME\"; fi # done");
+    let commands = [command("git_checkout", "/aliases/g.sh", 1, "git checkout")];
+    let results = SearchEngine::with_mode(SearchMode::Fuzzy).search("gco", &commands).unwrap();
+    let results: Vec<_> = results.iter().map(|r| r.to_owned()).collect();
+    let listed = formatter.format_search_results(&results);
+    colored::control::unset_override();
+
+    let underlined = "\x1b[1;4;33m";
+    assert_eq!(listed.matches(underlined).count(), 3, "{:?}", listed);
+
+    assert!(highlighted.contains('\x1b'));
+    // Stripping the escape codes gives back the original code.
+    let stripped = regex::Regex::new("\x1b\\[[0-9;]*m").unwrap().replace_all(&highlighted, "");
+    assert_eq!(stripped, "if true; then echo \"$HOME\"; fi # done");
+}
+
+#[test]
+fn test_shell_tokens() {
+    let code = "for f in *.sh; do echo \"${f}\" 'a\nb' $1 # c\ndone";
+    let tokens: Vec<_> = shell_tokens(code)
+        .into_iter()
+        .filter(|(kind, _)| *kind != TokenKind::Plain)
+        .map(|(kind, range)| (kind, &code[range]))
+        .collect();
+    assert_eq!(
+        tokens,
+        vec![
+            (TokenKind::Keyword, "for"),
+            (TokenKind::Keyword, "in"),
+            (TokenKind::Operator, ";"),
+            (TokenKind::Keyword, "do"),
+            (TokenKind::String, "\"${f}\""),
+            (TokenKind::String, "'a"),
+            (TokenKind::String, "b'"),
+            (TokenKind::Variable, "$1"),
+            (TokenKind::Comment, "# c"),
+            (TokenKind::Keyword, "done"),
+        ]
+    );
+}
+
+#[test]
+fn test_command_not_found_message() {
+    let error = LscmdError::CommandNotFound {
+        name: "gcp".to_string(),
+        suggestions: vec!["gco".to_string(), "gc".to_string()],
+    };
+    assert_eq!(error.to_string(), "Command not found: 'gcp'; did you mean one of: gco, gc?");
+
+    let error = LscmdError::CommandNotFound {
+        name: "zzz".to_string(),
+        suggestions: Vec::new(),
+    };
+    assert_eq!(error.to_string(), "Command not found: 'zzz'");
+}
+
+#[test]
+fn test_json_output_is_versioned() {
+    let mut active = command("deploy", "/aliases/b.sh", 3, "    ./deploy.sh");
+    active.description = Some("Deploy the app".to_string());
+    let shadowed = command("deploy", "/aliases/a.sh", 1, "    echo old");
+    let definitions = [shadowed, active];
+
+    let json = Records::definitions(&definitions, 1).format(OutputFormat::Json).unwrap();
+    let document: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(document["schema_version"], JSON_SCHEMA_VERSION);
+    let commands = document["commands"].as_array().unwrap();
+    assert_eq!(commands.len(), 2);
+    assert_eq!(commands[0]["path"], "/aliases/b.sh");
+    assert_eq!(commands[0]["cmd_type"], "function");
+    assert_eq!(commands[0]["description"], "Deploy the app");
+    assert_eq!(commands[0]["active"], true);
+    assert_eq!(commands[1]["active"], false);
+    assert!(commands[0].get("score").is_none());
+
+    // Each JSON Lines record carries the version and deserializes as a `Command`.
+    let jsonl = Records::listed(&definitions).format(OutputFormat::Jsonl).unwrap();
+    let lines: Vec<_> = jsonl.lines().collect();
+    assert_eq!(lines.len(), 2);
+    let record: serde_json::Value = serde_json::from_str(lines[1]).unwrap();
+    assert_eq!(record["schema_version"], JSON_SCHEMA_VERSION);
+    assert!(record.get("active").is_none());
+    let parsed: Command = serde_json::from_str(lines[1]).unwrap();
+    assert_eq!(parsed, definitions[1]);
+}
+
+#[test]
+fn test_delimited_output() {
+    let mut cmd = command("say", "/aliases/a,b.sh", 2, "    echo \"hi\"\tthere");
+    cmd.description = Some("Greets\\you".to_string());
+    let results = SearchEngine::new(false).search("say", std::slice::from_ref(&cmd)).unwrap();
+    let results: Vec<_> = results.iter().map(|r| r.to_owned()).collect();
+    let records = Records::scored(&results);
+
+    assert_eq!(
+        records.format(OutputFormat::Tsv).unwrap(),
+        concat!(
+            "name\ttype\tpath\tstart_line\tend_line\tdescription\tcode\tscore\n",
+            "say\tfunction\t/aliases/a,b.sh\t2\t4\tGreets\\\\you\t    echo \"hi\"\\tthere\t100\n",
+        )
+    );
+    assert_eq!(
+        records.format(OutputFormat::Csv).unwrap(),
+        concat!(
+            "name,type,path,start_line,end_line,description,code,score\n",
+            "say,function,\"/aliases/a,b.sh\",2,4,Greets\\you,\"    echo \"\"hi\"\"\tthere\",100\n",
+        )
+    );
+    assert_eq!(records.format(OutputFormat::Plain).unwrap(), "say\n");
+
+    // Without results the header still has every column.
+    let empty = Records::scored(&[]);
+    assert_eq!(empty.format(OutputFormat::Csv).unwrap(), "name,type,path,start_line,end_line,description,code,score\n");
+    assert_eq!(empty.format(OutputFormat::Plain).unwrap(), "");
+    let document: serde_json::Value = serde_json::from_str(&empty.format(OutputFormat::Json).unwrap()).unwrap();
+    assert_eq!(document["commands"], serde_json::json!([]));
+}
+
+#[test]
+fn test_column_listing() {
+    let mut commands: Vec<_> = ["gs", "ga", "Gp", "gco", "gb", "gd", "gr"]
+        .iter()
+        .enumerate()
+        .map(|(line, name)| command(name, "/aliases/git-aliases.sh", line + 1, "git"))
+        .collect();
+    commands.iter_mut().for_each(|c| c.cmd_type = CommandType::Alias);
+    commands[3].cmd_type = CommandType::Function;
+    commands.push(command("dps_with_a_long_name", "/aliases/docker.sh", 1, "docker ps"));
+    commands.push(command("dc", "/aliases/docker.sh", 2, "docker compose"));
+
+    // Files in path order, names sorted and filled top to bottom; 40 columns
+    // of terminal leave 3 columns of 11.
+    let output = DisplayFormatter::new(false).format_columns(&commands, 3, 40);
+    assert_eq!(
+        output,
+        concat!(
+            "## DOCKER\n",
+            "dc         dps_with_a_long_name\n",
+            "\n",
+            "## GIT-ALIASES\n",
+            "ga         gd         gs\n",
+            "gb         Gp\n",
+            "gco        gr\n",
+        )
+    );
+
+    colored::control::set_override(true);
+    let colored = DisplayFormatter::new(true).format_columns(&commands[..4], 5, 80);
+    colored::control::unset_override();
+    assert!(colored.contains("\x1b[1;33m## GIT-ALIASES\x1b[0m"), "{:?}", colored);
+    assert!(colored.contains("\x1b[94mgco\x1b[0m"), "{:?}", colored);
+    assert!(colored.contains("\x1b[92mgs\x1b[0m"), "{:?}", colored);
+}